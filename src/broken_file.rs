@@ -0,0 +1,158 @@
+use gpui::{prelude::FluentBuilder, *};
+use ui::{v_flex, h_flex, ActiveTheme, StyledExt, Sizable, button::{Button, ButtonVariants}, input::{InputState, TextInput}};
+use crate::schema;
+
+#[derive(Clone, Debug)]
+pub enum BrokenFileEvent {
+    /// The raw JSON now loads; carries the text to write back
+    Repaired(String),
+    ReloadRequested,
+}
+
+/// Shown instead of the workspace when `trait.json` can't be loaded. The file is never
+/// written from here except through "Save fixed file", once its raw JSON loads.
+pub struct BrokenFileView {
+    /// Why the file as read from disk failed to load
+    load_error: String,
+    /// `None` if the file couldn't be read at all, which leaves nothing to fix in place
+    raw_input: Option<Entity<InputState>>,
+    /// Problem with the text as currently edited
+    problem: Option<String>,
+    location: Option<(usize, usize)>,
+    /// The file's content as read
+    original: Option<String>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl BrokenFileView {
+    pub fn new(content: Option<String>, load_error: String, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut subscriptions = Vec::new();
+        let raw_input = content.clone().map(|content| {
+            let input = cx.new(|cx| {
+                let mut input = InputState::new(window, cx)
+                    .code_editor("json")
+                    .line_number(true);
+                input.set_value(content, window, cx);
+                input
+            });
+            subscriptions.push(cx.subscribe_in(&input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+                if let ui::input::InputEvent::Change = event {
+                    this.revalidate(cx);
+                }
+            }));
+            input
+        });
+
+        let mut view = Self {
+            load_error,
+            raw_input,
+            problem: None,
+            location: None,
+            original: content,
+            _subscriptions: subscriptions,
+        };
+        view.revalidate(cx);
+        view
+    }
+
+    /// Whether the raw JSON was changed since the file was read
+    pub fn is_edited(&self, cx: &App) -> bool {
+        self.text(cx) != self.original
+    }
+
+    fn text(&self, cx: &App) -> Option<String> {
+        self.raw_input.as_ref().map(|input| input.read(cx).text().to_string())
+    }
+
+    fn revalidate(&mut self, cx: &mut Context<Self>) {
+        let Some(text) = self.text(cx) else {
+            self.problem = Some(self.load_error.clone());
+            self.location = None;
+            cx.notify();
+            return;
+        };
+        self.problem = schema::load_asset(&text).err().map(|e| e.to_string());
+        self.location = schema::syntax_error_location(&text);
+        cx.notify();
+    }
+
+    fn save_fixed(&mut self, cx: &mut Context<Self>) {
+        if self.problem.is_none() {
+            if let Some(text) = self.text(cx) {
+                cx.emit(BrokenFileEvent::Repaired(text));
+            }
+        }
+    }
+}
+
+impl EventEmitter<BrokenFileEvent> for BrokenFileView {}
+
+impl Render for BrokenFileView {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let status = match (&self.problem, self.location) {
+            (Some(problem), Some((line, column))) => format!("Line {}, column {}: {}", line, column, problem),
+            (Some(problem), None) => problem.clone(),
+            (None, _) => "The JSON loads now. Save it to open the trait.".to_string(),
+        };
+        let status_color = if self.problem.is_some() { cx.theme().danger } else { cx.theme().muted_foreground };
+
+        v_flex()
+            .size_full()
+            .p_3()
+            .gap_2()
+            .child(
+                div()
+                    .text_sm()
+                    .font_semibold()
+                    .text_color(cx.theme().foreground)
+                    .child("This trait couldn't be opened")
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(format!(
+                        "{} The file is protected and won't be saved over until it loads.",
+                        self.load_error
+                    ))
+            )
+            .when_some(self.raw_input.clone(), |this, input| {
+                this.child(
+                    TextInput::new(&input)
+                        .w_full()
+                        .flex_1()
+                )
+            })
+            .child(
+                h_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .text_color(status_color)
+                            .child(status)
+                    )
+                    .child(
+                        Button::new("broken-file-reload")
+                            .ghost()
+                            .label("Reload from disk")
+                            .with_size(ui::Size::Small)
+                            .on_click(cx.listener(|_this, _, _window, cx| {
+                                cx.emit(BrokenFileEvent::ReloadRequested);
+                            }))
+                    )
+                    .when(self.raw_input.is_some() && self.problem.is_none(), |this| {
+                        this.child(
+                            Button::new("broken-file-save")
+                                .primary()
+                                .label("Save fixed file")
+                                .with_size(ui::Size::Small)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.save_fixed(cx);
+                                }))
+                        )
+                    })
+            )
+    }
+}
@@ -0,0 +1,851 @@
+//! Rust source generation for trait assets
+
+use ui_types_common::{TraitAsset, TraitMethod, TypeRef};
+use crate::model::{attribute_body, AssociatedConst, AssociatedType, GenericParam, Generics, MethodMeta, Receiver, TraitMeta};
+use crate::object_safety::requires_sized;
+use crate::type_expr::TypeExpr;
+
+/// Indentation unit used by the generated code
+const INDENT: &str = "    ";
+
+/// Type names a type built only from can be assumed to implement `Clone`, with the paths
+/// they are reached by
+const CLONE_TYPES: &[&str] = &[
+    "bool", "char", "u8", "u16", "u32", "u64", "u128", "usize", "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64",
+    "String", "Vec", "VecDeque", "Option", "Result", "HashMap", "HashSet", "BTreeMap", "BTreeSet", "Box", "Rc", "Arc",
+    "PathBuf", "Duration", "std", "core", "alloc", "collections", "string", "vec", "option", "result", "boxed", "rc",
+    "sync", "path", "time",
+];
+
+/// Supertraits a mock gets without an impl of its own: derived, or auto traits
+const MOCK_FREE_SUPERTRAITS: &[&str] = &["Default", "Send", "Sync", "Unpin", "Sized"];
+
+/// Layout of the generated code
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodegenOptions {
+    /// Column limit, rustfmt's `max_width`. Signatures and supertrait lists that don't fit
+    /// are broken over several lines.
+    pub max_width: usize,
+}
+
+impl Default for CodegenOptions {
+    fn default() -> Self {
+        Self { max_width: 100 }
+    }
+}
+
+/// Generates the trait declaration for `asset`
+pub fn generate_trait(asset: &TraitAsset, options: &CodegenOptions) -> String {
+    let mut code = String::new();
+
+    // Add documentation if present
+    if let Some(desc) = &asset.description {
+        code.push_str(&doc_comment(desc, 0));
+    }
+
+    let meta = TraitMeta::from_asset(asset);
+
+    for attribute in meta.attributes.iter().map(|attribute| attribute_body(attribute)) {
+        if !attribute.is_empty() {
+            code.push_str(&format!("#[{}]\n", attribute));
+        }
+    }
+
+    // Trait declaration
+    let mut header = meta.visibility.to_rust().to_string();
+    if meta.is_unsafe {
+        header.push_str("unsafe ");
+    }
+    header.push_str(&format!("trait {}{}", asset.name, generic_params(&meta.generics)));
+    let supertraits: Vec<&str> = meta
+        .supertraits
+        .iter()
+        .map(|bound| bound.trim())
+        .filter(|bound| !bound.is_empty())
+        .collect();
+    let one_line = format!("{}: {} {{", header, supertraits.join(" + "));
+    let bounds_wrapped = !supertraits.is_empty() && width(&one_line) > options.max_width;
+    code.push_str(&header);
+    if bounds_wrapped {
+        // Like rustfmt: the bounds move to their own line, or one per line if that
+        // isn't enough, and the brace gets a line of its own
+        let joined = format!("{}{}", INDENT, supertraits.join(" + "));
+        code.push_str(":\n");
+        if width(&joined) <= options.max_width {
+            code.push_str(&joined);
+        } else {
+            code.push_str(INDENT);
+            code.push_str(&supertraits.join(&format!("\n{}+ ", INDENT)));
+        }
+    } else if !supertraits.is_empty() {
+        code.push_str(": ");
+        code.push_str(&supertraits.join(" + "));
+    }
+    match where_clause(&meta.generics, 0) {
+        Some(clause) => {
+            code.push('\n');
+            code.push_str(&clause);
+            code.push_str(",\n{\n");
+        }
+        None if bounds_wrapped => code.push_str("\n{\n"),
+        None => code.push_str(" {\n"),
+    }
+
+    // Associated items come before the methods
+    for item in &meta.associated_types {
+        if let Some(line) = associated_type(item) {
+            code.push_str(INDENT);
+            code.push_str(&line);
+            code.push('\n');
+        }
+    }
+    for item in &meta.associated_consts {
+        if let Some(line) = associated_const(item) {
+            code.push_str(INDENT);
+            code.push_str(&line);
+            code.push('\n');
+        }
+    }
+    for item in &meta.verbatim_items {
+        code.push_str(&indent_block(item, 1));
+    }
+    let has_items =
+        !meta.associated_types.is_empty() || !meta.associated_consts.is_empty() || !meta.verbatim_items.is_empty();
+
+    // Methods, separated from the items and each other by a blank line
+    for (index, method) in asset.methods.iter().enumerate() {
+        if index > 0 || has_items {
+            code.push('\n');
+        }
+        if let Some(doc) = &method.doc {
+            code.push_str(&doc_comment(doc, 1));
+        }
+
+        let method_meta = meta.method(index);
+        for attribute in method_meta.attributes.iter().map(|attribute| attribute_body(attribute)) {
+            if !attribute.is_empty() {
+                code.push_str(&format!("{}#[{}]\n", INDENT, attribute));
+            }
+        }
+
+        push_method(&mut code, method, &method_meta, method.default_body.as_deref(), options);
+    }
+
+    close_block(&mut code);
+    code
+}
+
+/// Closes a block opened with `{\n`, as `{}` when nothing was written into it on the same
+/// line as its header
+fn close_block(code: &mut String) {
+    if code.ends_with(" {\n") {
+        code.pop();
+        code.push_str("}\n");
+    } else {
+        code.push_str("}\n");
+    }
+}
+
+/// What the code preview generates from a trait asset
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CodegenMode {
+    /// The trait declaration itself
+    #[default]
+    Trait,
+    /// An `impl` for a named type with a `todo!()` body for every required item
+    ImplSkeleton,
+    /// A struct implementing the trait that records calls and returns preset values
+    Mock,
+    /// Blanket impls for `Box<T>` and `&T` that forward to `T`
+    Forwarders,
+}
+
+impl CodegenMode {
+    pub const ALL: [CodegenMode; 4] = [
+        CodegenMode::Trait,
+        CodegenMode::ImplSkeleton,
+        CodegenMode::Mock,
+        CodegenMode::Forwarders,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            CodegenMode::Trait => "Trait",
+            CodegenMode::ImplSkeleton => "Impl skeleton",
+            CodegenMode::Mock => "Mock",
+            CodegenMode::Forwarders => "Box / & forwarders",
+        }
+    }
+
+    /// Whether the mode generates code for a type the user names
+    pub fn takes_type_name(&self) -> bool {
+        matches!(self, CodegenMode::ImplSkeleton)
+    }
+}
+
+/// Type an impl skeleton is generated for when no name is given
+pub const DEFAULT_IMPL_TYPE: &str = "MyType";
+
+/// Generates the code of `mode` for `asset`. `type_name` names the implementing type of
+/// an impl skeleton and is ignored by the other modes.
+pub fn generate(asset: &TraitAsset, mode: CodegenMode, type_name: &str, options: &CodegenOptions) -> String {
+    match mode {
+        CodegenMode::Trait => generate_trait(asset, options),
+        CodegenMode::ImplSkeleton => generate_impl_skeleton(asset, type_name, options),
+        CodegenMode::Mock => generate_mock(asset, options),
+        CodegenMode::Forwarders => generate_forwarders(asset, options),
+    }
+}
+
+/// Generates an `impl` of the trait for `type_name` with every item that has no default:
+/// associated types set to `()`, and consts and methods left as `todo!()`
+pub fn generate_impl_skeleton(asset: &TraitAsset, type_name: &str, options: &CodegenOptions) -> String {
+    let meta = TraitMeta::from_asset(asset);
+    let type_name = match type_name.trim() {
+        "" => DEFAULT_IMPL_TYPE,
+        name => name,
+    };
+
+    let associated = required_associated_items(&meta);
+    let mut methods = Vec::new();
+    for (index, method) in asset.methods.iter().enumerate() {
+        if method.default_body.is_none() {
+            let mut item = String::new();
+            push_method(&mut item, method, &meta.method(index), Some("todo!()"), options);
+            methods.push(item);
+        }
+    }
+
+    let header = format!(
+        "{}impl{} {} for {}",
+        if meta.is_unsafe { "unsafe " } else { "" },
+        generic_params(&without_defaults(&meta.generics)),
+        trait_ref(asset, &meta.generics),
+        type_name
+    );
+    impl_block(header, &meta.generics, associated, methods)
+}
+
+/// Generates a `Mock{Trait}` struct and its impl of the trait. Every call is recorded in
+/// `calls`. A method returning an owned type returns its `{method}_returns` field: a clone
+/// of it if the type is known to be `Clone`, and otherwise the value taken out of it, so
+/// only once. Calls panic if it isn't set. Supertraits other than `Debug` and those the
+/// struct already has are left to the user, with a comment naming them.
+pub fn generate_mock(asset: &TraitAsset, options: &CodegenOptions) -> String {
+    let meta = TraitMeta::from_asset(asset);
+    let mock = format!("Mock{}", asset.name);
+
+    // Names a stored return value can't mention: they only exist inside the impl
+    let mut scoped: Vec<String> = generic_type_names(&meta.generics);
+    scoped.extend(meta.associated_types.iter().map(|item| item.name.trim().to_string()));
+    // Stored values keep the mock from being `Send` or `Sync` unless they are too
+    let thread_safe = meta.supertraits.iter().any(|bound| matches!(bound.trim().rsplit("::").next(), Some("Send" | "Sync")));
+    if thread_safe {
+        scoped.extend(["dyn", "Rc"].map(String::from));
+    }
+
+    let mut fields = vec![format!(
+        "{}/// Names of the methods called, oldest first\n{}pub calls: std::sync::Mutex<Vec<&'static str>>,\n",
+        INDENT, INDENT
+    )];
+    let mut methods = Vec::new();
+
+    for (index, method) in asset.methods.iter().enumerate() {
+        let method_meta = meta.method(index);
+        let return_type = type_ref_to_string(&method.signature.return_type);
+        let mut method_scoped = scoped.clone();
+        method_scoped.extend(generic_type_names(&method_meta.generics));
+
+        let body = if method_meta.receiver == Receiver::None {
+            // Nothing to record the call in
+            if return_type == "Self" { "Self::default()".to_string() } else { "todo!()".to_string() }
+        } else {
+            let record = format!("self.calls.lock().unwrap().push(\"{}\");", method.name);
+            if return_type == "()" {
+                record
+            } else if is_storable(&return_type, &method_scoped) {
+                let field = format!("{}_returns", method.name);
+                let value = if is_clone(&return_type) {
+                    fields.push(format!("{}pub {}: Option<{}>,\n", INDENT, field, return_type));
+                    format!("self.{}.clone()", field)
+                } else {
+                    fields.push(format!(
+                        "{}/// Returned by the next call only\n{}pub {}: std::sync::Mutex<Option<{}>>,\n",
+                        INDENT, INDENT, field, return_type
+                    ));
+                    format!("self.{}.lock().unwrap().take()", field)
+                };
+                format!("{}\n{}.expect(\"{}::{} called without `{}` set\")", record, value, mock, method.name, field)
+            } else {
+                format!("{}\ntodo!()", record)
+            }
+        };
+        let mut item = String::new();
+        push_method(&mut item, method, &method_meta, Some(&body), options);
+        methods.push(item);
+    }
+
+    let mut code = format!(
+        "/// Records calls to [`{}`] and returns the values set on it\n#[derive(Default)]\npub struct {} {{\n",
+        asset.name, mock
+    );
+    code.push_str(&fields.concat());
+    code.push_str("}\n\n");
+
+    let header = format!(
+        "{}impl{} {} for {}",
+        if meta.is_unsafe { "unsafe " } else { "" },
+        generic_params(&without_defaults(&meta.generics)),
+        trait_ref(asset, &meta.generics),
+        mock
+    );
+    code.push_str(&impl_block(header, &meta.generics, required_associated_items(&meta), methods));
+
+    for bound in meta.supertraits.iter().map(|bound| bound.trim()) {
+        let name = bound.rsplit("::").next().unwrap_or(bound);
+        if name == "Debug" {
+            code.push_str(&format!(
+                "\nimpl std::fmt::Debug for {} {{\n{}fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {{\n{}{}f.debug_struct(\"{}\").finish_non_exhaustive()\n{}}}\n}}\n",
+                mock, INDENT, INDENT, INDENT, mock, INDENT
+            ));
+        } else if !bound.is_empty() && !bound.starts_with('\'') && !MOCK_FREE_SUPERTRAITS.contains(&name) {
+            code.push_str(&format!("\n// `{}` needs an impl of supertrait `{}`\n", mock, bound));
+        }
+    }
+    code
+}
+
+/// The associated items an impl must define, as lines: types set to `()` and consts left
+/// as `todo!()`
+fn required_associated_items(meta: &TraitMeta) -> Vec<String> {
+    let types = meta
+        .associated_types
+        .iter()
+        .filter(|item| !item.name.trim().is_empty() && !has_default(&item.default))
+        .map(|item| format!("type {} = ();", item.name.trim()));
+    let consts = meta
+        .associated_consts
+        .iter()
+        .filter(|item| !has_default(&item.default))
+        .filter_map(|item| associated_const(&AssociatedConst { default: Some("todo!()".to_string()), ..item.clone() }));
+    types.chain(consts).collect()
+}
+
+/// Generates blanket impls of the trait for `Box<T>` and `&T` that forward every item to
+/// `T`. Methods that can't be forwarded keep their default body, or panic if they have
+/// none; the `&T` impl is left out when a required method needs more than `&self`.
+pub fn generate_forwarders(asset: &TraitAsset, options: &CodegenOptions) -> String {
+    let meta = TraitMeta::from_asset(asset);
+    let taken = generic_type_names(&meta.generics);
+    let inner = ["T", "Inner", "Forwarded"]
+        .into_iter()
+        .find(|name| !taken.iter().any(|taken| taken == name))
+        .unwrap_or("Inner_");
+
+    let mut generics = without_defaults(&meta.generics);
+    generics.params.push(GenericParam::Type {
+        name: inner.to_string(),
+        bounds: vec![trait_ref(asset, &meta.generics), "?Sized".to_string()],
+        default: None,
+    });
+
+    let mut code = String::new();
+    for by_ref in [false, true] {
+        let self_ty = if by_ref { format!("&{}", inner) } else { format!("Box<{}>", inner) };
+
+        let mut associated: Vec<String> = meta
+            .associated_types
+            .iter()
+            .map(|item| item.name.trim())
+            .filter(|name| !name.is_empty())
+            .map(|name| format!("type {} = {}::{};", name, inner, name))
+            .collect();
+        associated.extend(meta.associated_consts.iter().filter_map(|item| {
+            associated_const(&AssociatedConst { default: Some(format!("{}::{}", inner, item.name.trim())), ..item.clone() })
+        }));
+
+        let mut methods = Vec::new();
+
+        let mut unforwardable = None;
+        for (index, method) in asset.methods.iter().enumerate() {
+            let method_meta = meta.method(index);
+            let body = match forwarding_call(method, &method_meta, inner, by_ref) {
+                Some(call) => call,
+                None if method.default_body.is_some() => continue,
+                // `&T` is `Sized` even where `T` isn't, so these only lack a body to call
+                None if by_ref && !requires_sized(&method_meta.generics) => {
+                    unforwardable = Some(method.name.clone());
+                    break;
+                }
+                None => format!("unimplemented!(\"`{}` can't be forwarded to `{}`\")", method.name, inner),
+            };
+            let mut item = String::new();
+            push_method(&mut item, method, &method_meta, Some(&body), options);
+            methods.push(item);
+        }
+
+        if !code.is_empty() {
+            code.push('\n');
+        }
+        if let Some(method) = unforwardable {
+            code.push_str(&format!("// No `&{}` forwarder: `{}` can't be called through a shared reference\n", inner, method));
+            continue;
+        }
+        let header = format!(
+            "{}impl{} {} for {}",
+            if meta.is_unsafe { "unsafe " } else { "" },
+            generic_params(&generics),
+            trait_ref(asset, &meta.generics),
+            self_ty
+        );
+        code.push_str(&impl_block(header, &meta.generics, associated, methods));
+    }
+    code
+}
+
+/// The call forwarding `method` to `inner`, or `None` if `Box<inner>` (or `&inner` with
+/// `by_ref`) can't pass its receiver on, a signature mentions `Self` itself, or the method
+/// requires `Self: Sized`, which the unsized `inner` doesn't meet
+fn forwarding_call(method: &TraitMethod, meta: &MethodMeta, inner: &str, by_ref: bool) -> Option<String> {
+    if requires_sized(&meta.generics) {
+        return None;
+    }
+    let receiver = match (meta.receiver, by_ref) {
+        (Receiver::None, _) => None,
+        // Deref coercion turns `&Box<T>` and `&&T` into `&T`
+        (Receiver::Ref, _) => Some("self"),
+        (Receiver::RefMut, false) => Some("self"),
+        (Receiver::Box, false) => Some("*self"),
+        _ => return None,
+    };
+    let mentions_self = method
+        .signature
+        .params
+        .iter()
+        .map(|param| type_ref_to_string(&param.type_ref))
+        .chain(std::iter::once(type_ref_to_string(&method.signature.return_type)))
+        .any(|ty| mentions_bare_self(&ty));
+    if mentions_self {
+        return None;
+    }
+
+    let args: Vec<&str> = receiver
+        .into_iter()
+        .chain(method.signature.params.iter().map(|param| param.name.as_str()))
+        .collect();
+    let mut call = format!("{}::{}({})", inner, method.name, args.join(", "));
+    if meta.is_async {
+        call.push_str(".await");
+    }
+    if meta.is_unsafe {
+        call = format!("unsafe {{ {} }}", call);
+    }
+    Some(call)
+}
+
+/// Renders an impl block: the associated items, each one line, then the methods separated
+/// by blank lines
+fn impl_block(header: String, generics: &Generics, associated: Vec<String>, methods: Vec<String>) -> String {
+    let mut code = header;
+    match where_clause(generics, 0) {
+        Some(clause) => {
+            code.push('\n');
+            code.push_str(&clause);
+            code.push_str(",\n{\n");
+        }
+        None => code.push_str(" {\n"),
+    }
+    for line in &associated {
+        code.push_str(INDENT);
+        code.push_str(line);
+        code.push('\n');
+    }
+    if !associated.is_empty() && !methods.is_empty() {
+        code.push('\n');
+    }
+    code.push_str(&methods.join("\n"));
+    close_block(&mut code);
+    code
+}
+
+/// The trait as named in an impl header, e.g. `Convert<'a, T>`
+fn trait_ref(asset: &TraitAsset, generics: &Generics) -> String {
+    let (lifetimes, others): (Vec<_>, Vec<_>) = generics
+        .params
+        .iter()
+        .filter(|param| !param.name().trim().is_empty())
+        .partition(|param| param.is_lifetime());
+    let args: Vec<String> = lifetimes
+        .into_iter()
+        .map(|param| lifetime(param.name()))
+        .chain(others.into_iter().map(|param| param.name().trim().to_string()))
+        .collect();
+    if args.is_empty() {
+        asset.name.clone()
+    } else {
+        format!("{}<{}>", asset.name, args.join(", "))
+    }
+}
+
+/// `generics` without the defaults of their parameters, which impls can't repeat
+fn without_defaults(generics: &Generics) -> Generics {
+    let mut generics = generics.clone();
+    for param in &mut generics.params {
+        match param {
+            GenericParam::Type { default, .. } | GenericParam::Const { default, .. } => *default = None,
+            GenericParam::Lifetime { .. } => {}
+        }
+    }
+    generics
+}
+
+fn has_default(default: &Option<String>) -> bool {
+    default.as_deref().is_some_and(|default| !default.trim().is_empty())
+}
+
+fn generic_type_names(generics: &Generics) -> Vec<String> {
+    generics
+        .params
+        .iter()
+        .filter(|param| !param.is_lifetime())
+        .map(|param| param.name().trim().to_string())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+/// Whether a mock struct can hold a value of `ty`: an owned type that mentions none of
+/// the `scoped` generic names, no `Self` and no lifetime but `'static`
+fn is_storable(ty: &str, scoped: &[String]) -> bool {
+    !ty.contains('&')
+        && type_words(ty).all(|word| match word {
+            "Self" | "impl" | "'_" => false,
+            word if word.starts_with('\'') => word == "'static",
+            word => !scoped.iter().any(|name| name == word),
+        })
+}
+
+/// Whether `ty` is built only from [`CLONE_TYPES`], tuples and arrays
+fn is_clone(ty: &str) -> bool {
+    type_words(ty).all(|word| CLONE_TYPES.contains(&word) || word.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Whether `ty` names `Self` other than as a path prefix such as `Self::Item`
+fn mentions_bare_self(ty: &str) -> bool {
+    ty.match_indices("Self").any(|(start, _)| {
+        let before = ty[..start].chars().next_back();
+        let after = &ty[start + "Self".len()..];
+        !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+            && !after.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+            && !after.trim_start().starts_with("::")
+    })
+}
+
+/// The identifiers and lifetimes in a type
+fn type_words(ty: &str) -> impl Iterator<Item = &str> {
+    ty.split(|c: char| !(c.is_alphanumeric() || c == '_' || c == '\'')).filter(|word| !word.is_empty())
+}
+
+/// Writes a method at one level of indentation: its signature, then `body` in braces, or
+/// a semicolon without one
+fn push_method(code: &mut String, method: &TraitMethod, meta: &MethodMeta, body: Option<&str>, options: &CodegenOptions) {
+    let method_where = where_clause(&meta.generics, 1);
+    // What follows the signature on its last line
+    let closing = match (&method_where, body) {
+        (Some(_), _) => "",
+        (None, Some(body)) if body.trim().is_empty() => " {}",
+        (None, Some(_)) => " {",
+        (None, None) => ";",
+    };
+    code.push_str(&method_signature(method, meta, closing, options));
+
+    // Where clause, closed by the body or the semicolon
+    if let Some(clause) = &method_where {
+        code.push('\n');
+        code.push_str(clause);
+    }
+
+    if let Some(body) = body {
+        if body.trim().is_empty() && method_where.is_none() {
+            code.push_str(" {}\n");
+            return;
+        }
+        if method_where.is_some() {
+            code.push_str(",\n");
+            code.push_str(INDENT);
+            code.push_str("{\n");
+        } else {
+            code.push_str(" {\n");
+        }
+        code.push_str(&indent_block(body, 2));
+        code.push_str(INDENT);
+        code.push_str("}\n");
+    } else {
+        code.push_str(";\n");
+    }
+}
+
+/// Renders `fn name<generics>(receiver, params) -> Return` with its qualifiers, indented
+/// one level. A signature that doesn't fit on one line along with `closing` gets one
+/// parameter per line, as rustfmt does.
+fn method_signature(method: &TraitMethod, meta: &MethodMeta, closing: &str, options: &CodegenOptions) -> String {
+    let mut start = String::from(INDENT);
+    start.push_str(&method_qualifiers(meta));
+    start.push_str("fn ");
+    start.push_str(&method.name);
+    start.push_str(&generic_params(&meta.generics));
+
+    // Receiver and parameters
+    let receiver = meta.receiver.to_rust().map(String::from);
+    let params: Vec<String> = receiver
+        .into_iter()
+        .chain(
+            method
+                .signature
+                .params
+                .iter()
+                .map(|param| format!("{}: {}", param.name, type_ref_to_string(&param.type_ref))),
+        )
+        .collect();
+
+    // Return type
+    let return_type = type_ref_to_string(&method.signature.return_type);
+    let end = if return_type == "()" { String::new() } else { format!(" -> {}", return_type) };
+
+    let one_line = format!("{}({}){}", start, params.join(", "), end);
+    if params.is_empty() || width(&one_line) + width(closing) <= options.max_width {
+        return one_line;
+    }
+    let mut signature = start;
+    signature.push_str("(\n");
+    for param in &params {
+        signature.push_str(&format!("{}{}{},\n", INDENT, INDENT, param));
+    }
+    signature.push_str(INDENT);
+    signature.push(')');
+    signature.push_str(&end);
+    signature
+}
+
+/// Display width of a line, counted in characters like rustfmt does
+fn width(line: &str) -> usize {
+    line.chars().count()
+}
+
+/// Renders `text` as `///` lines at `level`, one per line of text. Any line ending counts,
+/// a lone `\r` included, and blank lines around the text are dropped.
+fn doc_comment(text: &str, level: usize) -> String {
+    let indent = INDENT.repeat(level);
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+    let lines: Vec<&str> = text.lines().map(str::trim_end).collect();
+    let first = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let last = lines.iter().rposition(|line| !line.is_empty()).map_or(first, |last| last + 1);
+    lines[first..last]
+        .iter()
+        .map(|line| match *line {
+            "" => format!("{}///\n", indent),
+            line => format!("{}/// {}\n", indent, escape_doc_line(line)),
+        })
+        .collect()
+}
+
+/// Writes characters rustc rejects or warns about in comments as `\u{..}` escapes: control
+/// characters other than tab, and the bidirectional overrides that can make source read
+/// differently from how it compiles
+fn escape_doc_line(line: &str) -> String {
+    line.chars()
+        .map(|c| {
+            let bidi = matches!(c, '\u{202A}'..='\u{202E}' | '\u{2066}'..='\u{2069}');
+            if bidi || (c.is_control() && c != '\t') {
+                format!("\\u{{{:04X}}}", u32::from(c))
+            } else {
+                c.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Renders the qualifiers written before `fn`, in the order Rust requires:
+/// `async unsafe extern "ABI"`. Trait methods can't be `const`.
+pub fn method_qualifiers(meta: &MethodMeta) -> String {
+    let mut out = String::new();
+    if meta.is_async {
+        out.push_str("async ");
+    }
+    if meta.is_unsafe {
+        out.push_str("unsafe ");
+    }
+    if let Some(abi) = &meta.abi {
+        let abi = abi.trim().trim_matches('"');
+        if abi.is_empty() {
+            out.push_str("extern ");
+        } else {
+            out.push_str(&format!("extern \"{}\" ", abi));
+        }
+    }
+    out
+}
+
+/// Renders `type Name: Bounds = Default;`, or nothing for an unnamed item
+fn associated_type(item: &AssociatedType) -> Option<String> {
+    let name = item.name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let mut line = format!("type {}", with_bounds(name.to_string(), &item.bounds));
+    if let Some(default) = item.default.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        line.push_str(" = ");
+        line.push_str(default);
+    }
+    line.push(';');
+    Some(line)
+}
+
+/// Renders `const NAME: Type = default;`, or nothing for an unnamed item
+fn associated_const(item: &AssociatedConst) -> Option<String> {
+    let name = item.name.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let ty = item.ty.trim();
+    let mut line = format!("const {}: {}", name, if ty.is_empty() { "()" } else { ty });
+    if let Some(default) = item.default.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+        line.push_str(" = ");
+        line.push_str(default);
+    }
+    line.push(';');
+    Some(line)
+}
+
+/// Renders the `<...>` parameter list of `generics`, lifetimes first, or nothing if empty
+pub fn generic_params(generics: &Generics) -> String {
+    let (lifetimes, others): (Vec<_>, Vec<_>) = generics
+        .params
+        .iter()
+        .filter(|param| !param.name().trim().is_empty())
+        .partition(|param| param.is_lifetime());
+    if lifetimes.is_empty() && others.is_empty() {
+        return String::new();
+    }
+    let params: Vec<String> = lifetimes.into_iter().chain(others).map(generic_param).collect();
+    format!("<{}>", params.join(", "))
+}
+
+fn generic_param(param: &GenericParam) -> String {
+    match param {
+        GenericParam::Lifetime { name, bounds } => {
+            let bounds: Vec<String> = bounds.iter().map(|bound| lifetime(bound)).collect();
+            with_bounds(lifetime(name), &bounds)
+        }
+        GenericParam::Type { name, bounds, default } => {
+            let mut out = with_bounds(name.trim().to_string(), bounds);
+            if let Some(default) = default.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+                out.push_str(" = ");
+                out.push_str(default);
+            }
+            out
+        }
+        GenericParam::Const { name, ty, default } => {
+            let mut out = format!("const {}: {}", name.trim(), ty.trim());
+            if let Some(default) = default.as_deref().map(str::trim).filter(|d| !d.is_empty()) {
+                // Anything but a literal or a single identifier needs braces in a const default
+                let bare = default.starts_with('{')
+                    || default.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-');
+                out.push_str(" = ");
+                if bare {
+                    out.push_str(default);
+                } else {
+                    out.push_str(&format!("{{ {} }}", default));
+                }
+            }
+            out
+        }
+    }
+}
+
+/// Renders the `where` clause of `generics` at `level`, without the trailing comma of the
+/// last predicate so the caller can close it with `,` or `;`
+pub fn where_clause(generics: &Generics, level: usize) -> Option<String> {
+    let predicates: Vec<String> = generics
+        .where_clause
+        .iter()
+        .filter(|predicate| !predicate.bounded.trim().is_empty())
+        .map(|predicate| with_bounds(predicate.bounded.trim().to_string(), &predicate.bounds))
+        .collect();
+    if predicates.is_empty() {
+        return None;
+    }
+    let indent = INDENT.repeat(level);
+    let separator = format!(",\n{}{}", indent, INDENT);
+    Some(format!("{}where\n{}{}{}", indent, indent, INDENT, predicates.join(&separator)))
+}
+
+fn with_bounds(subject: String, bounds: &[String]) -> String {
+    if bounds.is_empty() {
+        subject
+    } else {
+        format!("{}: {}", subject, bounds.join(" + "))
+    }
+}
+
+/// Lifetimes may be entered with or without their leading apostrophe
+fn lifetime(name: &str) -> String {
+    let name = name.trim();
+    if name.starts_with('\'') {
+        name.to_string()
+    } else {
+        format!("'{}", name)
+    }
+}
+
+/// Path types are printed in canonical spelling when they parse, and verbatim otherwise
+pub fn type_ref_to_string(type_ref: &TypeRef) -> String {
+    match type_ref {
+        TypeRef::Primitive { name } => name.clone(),
+        TypeRef::Path { .. } => TypeExpr::from_type_ref(type_ref).to_string(),
+        TypeRef::AliasRef { alias } => alias.clone(),
+    }
+}
+
+/// Re-indents a block of code to `level` indentation units.
+///
+/// The common leading whitespace of the block is stripped first, so a body written at
+/// any indentation keeps its relative nesting. Leading and trailing blank lines are
+/// dropped, and blank lines inside the block carry no trailing whitespace.
+pub fn indent_block(block: &str, level: usize) -> String {
+    let lines: Vec<String> = block
+        .lines()
+        .map(|line| expand_leading_tabs(line.trim_end()))
+        .collect();
+
+    let first = lines.iter().position(|line| !line.is_empty());
+    let last = lines.iter().rposition(|line| !line.is_empty());
+    let (Some(first), Some(last)) = (first, last) else {
+        return String::new();
+    };
+    let lines = &lines[first..=last];
+
+    let common_indent = lines
+        .iter()
+        .filter(|line| !line.is_empty())
+        .map(|line| line.len() - line.trim_start_matches(' ').len())
+        .min()
+        .unwrap_or(0);
+
+    let prefix = INDENT.repeat(level);
+    let mut out = String::new();
+    for line in lines {
+        if !line.is_empty() {
+            out.push_str(&prefix);
+            out.push_str(&line[common_indent..]);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn expand_leading_tabs(line: &str) -> String {
+    let content = line.trim_start_matches([' ', '\t']);
+    let leading = &line[..line.len() - content.len()];
+    let width: usize = leading.chars().map(|c| if c == '\t' { INDENT.len() } else { 1 }).sum();
+    format!("{}{}", " ".repeat(width), content)
+}
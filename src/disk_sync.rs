@@ -0,0 +1,203 @@
+//! Noticing when `trait.json` changes behind the editor's back, and merging those changes
+//! with unsaved edits
+//!
+//! The editor remembers a [`DiskState`] for the content it last loaded or saved and polls
+//! the file every [`POLL_INTERVAL`]. Merging is three-way at the JSON level, with the
+//! snapshot the editor last loaded or saved as the common base.
+
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use serde_json::{Map, Value};
+use ui_types_common::TraitAsset;
+use crate::model::asset_snapshot;
+
+/// How often an open editor checks its file for outside changes
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// What the editor knows about the file on disk
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DiskState {
+    /// Hash of the file's content
+    pub hash: u64,
+    /// Modification time, used to skip reading files that weren't touched
+    pub modified: Option<SystemTime>,
+}
+
+impl DiskState {
+    /// State of `path` right after `content` was read from or written to it
+    pub fn new(path: &Path, content: &str) -> Self {
+        Self {
+            hash: content_hash(content),
+            modified: modified_time(path),
+        }
+    }
+}
+
+/// FNV-1a, stable across runs and platforms
+pub fn content_hash(content: &str) -> u64 {
+    content.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+pub fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+/// Reads `path` if it may have changed since `known`, returning its content and new state.
+/// `None` means the file is unchanged or can't be read right now.
+pub fn read_if_changed(path: &Path, known: Option<DiskState>) -> Option<(String, DiskState)> {
+    let modified = modified_time(path);
+    if let Some(known) = known {
+        if modified.is_some() && modified == known.modified {
+            return None;
+        }
+    }
+    let content = std::fs::read_to_string(path).ok()?;
+    let state = DiskState { hash: content_hash(&content), modified };
+    Some((content, state))
+}
+
+/// Result of merging the file on disk into unsaved edits
+pub struct Merge {
+    pub asset: TraitAsset,
+    /// Paths such as `methods.update.signature` that both sides changed differently. Those
+    /// keep the local version.
+    pub conflicts: Vec<String>,
+}
+
+/// Three-way merges `mine` and `theirs`, both edited from `base`, an [`asset_snapshot`].
+/// Methods are matched by name together with their editor meta, so reordering or adding
+/// methods on either side doesn't misalign them.
+pub fn merge(base: &Value, mine: &TraitAsset, theirs: &TraitAsset) -> Result<Merge, String> {
+    // In the same form as `base`, so meta written out in full doesn't count as a change
+    let mine = asset_snapshot(mine);
+    let theirs = asset_snapshot(theirs);
+
+    let mut conflicts = Vec::new();
+    let merged = match (fold_methods(base), fold_methods(&mine), fold_methods(&theirs)) {
+        (Some((base, _)), Some((folded_mine, mine_order)), Some((folded_theirs, theirs_order))) => {
+            let merged = merge_value(Some(&base), &folded_mine, &folded_theirs, "", &mut conflicts);
+            // `methods.update.method.signature` reads as `methods.update.signature`
+            for conflict in &mut conflicts {
+                if conflict.starts_with("methods.") {
+                    *conflict = conflict.replacen(".method.", ".", 1);
+                }
+            }
+            unfold_methods(merged, &mine_order, &theirs_order)
+        }
+        // Duplicate method names can't be matched up, so methods merge by position
+        _ => merge_value(Some(base), &mine, &theirs, "", &mut conflicts),
+    };
+
+    let asset = serde_json::from_value(merged).map_err(|e| e.to_string())?;
+    Ok(Merge { asset, conflicts })
+}
+
+fn merge_value(base: Option<&Value>, mine: &Value, theirs: &Value, path: &str, conflicts: &mut Vec<String>) -> Value {
+    if mine == theirs || base == Some(theirs) {
+        return mine.clone();
+    }
+    if base == Some(mine) {
+        return theirs.clone();
+    }
+    let (Value::Object(mine), Value::Object(theirs)) = (mine, theirs) else {
+        conflicts.push(path.to_string());
+        return mine.clone();
+    };
+
+    let base = base.and_then(Value::as_object);
+    let keys = mine.keys().chain(theirs.keys().filter(|key| !mine.contains_key(*key)));
+    let mut merged = Map::new();
+    for key in keys {
+        let base_value = base.and_then(|base| base.get(key));
+        let child_path = if path.is_empty() { key.clone() } else { format!("{}.{}", path, key) };
+        let value = match (mine.get(key), theirs.get(key)) {
+            (Some(mine), Some(theirs)) => Some(merge_value(base_value, mine, theirs, &child_path, conflicts)),
+            // Kept on one side and removed on the other: the removal wins unless the kept
+            // side also changed it
+            (Some(kept), None) | (None, Some(kept)) => match base_value {
+                None => Some(kept.clone()),
+                Some(base_value) if base_value == kept => None,
+                Some(_) => {
+                    conflicts.push(child_path);
+                    mine.get(key).cloned()
+                }
+            },
+            (None, None) => None,
+        };
+        if let Some(value) = value {
+            merged.insert(key.clone(), value);
+        }
+    }
+    Value::Object(merged)
+}
+
+/// Replaces the `methods` array and `meta.methods` with one object keyed by method name,
+/// returning the original order of the names. `None` if names aren't unique.
+fn fold_methods(asset: &Value) -> Option<(Value, Vec<String>)> {
+    let mut asset = asset.as_object()?.clone();
+    let methods = match asset.remove("methods") {
+        Some(Value::Array(methods)) => methods,
+        None | Some(Value::Null) => Vec::new(),
+        Some(_) => return None,
+    };
+    let mut metas = match asset.get_mut("meta").and_then(Value::as_object_mut).map(|meta| meta.remove("methods")) {
+        Some(Some(Value::Array(metas))) => metas,
+        _ => Vec::new(),
+    };
+    metas.resize(methods.len(), Value::Null);
+
+    let mut order = Vec::new();
+    let mut folded = Map::new();
+    for (method, meta) in methods.into_iter().zip(metas) {
+        let name = method.get("name")?.as_str()?.to_string();
+        let mut entry = Map::new();
+        entry.insert("method".to_string(), method);
+        if !meta.is_null() {
+            entry.insert("meta".to_string(), meta);
+        }
+        if folded.insert(name.clone(), Value::Object(entry)).is_some() {
+            return None;
+        }
+        order.push(name);
+    }
+    asset.insert("methods".to_string(), Value::Object(folded));
+    Some((Value::Object(asset), order))
+}
+
+/// Undoes [`fold_methods`], keeping the local order and appending methods only the other
+/// side added
+fn unfold_methods(asset: Value, mine_order: &[String], theirs_order: &[String]) -> Value {
+    let Value::Object(mut asset) = asset else {
+        return asset;
+    };
+    let Some(Value::Object(mut folded)) = asset.remove("methods") else {
+        return Value::Object(asset);
+    };
+
+    let order = mine_order.iter().chain(theirs_order.iter().filter(|name| !mine_order.contains(name)));
+    let mut methods = Vec::new();
+    let mut metas = Vec::new();
+    for name in order {
+        let Some(Value::Object(mut entry)) = folded.remove(name) else {
+            continue;
+        };
+        let Some(method) = entry.remove("method") else {
+            continue;
+        };
+        methods.push(method);
+        metas.push(entry.remove("meta").unwrap_or_else(|| Value::Object(Map::new())));
+    }
+
+    if metas.iter().any(|meta| meta.as_object().is_none_or(|meta| !meta.is_empty())) {
+        if !asset.get("meta").is_some_and(Value::is_object) {
+            asset.insert("meta".to_string(), Value::Object(Map::new()));
+        }
+        if let Some(Value::Object(meta)) = asset.get_mut("meta") {
+            meta.insert("methods".to_string(), Value::Array(metas));
+        }
+    }
+    asset.insert("methods".to_string(), Value::Array(methods));
+    Value::Object(asset)
+}
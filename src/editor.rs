@@ -1,24 +1,73 @@
 use gpui::{*, prelude::FluentBuilder, actions};
 use ui::{
     v_flex, h_flex, ActiveTheme, StyledExt, IconName,
-    dock::{Panel, PanelEvent, DockItem, DockChannel},
+    dock::{Panel, PanelEvent, DockItem, DockChannel, DockPlacement},
     workspace::Workspace,
     button::{Button, ButtonVariants},
     divider::Divider,
     input::{InputState, TextInput},
 };
-use ui_types_common::{TraitAsset, TraitMethod, MethodSignature, TypeRef, TypeKind};
+use ui_types_common::{TraitAsset, TraitMethod, MethodSignature, TypeRef};
 use std::path::PathBuf;
 use std::sync::Arc;
+use crate::broken_file::{BrokenFileEvent, BrokenFileView};
+use crate::disk_sync::{self, DiskState};
 use crate::method_editor::{MethodEditorView, MethodEditorEvent};
-use crate::workspace_panels::{PropertiesPanel, MethodsPanel, CodePreviewPanel};
+use crate::model;
+use crate::history::{EditHistory, TraitCommand};
+use crate::import_dialog::{RustImportDialog, RustImportEvent};
+use crate::keymap::KEY_CONTEXT;
+use crate::output::{self, OutputStatus};
+use crate::persistence;
+use crate::schema;
+use crate::template_wizard::{NewTraitEvent, NewTraitWizard};
+use crate::templates::{self, TraitTemplate};
+use crate::type_picker::project_root_for;
+use crate::validation::DiagnosticTarget;
+use crate::workspace_panels::{AssetChanged, ImportRequested, NavigateToDiagnostic, RegenerateAllRequested, SyncRequested, TemplateRequested, PropertiesPanel, MethodsPanel, AssociatedItemsPanel, CodePreviewPanel, DiagnosticsPanel};
 
 actions!(trait_editor, [
     Save,
     AddMethod,
     TogglePreview,
+    Undo,
+    Redo,
+    ImportFromRust,
+    RegenerateAllTraits,
 ]);
 
+/// Result of writing generated code, shown above the workspace until dismissed
+struct OutputNotice {
+    message: String,
+    details: Vec<String>,
+    is_warning: bool,
+    /// The output file was left alone and can be overwritten on request
+    can_overwrite: bool,
+}
+
+/// `trait.json` changed on disk while the editor had unsaved edits, or in a way it can't
+/// load. Shown above the workspace until resolved.
+struct DiskConflict {
+    /// The asset now on disk, or why it can't be loaded
+    theirs: Result<TraitAsset, String>,
+    state: DiskState,
+}
+
+/// Files a template created next to `trait.json`. They go away when the template is
+/// undone and come back when it is redone.
+struct TemplateFiles {
+    files: Vec<(PathBuf, String)>,
+    /// Snapshots of the asset before and after the template was applied
+    before: serde_json::Value,
+    after: serde_json::Value,
+}
+
+/// Unsaved edits autosaved by an earlier session, offered back when the trait opens
+struct Recovery {
+    asset: TraitAsset,
+    autosaved_at: std::time::SystemTime,
+}
+
 #[derive(Clone, Debug)]
 pub enum TraitEditorEvent {
     Modified,
@@ -28,43 +77,103 @@ pub enum TraitEditorEvent {
 pub struct TraitEditor {
     file_path: Option<PathBuf>,
     asset: Arc<parking_lot::RwLock<TraitAsset>>,
+    history: Arc<parking_lot::Mutex<EditHistory>>,
     error_message: Option<String>,
     focus_handle: FocusHandle,
 
     // Workspace for dock panels
     workspace: Option<Entity<Workspace>>,
+    properties_panel: Option<Entity<PropertiesPanel>>,
+    methods_panel: Option<Entity<MethodsPanel>>,
+    associated_items_panel: Option<Entity<AssociatedItemsPanel>>,
+    code_preview_panel: Option<Entity<CodePreviewPanel>>,
+    diagnostics_panel: Option<Entity<DiagnosticsPanel>>,
+    import_dialog: Option<Entity<RustImportDialog>>,
+    template_wizard: Option<Entity<NewTraitWizard>>,
+    template_files: Option<TemplateFiles>,
+    output_notice: Option<OutputNotice>,
+    // Set while trait.json can't be loaded; the asset is then a placeholder never saved
+    broken_file: Option<Entity<BrokenFileView>>,
 
-    // Modified flag
-    modified: bool,
+    // Modified flag, shared with the plugin wrapper so the host can query it
+    modified: Arc<parking_lot::Mutex<bool>>,
+    // Serialized asset as last loaded or saved, used to detect edits that return to it
+    saved_snapshot: serde_json::Value,
+    // The file as last loaded or saved, to notice when something else writes to it
+    disk_state: Option<DiskState>,
+    disk_conflict: Option<DiskConflict>,
+    recovery: Option<Recovery>,
+    // The asset changed since the recovery file was last brought up to date
+    autosave_pending: bool,
 }
 
 impl TraitEditor {
     pub fn new_with_file(file_path: PathBuf, window: &mut Window, cx: &mut Context<Self>) -> Self {
-        // Try to load the trait data
-        let (asset, error_message) = match std::fs::read_to_string(&file_path) {
+        // Try to load the trait data. A file that fails to load opens in the broken file
+        // view with the error and content needed to fix it.
+        let (asset, load_failure, disk_state) = match std::fs::read_to_string(&file_path) {
             Ok(json_content) => {
-                match serde_json::from_str::<TraitAsset>(&json_content) {
-                    Ok(asset) => (asset, None),
+                let disk_state = Some(DiskState::new(&file_path, &json_content));
+                match schema::load_asset(&json_content) {
+                    Ok(asset) => (asset, None, disk_state),
                     Err(e) => (
-                        Self::create_empty_asset(),
-                        Some(format!("Failed to parse trait: {}", e))
+                        templates::empty_asset(),
+                        Some((Some(json_content), format!("Failed to load trait: {}", e))),
+                        disk_state,
                     ),
                 }
             }
-            Err(_) => (Self::create_empty_asset(), None),
+            // Nothing to lose: saving creates the file
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => (templates::empty_asset(), None, None),
+            Err(e) => (
+                templates::empty_asset(),
+                Some((None, format!("Failed to read {}: {}", file_path.display(), e))),
+                None,
+            ),
         };
 
+        let saved_snapshot = Self::snapshot(&asset);
+
         let mut editor = Self {
             file_path: Some(file_path),
             asset: Arc::new(parking_lot::RwLock::new(asset)),
-            error_message,
+            history: Arc::new(parking_lot::Mutex::new(EditHistory::new())),
+            error_message: None,
             focus_handle: cx.focus_handle(),
             workspace: None,
-            modified: false,
+            properties_panel: None,
+            methods_panel: None,
+            associated_items_panel: None,
+            code_preview_panel: None,
+            diagnostics_panel: None,
+            import_dialog: None,
+            template_wizard: None,
+            template_files: None,
+            output_notice: None,
+            broken_file: None,
+            modified: Arc::new(parking_lot::Mutex::new(false)),
+            saved_snapshot,
+            disk_state,
+            disk_conflict: None,
+            recovery: None,
+            autosave_pending: false,
         };
 
         // Initialize workspace with panels
         editor.initialize_workspace(window, cx);
+        editor.watch_file(window, cx);
+        editor.start_autosave(window, cx);
+
+        if let Some((content, error)) = load_failure {
+            tracing::error!("{}", error);
+            editor.open_broken_file(content, error, window, cx);
+        } else {
+            editor.recovery = editor.find_recovery();
+            // A freshly created trait starts with a choice of template
+            if editor.recovery.is_none() && templates::is_untouched(&editor.asset.read()) {
+                editor.open_template_wizard(window, cx);
+            }
+        }
 
         editor
     }
@@ -85,42 +194,59 @@ impl TraitEditor {
         });
 
         let asset_clone = self.asset.clone();
+        let history_clone = self.history.clone();
 
-        workspace.update(cx, |workspace, cx| {
+        let (properties_panel, methods_panel, associated_items_panel, code_preview_panel, diagnostics_panel) = workspace.update(cx, |workspace, cx| {
             let dock_area = workspace.dock_area().downgrade();
 
             // Create Properties Panel (left)
             let properties_panel = cx.new(|cx| {
-                PropertiesPanel::new(asset_clone.clone(), window, cx)
+                PropertiesPanel::new(asset_clone.clone(), history_clone.clone(), window, cx)
             });
 
             // Create Methods Panel (center)
             let methods_panel = cx.new(|cx| {
-                MethodsPanel::new(asset_clone.clone(), window, cx)
+                MethodsPanel::new(asset_clone.clone(), history_clone.clone(), window, cx)
+            });
+
+            // Create Associated Items Panel (center, next to methods)
+            let associated_items_panel = cx.new(|cx| {
+                AssociatedItemsPanel::new(asset_clone.clone(), history_clone.clone(), window, cx)
             });
 
             // Create Code Preview Panel (right)
             let code_preview_panel = cx.new(|cx| {
-                CodePreviewPanel::new(asset_clone.clone(), window, cx)
+                CodePreviewPanel::new(asset_clone.clone(), history_clone.clone(), window, cx)
+            });
+
+            // Create Diagnostics Panel (right, next to the code preview)
+            let diagnostics_panel = cx.new(|cx| {
+                DiagnosticsPanel::new(asset_clone.clone(), window, cx)
             });
 
             // Setup dock layout - all panels in tabs for consistency
             let center = DockItem::tabs(
-                vec![Arc::new(methods_panel) as Arc<dyn ui::dock::PanelView>],
+                vec![
+                    Arc::new(methods_panel.clone()) as Arc<dyn ui::dock::PanelView>,
+                    Arc::new(associated_items_panel.clone()) as Arc<dyn ui::dock::PanelView>,
+                ],
                 Some(0),
                 &dock_area,
                 window,
                 cx,
             );
             let left = DockItem::tabs(
-                vec![Arc::new(properties_panel) as Arc<dyn ui::dock::PanelView>],
+                vec![Arc::new(properties_panel.clone()) as Arc<dyn ui::dock::PanelView>],
                 Some(0),
                 &dock_area,
                 window,
                 cx,
             );
             let right = DockItem::tabs(
-                vec![Arc::new(code_preview_panel) as Arc<dyn ui::dock::PanelView>],
+                vec![
+                    Arc::new(code_preview_panel.clone()) as Arc<dyn ui::dock::PanelView>,
+                    Arc::new(diagnostics_panel.clone()) as Arc<dyn ui::dock::PanelView>,
+                ],
                 Some(0),
                 &dock_area,
                 window,
@@ -133,58 +259,921 @@ impl TraitEditor {
                 dock_area.set_left_dock(left, Some(px(300.0)), true, window, cx);
                 dock_area.set_right_dock(right, Some(px(400.0)), true, window, cx);
             });
+
+            (properties_panel, methods_panel, associated_items_panel, code_preview_panel, diagnostics_panel)
         });
 
+        // Every panel edit flows back here so dirty state and the preview stay current
+        cx.subscribe_in(&properties_panel, window, |this: &mut Self, _, _: &AssetChanged, window, cx| {
+            this.on_asset_changed(window, cx);
+        }).detach();
+        cx.subscribe_in(&properties_panel, window, |this: &mut Self, _, _: &SyncRequested, window, cx| {
+            this.sync_panels(window, cx);
+        }).detach();
+        cx.subscribe_in(&properties_panel, window, |this: &mut Self, _, _: &ImportRequested, window, cx| {
+            this.open_import_dialog(window, cx);
+        }).detach();
+        cx.subscribe_in(&properties_panel, window, |this: &mut Self, _, _: &TemplateRequested, window, cx| {
+            this.open_template_wizard(window, cx);
+        }).detach();
+        cx.subscribe_in(&properties_panel, window, |this: &mut Self, _, _: &RegenerateAllRequested, _window, cx| {
+            this.regenerate_all(cx);
+        }).detach();
+        cx.subscribe_in(&methods_panel, window, |this: &mut Self, _, _: &AssetChanged, window, cx| {
+            this.on_asset_changed(window, cx);
+        }).detach();
+        cx.subscribe_in(&associated_items_panel, window, |this: &mut Self, _, _: &AssetChanged, window, cx| {
+            this.on_asset_changed(window, cx);
+        }).detach();
+        // Code typed into the preview replaces the asset, so the form panels re-read it
+        cx.subscribe_in(&code_preview_panel, window, |this: &mut Self, _, _: &SyncRequested, window, cx| {
+            this.sync_panels(window, cx);
+        }).detach();
+        cx.subscribe_in(&code_preview_panel, window, |this: &mut Self, _, _: &AssetChanged, window, cx| {
+            this.on_asset_changed(window, cx);
+        }).detach();
+
+        cx.subscribe_in(&diagnostics_panel, window, |this: &mut Self, _, event: &NavigateToDiagnostic, window, cx| {
+            this.navigate_to(event, window, cx);
+        }).detach();
+
+        let project_root = self.file_path.as_deref().and_then(project_root_for);
+        properties_panel.update(cx, |panel, _cx| panel.set_project_root(project_root.clone()));
+        methods_panel.update(cx, |panel, _cx| panel.set_project_root(project_root.clone()));
+        diagnostics_panel.update(cx, |panel, cx| panel.set_project_root(project_root, cx));
+
         self.workspace = Some(workspace);
+        self.properties_panel = Some(properties_panel);
+        self.methods_panel = Some(methods_panel);
+        self.associated_items_panel = Some(associated_items_panel);
+        self.code_preview_panel = Some(code_preview_panel);
+        self.diagnostics_panel = Some(diagnostics_panel);
     }
 
-    fn create_empty_asset() -> TraitAsset {
-        TraitAsset {
-            schema_version: 1,
-            type_kind: TypeKind::Trait,
-            name: String::from("NewTrait"),
-            display_name: String::from("New Trait"),
-            description: None,
-            methods: Vec::new(),
-            meta: serde_json::Value::Object(serde_json::Map::new()),
+    /// Reveals the method editor a clicked diagnostic points at
+    fn navigate_to(&mut self, NavigateToDiagnostic(target): &NavigateToDiagnostic, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(index) = target.method_index() else {
+            return;
+        };
+        let param = match target {
+            DiagnosticTarget::Param { param, .. } => Some(*param),
+            _ => None,
+        };
+        if let Some(methods_panel) = &self.methods_panel {
+            methods_panel.update(cx, |panel, cx| panel.reveal_method(index, param, window, cx));
         }
     }
 
-    pub fn file_path(&self) -> Option<PathBuf> {
-        self.file_path.clone()
+    fn on_asset_changed(&mut self, _window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(code_preview_panel) = &self.code_preview_panel {
+            code_preview_panel.update(cx, |panel, cx| {
+                panel.mark_needs_update();
+                cx.notify();
+            });
+        }
+        if let Some(diagnostics_panel) = &self.diagnostics_panel {
+            diagnostics_panel.update(cx, |panel, cx| panel.refresh(cx));
+        }
+        // The properties panel shows the dyn-compatibility of the methods
+        if let Some(properties_panel) = &self.properties_panel {
+            properties_panel.update(cx, |_panel, cx| cx.notify());
+        }
+
+        let was_modified = self.is_dirty();
+        let modified = Self::snapshot(&self.asset.read()) != self.saved_snapshot;
+        *self.modified.lock() = modified;
+        self.autosave_pending = true;
+
+        if modified {
+            cx.emit(TraitEditorEvent::Modified);
+        }
+        if modified != was_modified {
+            cx.emit(PanelEvent::LayoutChanged);
+        }
+        cx.notify();
     }
 
-    fn save(&mut self, _: &Save, _window: &mut Window, cx: &mut Context<Self>) {
-        if let Some(file_path) = &self.file_path {
+    fn undo(&mut self, _: &Undo, window: &mut Window, cx: &mut Context<Self>) {
+        let undone = self.history.lock().undo(&mut self.asset.write());
+        if undone {
+            self.sync_template_files();
+            self.sync_panels(window, cx);
+            self.on_asset_changed(window, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, window: &mut Window, cx: &mut Context<Self>) {
+        let redone = self.history.lock().redo(&mut self.asset.write());
+        if redone {
+            self.sync_template_files();
+            self.sync_panels(window, cx);
+            self.on_asset_changed(window, cx);
+        }
+    }
+
+    fn add_method(&mut self, _: &AddMethod, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(methods_panel) = &self.methods_panel {
+            methods_panel.update(cx, |panel, cx| panel.add_method(window, cx));
+        }
+    }
+
+    fn toggle_preview(&mut self, _: &TogglePreview, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(workspace) = &self.workspace {
+            let dock_area = workspace.read(cx).dock_area().clone();
+            dock_area.update(cx, |dock_area, cx| {
+                dock_area.toggle_dock(DockPlacement::Right, window, cx);
+            });
+        }
+    }
+
+    fn import_from_rust(&mut self, _: &ImportFromRust, window: &mut Window, cx: &mut Context<Self>) {
+        self.open_import_dialog(window, cx);
+    }
+
+    fn open_import_dialog(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let directory = self.file_path.as_deref().and_then(project_root_for);
+        let dialog = cx.new(|cx| RustImportDialog::new(directory, window, cx));
+        cx.subscribe_in(&dialog, window, |this: &mut Self, _, event: &RustImportEvent, window, cx| {
+            if let RustImportEvent::Imported(asset) = event {
+                this.apply_import(asset, window, cx);
+            }
+            this.import_dialog = None;
+            window.focus(&this.focus_handle);
+            cx.notify();
+        }).detach();
+        dialog.update(cx, |dialog, cx| dialog.focus(window, cx));
+
+        self.import_dialog = Some(dialog);
+        cx.notify();
+    }
+
+    /// Replaces the edited trait with an imported one as a single undo step
+    fn apply_import(&mut self, imported: &TraitAsset, window: &mut Window, cx: &mut Context<Self>) {
+        let command = {
             let asset = self.asset.read();
-            match serde_json::to_string_pretty(&*asset) {
-                Ok(json) => {
-                    drop(asset); // Release the read lock before writing
-                    if let Err(e) = std::fs::write(file_path, json) {
-                        self.error_message = Some(format!("Failed to save: {}", e));
-                    } else {
-                        self.error_message = None;
-                        self.modified = false;
-                        tracing::info!("✅ Saved trait to {:?}", file_path);
-                        cx.emit(TraitEditorEvent::Saved);
-                    }
+            TraitCommand::replace_asset(&asset, imported)
+        };
+        let changed = self.history.lock().execute(command, &mut self.asset.write());
+        if changed {
+            self.sync_panels(window, cx);
+            self.on_asset_changed(window, cx);
+        }
+    }
+
+    fn open_template_wizard(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let wizard = cx.new(|cx| NewTraitWizard::new(window, cx));
+        cx.subscribe_in(&wizard, window, |this: &mut Self, _, event: &NewTraitEvent, window, cx| {
+            if let NewTraitEvent::Created { template, name } = event {
+                this.apply_template(*template, name, window, cx);
+            }
+            this.template_wizard = None;
+            window.focus(&this.focus_handle);
+            cx.notify();
+        }).detach();
+        wizard.update(cx, |wizard, cx| wizard.focus(window, cx));
+
+        self.template_wizard = Some(wizard);
+        cx.notify();
+    }
+
+    /// Replaces the trait with `template` as a single undo step and creates the template's
+    /// extra files next to `trait.json`. Existing files are left alone.
+    fn apply_template(&mut self, template: TraitTemplate, name: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let asset = template.asset(name);
+        let before = Self::snapshot(&self.asset.read());
+        self.apply_import(&asset, window, cx);
+
+        let mut files = Vec::new();
+        if let Some(dir) = self.file_path.as_deref().and_then(std::path::Path::parent) {
+            for (relative, content) in template.extra_files(&asset) {
+                let path = dir.join(relative);
+                if path.exists() {
+                    continue;
                 }
-                Err(e) => {
-                    self.error_message = Some(format!("Failed to serialize: {}", e));
+                match persistence::write_atomic(&path, &content) {
+                    Ok(()) => files.push((path, content)),
+                    Err(e) => self.error_message = Some(format!("Failed to create {}: {}", path.display(), e)),
                 }
             }
         }
+        let after = Self::snapshot(&self.asset.read());
+        self.template_files = (!files.is_empty()).then_some(TemplateFiles { files, before, after });
+    }
+
+    /// Removes the files of a template that was just undone, and recreates them when it is
+    /// redone. Files edited since the template created them are left alone.
+    fn sync_template_files(&mut self) {
+        let Some(template) = &self.template_files else {
+            return;
+        };
+        let snapshot = Self::snapshot(&self.asset.read());
+        for (path, content) in &template.files {
+            let result = if snapshot == template.before {
+                match std::fs::read_to_string(path) {
+                    Ok(current) if current == *content => std::fs::remove_file(path),
+                    _ => Ok(()),
+                }
+            } else if snapshot == template.after && !path.exists() {
+                persistence::write_atomic(path, content)
+            } else {
+                Ok(())
+            };
+            if let Err(e) = result {
+                self.error_message = Some(format!("Failed to update {}: {}", path.display(), e));
+            }
+        }
+    }
+
+    /// Polls `trait.json` for changes made by other programs for as long as the editor lives
+    fn watch_file(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor().timer(disk_sync::POLL_INTERVAL).await;
+                if this.update_in(cx, |this, window, cx| this.check_disk(window, cx)).is_err() {
+                    break;
+                }
+            }
+        }).detach();
+    }
+
+    /// Reloads `trait.json` if it changed on disk and there are no unsaved edits, and
+    /// raises a conflict otherwise
+    fn check_disk(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(file_path) = &self.file_path else {
+            return;
+        };
+        let latest = self.disk_conflict.as_ref().map(|conflict| conflict.state).or(self.disk_state);
+        let Some((content, state)) = disk_sync::read_if_changed(file_path, latest) else {
+            return;
+        };
+        if latest.is_some_and(|latest| latest.hash == state.hash) {
+            // Touched without changing, such as our own save or a checkout of the same content
+            match &mut self.disk_conflict {
+                Some(conflict) => conflict.state = state,
+                None => self.disk_state = Some(state),
+            }
+            return;
+        }
+
+        tracing::info!("{:?} changed on disk", file_path);
+        if self.broken_file.is_some() {
+            self.load_broken_content(content, state, false, window, cx);
+            return;
+        }
+        match schema::load_asset(&content) {
+            Ok(theirs) if !self.is_dirty() => self.take_theirs(theirs, state, window, cx),
+            theirs => {
+                self.disk_conflict = Some(DiskConflict { theirs: theirs.map_err(|e| e.to_string()), state });
+                cx.notify();
+            }
+        }
+    }
+
+    /// Checks that `trait.json` still holds what was last loaded or saved, so a save doesn't
+    /// overwrite changes made elsewhere. Raises the conflict banner if it doesn't.
+    fn ensure_disk_unchanged(&mut self, cx: &mut Context<Self>) -> Result<(), String> {
+        let (Some(file_path), Some(known)) = (&self.file_path, self.disk_state) else {
+            return Ok(());
+        };
+        // A deleted file is simply written again
+        let Ok(content) = std::fs::read_to_string(file_path) else {
+            return Ok(());
+        };
+        let state = DiskState::new(file_path, &content);
+        if state.hash == known.hash {
+            return Ok(());
+        }
+
+        let theirs = schema::load_asset(&content).map_err(|e| e.to_string());
+        self.disk_conflict = Some(DiskConflict { theirs, state });
         cx.notify();
+        Err("trait.json changed on disk since it was loaded. Keep your version, take theirs or merge before saving.".to_string())
+    }
+
+    /// Replaces the edited trait with the one on disk, dropping unsaved edits
+    fn take_theirs(&mut self, theirs: TraitAsset, state: DiskState, window: &mut Window, cx: &mut Context<Self>) {
+        *self.asset.write() = theirs;
+        self.history.lock().clear();
+        self.template_files = None;
+        self.error_message = None;
+        self.disk_state = Some(state);
+        self.disk_conflict = None;
+        self.broken_file = None;
+        self.mark_saved();
+        self.reload_panels(window, cx);
+        self.on_asset_changed(window, cx);
+    }
+
+    /// Keeps the unsaved edits and lets the next save overwrite the file on disk
+    fn keep_mine(&mut self, cx: &mut Context<Self>) {
+        if let Some(conflict) = self.disk_conflict.take() {
+            self.disk_state = Some(conflict.state);
+            cx.notify();
+        }
+    }
+
+    /// Folds the changes made on disk into the unsaved edits. Fields both sides changed keep
+    /// the local version and are listed in a notice.
+    fn merge_theirs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(DiskConflict { theirs: Ok(theirs), state }) = &self.disk_conflict else {
+            return;
+        };
+        let merged = {
+            let asset = self.asset.read();
+            disk_sync::merge(&self.saved_snapshot, &asset, theirs)
+        };
+        let merge = match merged {
+            Ok(merge) => merge,
+            Err(e) => {
+                self.error_message = Some(format!("Failed to merge: {}", e));
+                cx.notify();
+                return;
+            }
+        };
+
+        // The file on disk becomes the saved state, so the merge result shows as unsaved
+        self.saved_snapshot = Self::snapshot(theirs);
+        self.disk_state = Some(*state);
+        self.disk_conflict = None;
+        *self.asset.write() = merge.asset;
+        self.history.lock().clear();
+        self.template_files = None;
+
+        self.output_notice = Some(if merge.conflicts.is_empty() {
+            OutputNotice {
+                message: "Merged the changes made on disk".to_string(),
+                details: Vec::new(),
+                is_warning: false,
+                can_overwrite: false,
+            }
+        } else {
+            OutputNotice {
+                message: "Merged the changes made on disk. These were changed on both sides and kept your version:".to_string(),
+                details: merge.conflicts,
+                is_warning: true,
+                can_overwrite: false,
+            }
+        });
+        self.reload_panels(window, cx);
+        self.on_asset_changed(window, cx);
+    }
+
+    /// Writes unsaved edits to the recovery file every [`persistence::AUTOSAVE_INTERVAL`]
+    fn start_autosave(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.spawn_in(window, async move |this, cx| {
+            loop {
+                cx.background_executor().timer(persistence::AUTOSAVE_INTERVAL).await;
+                if this.update_in(cx, |this, _window, _cx| this.autosave()).is_err() {
+                    break;
+                }
+            }
+        }).detach();
+    }
+
+    fn autosave(&mut self) {
+        // An unanswered recovery offer is kept until the user decides, and the placeholder
+        // shown for a broken file has nothing worth recovering
+        if !self.autosave_pending || self.recovery.is_some() || self.broken_file.is_some() {
+            return;
+        }
+        let Some(file_path) = &self.file_path else {
+            return;
+        };
+        self.autosave_pending = false;
+
+        // Edits undone back to the saved state leave nothing to recover
+        if !self.is_dirty() {
+            persistence::clear_recovery(file_path);
+            return;
+        }
+        let json = schema::asset_to_json(&self.asset.read());
+        let result = json.and_then(|json| persistence::write_recovery(file_path, &json).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::warn!("Failed to autosave {:?}: {}", file_path, e);
+        }
+    }
+
+    /// Recovery file left by a session that ended with unsaved edits
+    fn find_recovery(&self) -> Option<Recovery> {
+        let file_path = self.file_path.as_ref()?;
+        let (content, autosaved_at) = persistence::pending_recovery(file_path)?;
+        match schema::load_asset(&content) {
+            Ok(asset) if Self::snapshot(&asset) != self.saved_snapshot => Some(Recovery { asset, autosaved_at }),
+            Ok(_) => None,
+            Err(e) => {
+                tracing::warn!("Ignoring unreadable recovery file for {:?}: {}", file_path, e);
+                None
+            }
+        }
+    }
+
+    /// Puts the recovered edits in place as unsaved changes, undoable as one step
+    fn restore_recovery(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(recovery) = self.recovery.take() {
+            self.apply_import(&recovery.asset, window, cx);
+            cx.notify();
+        }
+    }
+
+    fn discard_recovery(&mut self, cx: &mut Context<Self>) {
+        self.recovery = None;
+        if let Some(file_path) = &self.file_path {
+            persistence::clear_recovery(file_path);
+        }
+        cx.notify();
+    }
+
+    /// Shows the broken file view in place of the workspace. `content` is the raw file, if
+    /// it could be read.
+    fn open_broken_file(&mut self, content: Option<String>, error: String, window: &mut Window, cx: &mut Context<Self>) {
+        let view = cx.new(|cx| BrokenFileView::new(content, error, window, cx));
+        cx.subscribe_in(&view, window, |this: &mut Self, _, event: &BrokenFileEvent, window, cx| {
+            match event {
+                BrokenFileEvent::Repaired(json) => this.save_repaired(json, window, cx),
+                BrokenFileEvent::ReloadRequested => this.reload_broken_file(window, cx),
+            }
+        }).detach();
+        self.broken_file = Some(view);
+        self.template_wizard = None;
+        self.recovery = None;
+        cx.notify();
+    }
+
+    /// Writes raw JSON fixed in the broken file view and opens the trait it now holds
+    fn save_repaired(&mut self, json: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(file_path) = self.file_path.clone() else {
+            return;
+        };
+        let asset = match schema::load_asset(json) {
+            Ok(asset) => asset,
+            Err(e) => {
+                self.error_message = Some(format!("Failed to load trait: {}", e));
+                cx.notify();
+                return;
+            }
+        };
+        // The broken version goes to the backups like any other replaced version
+        if let Err(e) = persistence::save(&file_path, json) {
+            self.error_message = Some(format!("Failed to save: {}", e));
+            cx.notify();
+            return;
+        }
+        tracing::info!("Repaired {:?}", file_path);
+        self.take_theirs(asset, DiskState::new(&file_path, json), window, cx);
+    }
+
+    fn reload_broken_file(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(file_path) = self.file_path.clone() else {
+            return;
+        };
+        match std::fs::read_to_string(&file_path) {
+            Ok(content) => {
+                let state = DiskState::new(&file_path, &content);
+                self.load_broken_content(content, state, true, window, cx);
+            }
+            Err(e) => {
+                let error = format!("Failed to read {}: {}", file_path.display(), e);
+                self.open_broken_file(None, error, window, cx);
+            }
+        }
+    }
+
+    /// Opens `content`, just read for a file in the broken file view, if it loads now, and
+    /// shows it in the view otherwise. Edits made in the view are only replaced if
+    /// `replace_edits` is set.
+    fn load_broken_content(&mut self, content: String, state: DiskState, replace_edits: bool, window: &mut Window, cx: &mut Context<Self>) {
+        self.disk_state = Some(state);
+        match schema::load_asset(&content) {
+            Ok(asset) => self.take_theirs(asset, state, window, cx),
+            Err(e) => {
+                let edited = self.broken_file.as_ref().is_some_and(|view| view.read(cx).is_edited(cx));
+                if replace_edits || !edited {
+                    self.open_broken_file(Some(content), format!("Failed to load trait: {}", e), window, cx);
+                }
+            }
+        }
+    }
+
+    /// Why saving is refused while the file failed to load
+    fn protected_error(&self) -> Option<String> {
+        self.broken_file.as_ref().map(|_| {
+            "trait.json couldn't be loaded, so it isn't saved over. Fix or reload it first.".to_string()
+        })
+    }
+
+    /// Writes the generated code to the asset's output file, if it has one. Files edited by
+    /// hand are only overwritten with `force`.
+    fn write_generated_code(&mut self, force: bool, cx: &mut Context<Self>) {
+        let Some(file_path) = &self.file_path else {
+            return;
+        };
+        let result = {
+            let asset = self.asset.read();
+            output::write_output(&asset, file_path, force)
+        };
+        self.output_notice = match result {
+            Ok(status) if status.is_conflict() => {
+                let message = status.message().unwrap_or_default();
+                tracing::warn!("{}", message);
+                Some(OutputNotice { message, details: Vec::new(), is_warning: true, can_overwrite: true })
+            }
+            Ok(status) => {
+                if let OutputStatus::Written(path) = &status {
+                    tracing::info!("Generated trait code at {:?}", path);
+                }
+                None
+            }
+            Err(e) => Some(OutputNotice { message: e, details: Vec::new(), is_warning: true, can_overwrite: false }),
+        };
+        cx.notify();
+    }
+
+    fn regenerate_all_traits(&mut self, _: &RegenerateAllTraits, _window: &mut Window, cx: &mut Context<Self>) {
+        self.regenerate_all(cx);
+    }
+
+    /// Regenerates the output files of every trait in the project, using this editor's
+    /// unsaved state for its own asset
+    fn regenerate_all(&mut self, cx: &mut Context<Self>) {
+        let Some(file_path) = self.file_path.clone() else {
+            return;
+        };
+        let Some(root) = project_root_for(&file_path) else {
+            return;
+        };
+        let summary = {
+            let asset = self.asset.read();
+            output::regenerate_all(&root, Some((&file_path, &asset)))
+        };
+        tracing::info!("{}", summary.message());
+        self.output_notice = Some(OutputNotice {
+            message: summary.message(),
+            is_warning: !summary.problems.is_empty(),
+            details: summary.problems,
+            can_overwrite: false,
+        });
+        cx.notify();
+    }
+
+    /// Pushes the shared asset back into the panels after it changed outside of them
+    fn sync_panels(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(properties_panel) = &self.properties_panel {
+            properties_panel.update(cx, |panel, cx| panel.sync_from_asset(window, cx));
+        }
+        if let Some(methods_panel) = &self.methods_panel {
+            methods_panel.update(cx, |panel, cx| panel.sync_from_asset(window, cx));
+        }
+        if let Some(associated_items_panel) = &self.associated_items_panel {
+            associated_items_panel.update(cx, |panel, cx| panel.sync_from_asset(window, cx));
+        }
+    }
+
+    /// Refreshes every panel after the asset was replaced as a whole (reload, merge). Panels
+    /// keep their scroll position, and method editors stay with their methods.
+    fn reload_panels(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.sync_panels(window, cx);
+        if let Some(code_preview_panel) = &self.code_preview_panel {
+            code_preview_panel.update(cx, |panel, cx| panel.sync_from_asset(cx));
+        }
+    }
+
+    /// Records the current asset as the saved state and clears the modified flag
+    fn mark_saved(&mut self) {
+        self.saved_snapshot = Self::snapshot(&self.asset.read());
+        *self.modified.lock() = false;
+    }
+
+    fn snapshot(asset: &TraitAsset) -> serde_json::Value {
+        model::asset_snapshot(asset)
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        *self.modified.lock()
+    }
+
+    /// Shared handle to the modified flag, readable without access to the entity
+    pub fn modified_flag(&self) -> Arc<parking_lot::Mutex<bool>> {
+        self.modified.clone()
+    }
+
+    pub fn file_path(&self) -> Option<PathBuf> {
+        self.file_path.clone()
+    }
+
+    fn save(&mut self, _: &Save, _window: &mut Window, cx: &mut Context<Self>) {
+        // Failures are shown in the editor
+        let _ = self.write_to_disk(cx);
+    }
+
+    /// Saves the asset to `trait.json` and regenerates its output file. Refused while the
+    /// file is protected or was changed on disk since it was loaded.
+    fn write_to_disk(&mut self, cx: &mut Context<Self>) -> Result<(), String> {
+        let Some(file_path) = self.file_path.clone() else {
+            return Err("No file path set".to_string());
+        };
+        let result = self
+            .protected_error()
+            .map_or(Ok(()), Err)
+            .and_then(|()| self.ensure_disk_unchanged(cx))
+            .and_then(|()| schema::asset_to_json(&self.asset.read()).map_err(|e| format!("Failed to serialize: {}", e)))
+            .and_then(|json| {
+                persistence::save(&file_path, &json).map_err(|e| format!("Failed to save: {}", e))?;
+                Ok(json)
+            });
+        let json = match result {
+            Ok(json) => json,
+            Err(e) => {
+                self.error_message = Some(e.clone());
+                cx.notify();
+                return Err(e);
+            }
+        };
+
+        tracing::info!("✅ Saved trait to {:?}", file_path);
+        self.error_message = None;
+        self.disk_state = Some(DiskState::new(&file_path, &json));
+        self.mark_saved();
+        self.write_generated_code(false, cx);
+        cx.emit(TraitEditorEvent::Saved);
+        cx.emit(PanelEvent::LayoutChanged);
+        cx.notify();
+        Ok(())
     }
 }
 
 impl EventEmitter<TraitEditorEvent> for TraitEditor {}
 impl EventEmitter<PanelEvent> for TraitEditor {}
 
+impl TraitEditor {
+    fn render_output_notice(&self, notice: &OutputNotice, cx: &mut Context<Self>) -> AnyElement {
+        let color = if notice.is_warning { cx.theme().warning } else { cx.theme().muted_foreground };
+
+        h_flex()
+            .w_full()
+            .px_3()
+            .py_1()
+            .gap_2()
+            .items_start()
+            .bg(color.opacity(0.1))
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                v_flex()
+                    .flex_1()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(color)
+                            .child(notice.message.clone())
+                    )
+                    .children(notice.details.iter().map(|detail| {
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(detail.clone())
+                    }))
+            )
+            .when(notice.can_overwrite, |this| {
+                this.child(
+                    Button::new("overwrite-output")
+                        .ghost()
+                        .label("Overwrite")
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.write_generated_code(true, cx);
+                        }))
+                )
+            })
+            .child(
+                Button::new("dismiss-output-notice")
+                    .ghost()
+                    .icon(IconName::Close)
+                    .on_click(cx.listener(|this, _, _window, cx| {
+                        this.output_notice = None;
+                        cx.notify();
+                    }))
+            )
+            .into_any_element()
+    }
+}
+
+impl TraitEditor {
+    fn render_error(&self, message: &str, cx: &mut Context<Self>) -> AnyElement {
+        h_flex()
+            .w_full()
+            .px_3()
+            .py_1()
+            .gap_2()
+            .bg(cx.theme().danger.opacity(0.1))
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .flex_1()
+                    .text_sm()
+                    .text_color(cx.theme().danger)
+                    .child(message.to_string())
+            )
+            .child(
+                Button::new("dismiss-error")
+                    .ghost()
+                    .icon(IconName::Close)
+                    .on_click(cx.listener(|this, _, _window, cx| {
+                        this.error_message = None;
+                        cx.notify();
+                    }))
+            )
+            .into_any_element()
+    }
+
+    fn render_disk_conflict(&self, conflict: &DiskConflict, cx: &mut Context<Self>) -> AnyElement {
+        let dirty = self.is_dirty();
+        let message = match &conflict.theirs {
+            Ok(_) if dirty => "trait.json was changed outside the editor while you have unsaved changes".to_string(),
+            Ok(_) => "trait.json was changed outside the editor".to_string(),
+            Err(e) => format!("trait.json was changed outside the editor and can't be loaded: {}", e),
+        };
+        let loaded = conflict.theirs.is_ok();
+
+        h_flex()
+            .w_full()
+            .px_3()
+            .py_1()
+            .gap_2()
+            .bg(cx.theme().warning.opacity(0.1))
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .flex_1()
+                    .text_sm()
+                    .text_color(cx.theme().warning)
+                    .child(message)
+            )
+            .child(
+                Button::new("disk-keep-mine")
+                    .ghost()
+                    .label("Keep mine")
+                    .on_click(cx.listener(|this, _, _window, cx| {
+                        this.keep_mine(cx);
+                    }))
+            )
+            .when(loaded, |this| {
+                this.child(
+                    Button::new("disk-take-theirs")
+                        .ghost()
+                        .label("Take theirs")
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            if let Some(DiskConflict { theirs: Ok(theirs), state }) = this.disk_conflict.take() {
+                                this.take_theirs(theirs, state, window, cx);
+                            }
+                        }))
+                )
+            })
+            .when(loaded && dirty, |this| {
+                this.child(
+                    Button::new("disk-merge")
+                        .primary()
+                        .label("Merge")
+                        .on_click(cx.listener(|this, _, window, cx| {
+                            this.merge_theirs(window, cx);
+                        }))
+                )
+            })
+            .into_any_element()
+    }
+}
+
+impl TraitEditor {
+    fn render_recovery(&self, recovery: &Recovery, cx: &mut Context<Self>) -> AnyElement {
+        let age = recovery.autosaved_at.elapsed().unwrap_or_default().as_secs();
+        let when = match age {
+            0..60 => "less than a minute ago".to_string(),
+            60..7200 => format!("{} minutes ago", age / 60),
+            7200..172800 => format!("{} hours ago", age / 3600),
+            _ => format!("{} days ago", age / 86400),
+        };
+
+        h_flex()
+            .w_full()
+            .px_3()
+            .py_1()
+            .gap_2()
+            .bg(cx.theme().accent.opacity(0.1))
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                div()
+                    .flex_1()
+                    .text_sm()
+                    .text_color(cx.theme().foreground)
+                    .child(format!("Found unsaved changes to this trait, autosaved {}", when))
+            )
+            .child(
+                Button::new("recovery-discard")
+                    .ghost()
+                    .label("Discard")
+                    .on_click(cx.listener(|this, _, _window, cx| {
+                        this.discard_recovery(cx);
+                    }))
+            )
+            .child(
+                Button::new("recovery-restore")
+                    .primary()
+                    .label("Restore")
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.restore_recovery(window, cx);
+                    }))
+            )
+            .into_any_element()
+    }
+}
+
 impl Render for TraitEditor {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let error = self.error_message.as_deref().map(|message| self.render_error(message, cx));
+        if let Some(broken_file) = self.broken_file.clone() {
+            // Only saving is wired up, to explain why it is refused
+            return v_flex()
+                .size_full()
+                .key_context(KEY_CONTEXT)
+                .track_focus(&self.focus_handle)
+                .on_action(cx.listener(Self::save))
+                .children(error)
+                .child(div().flex_1().min_h_0().child(broken_file))
+                .into_any_element();
+        }
         if let Some(ref workspace) = self.workspace {
-            workspace.clone().into_any_element()
+            let conflict = self.disk_conflict.as_ref().map(|conflict| self.render_disk_conflict(conflict, cx));
+            let recovery = self.recovery.as_ref().map(|recovery| self.render_recovery(recovery, cx));
+            let notice = self.output_notice.as_ref().map(|notice| self.render_output_notice(notice, cx));
+
+            v_flex()
+                .size_full()
+                .key_context(KEY_CONTEXT)
+                .track_focus(&self.focus_handle)
+                .on_action(cx.listener(Self::save))
+                .on_action(cx.listener(Self::add_method))
+                .on_action(cx.listener(Self::toggle_preview))
+                .on_action(cx.listener(Self::undo))
+                .on_action(cx.listener(Self::redo))
+                .on_action(cx.listener(Self::import_from_rust))
+                .on_action(cx.listener(Self::regenerate_all_traits))
+                .children(error)
+                .children(conflict)
+                .children(recovery)
+                .children(notice)
+                .child(div().flex_1().min_h_0().child(workspace.clone()))
+                .when_some(self.import_dialog.clone(), |this, dialog| {
+                    this.child(
+                        div()
+                            .id("import-dialog-overlay")
+                            .absolute()
+                            .top_0()
+                            .left_0()
+                            .size_full()
+                            .flex()
+                            .justify_center()
+                            .pt(px(64.0))
+                            .bg(gpui::black().opacity(0.3))
+                            .occlude()
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.import_dialog = None;
+                                cx.notify();
+                            }))
+                            .child(
+                                div()
+                                    .id("import-dialog-popover")
+                                    .on_click(|_, _window, cx| cx.stop_propagation())
+                                    .child(dialog)
+                            )
+                    )
+                })
+                .when_some(self.template_wizard.clone(), |this, wizard| {
+                    this.child(
+                        div()
+                            .id("template-wizard-overlay")
+                            .absolute()
+                            .top_0()
+                            .left_0()
+                            .size_full()
+                            .flex()
+                            .justify_center()
+                            .pt(px(64.0))
+                            .bg(gpui::black().opacity(0.3))
+                            .occlude()
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.template_wizard = None;
+                                cx.notify();
+                            }))
+                            .child(
+                                div()
+                                    .id("template-wizard-popover")
+                                    .on_click(|_, _window, cx| cx.stop_propagation())
+                                    .child(wizard)
+                            )
+                    )
+                })
+                .into_any_element()
         } else {
             div()
                 .size_full()
@@ -209,11 +1198,20 @@ impl Panel for TraitEditor {
     }
 
     fn title(&self, _window: &Window, _cx: &App) -> gpui::AnyElement {
+        // The placeholder asset's name would pass the broken file off as a new trait
+        if self.broken_file.is_some() {
+            let folder = self.file_path.as_deref()
+                .and_then(std::path::Path::parent)
+                .and_then(std::path::Path::file_stem)
+                .map(|stem| stem.to_string_lossy().to_string())
+                .unwrap_or_default();
+            return format!("{} (can't load)", folder).into_any_element();
+        }
         let asset = self.asset.read();
         format!(
             "{}{}",
             asset.display_name,
-            if self.modified { " •" } else { "" }
+            if self.is_dirty() { " •" } else { "" }
         )
         .into_any_element()
     }
@@ -236,60 +1234,41 @@ impl Panel for TraitEditor {
 // Plugin-related methods (called by TraitEditorWrapper)
 impl TraitEditor {
     pub fn plugin_save(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> Result<(), plugin_editor_api::PluginError> {
-        if let Some(file_path) = &self.file_path {
-            let asset = self.asset.read();
-            match serde_json::to_string_pretty(&*asset) {
-                Ok(json) => {
-                    drop(asset); // Release the read lock before writing
-                    std::fs::write(file_path, json)
-                        .map_err(|e| plugin_editor_api::PluginError::FileSaveError {
-                            path: file_path.clone(),
-                            message: e.to_string(),
-                        })?;
-                    self.error_message = None;
-                    self.modified = false;
-                    cx.emit(TraitEditorEvent::Saved);
-                    cx.notify();
-                    Ok(())
-                }
-                Err(e) => {
-                    Err(plugin_editor_api::PluginError::FileSaveError {
-                        path: file_path.clone(),
-                        message: format!("Failed to serialize: {}", e),
-                    })
-                }
-            }
-        } else {
-            Err(plugin_editor_api::PluginError::Other {
+        let Some(path) = self.file_path.clone() else {
+            return Err(plugin_editor_api::PluginError::Other {
                 message: "No file path set".into(),
-            })
-        }
+            });
+        };
+        self.write_to_disk(cx)
+            .map_err(|message| plugin_editor_api::PluginError::FileSaveError { path, message })
     }
 
     pub fn plugin_reload(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Result<(), plugin_editor_api::PluginError> {
-        if let Some(file_path) = &self.file_path {
+        if let Some(file_path) = self.file_path.clone() {
             match std::fs::read_to_string(&file_path) {
                 Ok(json_content) => {
-                    match serde_json::from_str::<ui_types_common::TraitAsset>(&json_content) {
+                    let state = DiskState::new(&file_path, &json_content);
+                    match schema::load_asset(&json_content) {
                         Ok(asset) => {
-                            *self.asset.write() = asset;
-                            self.error_message = None;
-                            self.modified = false;
-                            self.initialize_workspace(window, cx);
-                            cx.notify();
+                            self.take_theirs(asset, state, window, cx);
                             Ok(())
                         }
                         Err(e) => {
+                            // Protected like a file that fails to load when it is opened
+                            self.disk_state = Some(state);
+                            self.open_broken_file(Some(json_content), format!("Failed to load trait: {}", e), window, cx);
                             Err(plugin_editor_api::PluginError::FileLoadError {
-                                path: file_path.clone(),
-                                message: format!("Failed to parse: {}", e),
+                                path: file_path,
+                                message: e.to_string(),
                             })
                         }
                     }
                 }
                 Err(e) => {
+                    self.error_message = Some(format!("Failed to reload trait: {}", e));
+                    cx.notify();
                     Err(plugin_editor_api::PluginError::FileLoadError {
-                        path: file_path.clone(),
+                        path: file_path,
                         message: e.to_string(),
                     })
                 }
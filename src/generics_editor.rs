@@ -0,0 +1,374 @@
+use gpui::{prelude::FluentBuilder, *};
+use ui::{v_flex, h_flex, ActiveTheme, StyledExt, IconName, Sizable, button::{Button, ButtonVariants}, input::{InputState, TextInput}};
+use crate::ident::identifier_error;
+use crate::model::{split_bounds, GenericParam, Generics, WherePredicate};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ParamKind {
+    Lifetime,
+    Type,
+    Const,
+}
+
+impl ParamKind {
+    fn of(param: &GenericParam) -> Self {
+        match param {
+            GenericParam::Lifetime { .. } => ParamKind::Lifetime,
+            GenericParam::Type { .. } => ParamKind::Type,
+            GenericParam::Const { .. } => ParamKind::Const,
+        }
+    }
+
+    fn badge(&self) -> &'static str {
+        match self {
+            ParamKind::Lifetime => "'a",
+            ParamKind::Type => "T",
+            ParamKind::Const => "const",
+        }
+    }
+}
+
+/// Inputs of one generic parameter. For const params `bounds` holds the type.
+struct ParamRow {
+    kind: ParamKind,
+    name: Entity<InputState>,
+    bounds: Entity<InputState>,
+    default: Entity<InputState>,
+}
+
+struct PredicateRow {
+    bounded: Entity<InputState>,
+    bounds: Entity<InputState>,
+}
+
+#[derive(Clone, Debug)]
+pub enum GenericsEditorEvent {
+    Changed(Generics),
+}
+
+/// Edits the generic parameters and `where` predicates of a trait or a method
+pub struct GenericsEditor {
+    generics: Generics,
+    param_rows: Vec<ParamRow>,
+    predicate_rows: Vec<PredicateRow>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl GenericsEditor {
+    pub fn new(generics: Generics, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let mut editor = Self {
+            generics,
+            param_rows: Vec::new(),
+            predicate_rows: Vec::new(),
+            _subscriptions: Vec::new(),
+        };
+        editor.rebuild_rows(window, cx);
+        editor
+    }
+
+    /// Replaces the edited generics, e.g. after an undo. Inputs already holding an
+    /// equivalent value are left alone.
+    pub fn set_generics(&mut self, generics: Generics, window: &mut Window, cx: &mut Context<Self>) {
+        if self.read_rows(cx) == generics {
+            self.generics = generics;
+            return;
+        }
+
+        let same_shape = self.param_rows.len() == generics.params.len()
+            && self.predicate_rows.len() == generics.where_clause.len()
+            && self.param_rows.iter().zip(&generics.params).all(|(row, param)| row.kind == ParamKind::of(param));
+        self.generics = generics;
+        if !same_shape {
+            self.rebuild_rows(window, cx);
+            return;
+        }
+
+        let mut values = Vec::new();
+        for (row, param) in self.param_rows.iter().zip(&self.generics.params) {
+            let (name, bounds, default) = Self::param_texts(param);
+            values.push((row.name.clone(), name));
+            values.push((row.bounds.clone(), bounds));
+            values.push((row.default.clone(), default));
+        }
+        for (row, predicate) in self.predicate_rows.iter().zip(&self.generics.where_clause) {
+            values.push((row.bounded.clone(), predicate.bounded.clone()));
+            values.push((row.bounds.clone(), predicate.bounds.join(" + ")));
+        }
+        for (input, value) in values {
+            if input.read(cx).text().to_string() != value {
+                input.update(cx, |input, cx| input.set_value(value, window, cx));
+            }
+        }
+        cx.notify();
+    }
+
+    /// Name, bounds (or const type) and default of a parameter as shown in its inputs
+    fn param_texts(param: &GenericParam) -> (String, String, String) {
+        match param {
+            GenericParam::Lifetime { name, bounds } => (name.clone(), bounds.join(" + "), String::new()),
+            GenericParam::Type { name, bounds, default } => {
+                (name.clone(), bounds.join(" + "), default.clone().unwrap_or_default())
+            }
+            GenericParam::Const { name, ty, default } => {
+                (name.clone(), ty.clone(), default.clone().unwrap_or_default())
+            }
+        }
+    }
+
+    /// Recreates every input from the current generics
+    fn rebuild_rows(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.param_rows.clear();
+        self.predicate_rows.clear();
+        self._subscriptions.clear();
+
+        let params = self.generics.params.clone();
+        for param in &params {
+            let kind = ParamKind::of(param);
+            let (name, bounds, default) = Self::param_texts(param);
+            let bounds_placeholder = match kind {
+                ParamKind::Lifetime => "'b",
+                ParamKind::Type => "Clone + Send",
+                ParamKind::Const => "usize",
+            };
+            let row = ParamRow {
+                kind,
+                name: self.new_input("name", name, window, cx),
+                bounds: self.new_input(bounds_placeholder, bounds, window, cx),
+                default: self.new_input("default", default, window, cx),
+            };
+            self.param_rows.push(row);
+        }
+
+        let predicates = self.generics.where_clause.clone();
+        for predicate in &predicates {
+            let row = PredicateRow {
+                bounded: self.new_input("T::Item", predicate.bounded.clone(), window, cx),
+                bounds: self.new_input("Into<String>", predicate.bounds.join(" + "), window, cx),
+            };
+            self.predicate_rows.push(row);
+        }
+        cx.notify();
+    }
+
+    fn new_input(&mut self, placeholder: &'static str, value: String, window: &mut Window, cx: &mut Context<Self>) -> Entity<InputState> {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
+        input.update(cx, |input, cx| input.set_value(value, window, cx));
+
+        let subscription = cx.subscribe_in(&input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                let generics = this.read_rows(cx);
+                if generics != this.generics {
+                    this.generics = generics;
+                    cx.emit(GenericsEditorEvent::Changed(this.generics.clone()));
+                }
+                cx.notify();
+            }
+        });
+        self._subscriptions.push(subscription);
+        input
+    }
+
+    /// Builds the generics described by the inputs
+    fn read_rows(&self, cx: &App) -> Generics {
+        let text = |input: &Entity<InputState>| input.read(cx).text().to_string().trim().to_string();
+        let optional = |input: &Entity<InputState>| Some(text(input)).filter(|value| !value.is_empty());
+
+        let params = self
+            .param_rows
+            .iter()
+            .map(|row| match row.kind {
+                ParamKind::Lifetime => GenericParam::Lifetime {
+                    name: text(&row.name),
+                    bounds: split_bounds(&text(&row.bounds)),
+                },
+                ParamKind::Type => GenericParam::Type {
+                    name: text(&row.name),
+                    bounds: split_bounds(&text(&row.bounds)),
+                    default: optional(&row.default),
+                },
+                ParamKind::Const => GenericParam::Const {
+                    name: text(&row.name),
+                    ty: text(&row.bounds),
+                    default: optional(&row.default),
+                },
+            })
+            .collect();
+        let where_clause = self
+            .predicate_rows
+            .iter()
+            .map(|row| WherePredicate {
+                bounded: text(&row.bounded),
+                bounds: split_bounds(&text(&row.bounds)),
+            })
+            .collect();
+
+        Generics { params, where_clause }
+    }
+
+    fn add_param(&mut self, kind: ParamKind, window: &mut Window, cx: &mut Context<Self>) {
+        let taken = |name: &str| self.generics.params.iter().any(|param| param.name() == name);
+        let param = match kind {
+            ParamKind::Lifetime => {
+                let name = ('a'..='z').map(|c| format!("'{}", c)).find(|name| !taken(name)).unwrap_or_default();
+                GenericParam::Lifetime { name, bounds: Vec::new() }
+            }
+            ParamKind::Type => {
+                let name = ["T", "U", "V", "W", "K"].into_iter().find(|name| !taken(name)).unwrap_or_default();
+                GenericParam::Type { name: name.to_string(), bounds: Vec::new(), default: None }
+            }
+            ParamKind::Const => {
+                let name = ["N", "M", "L"].into_iter().find(|name| !taken(name)).unwrap_or_default();
+                GenericParam::Const { name: name.to_string(), ty: "usize".to_string(), default: None }
+            }
+        };
+        self.generics.params.push(param);
+        self.rebuild_rows(window, cx);
+        cx.emit(GenericsEditorEvent::Changed(self.generics.clone()));
+    }
+
+    fn remove_param(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if index < self.generics.params.len() {
+            self.generics.params.remove(index);
+            self.rebuild_rows(window, cx);
+            cx.emit(GenericsEditorEvent::Changed(self.generics.clone()));
+        }
+    }
+
+    fn add_predicate(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let bounded = self
+            .generics
+            .params
+            .iter()
+            .find(|param| matches!(param, GenericParam::Type { .. }))
+            .map(|param| param.name().to_string())
+            .unwrap_or_default();
+        self.generics.where_clause.push(WherePredicate { bounded, bounds: Vec::new() });
+        self.rebuild_rows(window, cx);
+        cx.emit(GenericsEditorEvent::Changed(self.generics.clone()));
+    }
+
+    fn remove_predicate(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if index < self.generics.where_clause.len() {
+            self.generics.where_clause.remove(index);
+            self.rebuild_rows(window, cx);
+            cx.emit(GenericsEditorEvent::Changed(self.generics.clone()));
+        }
+    }
+
+    fn name_error(row: &ParamRow, cx: &App) -> Option<String> {
+        let name = row.name.read(cx).text().to_string();
+        let name = name.trim();
+        match row.kind {
+            ParamKind::Lifetime => identifier_error(name.trim_start_matches('\'')),
+            ParamKind::Type | ParamKind::Const => identifier_error(name),
+        }
+    }
+}
+
+impl EventEmitter<GenericsEditorEvent> for GenericsEditor {}
+
+impl Render for GenericsEditor {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let id = cx.entity_id();
+        let add_button = |label: &'static str, kind: Option<ParamKind>, cx: &Context<Self>| {
+            Button::new(SharedString::from(format!("generics-add-{}-{}", label, id)))
+                .ghost()
+                .with_size(ui::Size::XSmall)
+                .icon(IconName::Plus)
+                .label(label)
+                .on_click(cx.listener(move |this, _, window, cx| match kind {
+                    Some(kind) => this.add_param(kind, window, cx),
+                    None => this.add_predicate(window, cx),
+                }))
+        };
+
+        v_flex()
+            .gap_2()
+            .child(
+                h_flex()
+                    .items_center()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .font_semibold()
+                            .text_color(cx.theme().muted_foreground)
+                            .child(format!("Generics ({})", self.generics.params.len()))
+                    )
+                    .child(add_button("Type", Some(ParamKind::Type), cx))
+                    .child(add_button("Lifetime", Some(ParamKind::Lifetime), cx))
+                    .child(add_button("Const", Some(ParamKind::Const), cx))
+                    .child(add_button("Where", None, cx))
+            )
+            .children(self.param_rows.iter().enumerate().map(|(ix, row)| {
+                let name_error = Self::name_error(row, cx);
+                h_flex()
+                    .gap_2()
+                    .p_1()
+                    .items_center()
+                    .rounded(px(4.0))
+                    .border_1()
+                    .border_color(if name_error.is_some() {
+                        cx.theme().danger
+                    } else {
+                        cx.theme().border.opacity(0.3)
+                    })
+                    .child(
+                        div()
+                            .text_xs()
+                            .px_1()
+                            .rounded(px(3.0))
+                            .bg(cx.theme().accent.opacity(0.2))
+                            .text_color(cx.theme().accent)
+                            .child(row.kind.badge())
+                    )
+                    .child(TextInput::new(&row.name).w(px(90.0)).with_size(ui::Size::Small))
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground).child(":"))
+                    .child(TextInput::new(&row.bounds).flex_1().with_size(ui::Size::Small))
+                    .when(row.kind != ParamKind::Lifetime, |this| {
+                        this.child(div().text_sm().text_color(cx.theme().muted_foreground).child("="))
+                            .child(TextInput::new(&row.default).w(px(90.0)).with_size(ui::Size::Small))
+                    })
+                    .child(
+                        Button::new(SharedString::from(format!("generics-remove-param-{}-{}", id, ix)))
+                            .ghost()
+                            .with_size(ui::Size::XSmall)
+                            .icon(IconName::Close)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.remove_param(ix, window, cx);
+                            }))
+                    )
+            }))
+            .when(!self.predicate_rows.is_empty(), |this| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .font_semibold()
+                        .text_color(cx.theme().muted_foreground)
+                        .child("where")
+                )
+            })
+            .children(self.predicate_rows.iter().enumerate().map(|(ix, row)| {
+                h_flex()
+                    .gap_2()
+                    .p_1()
+                    .items_center()
+                    .rounded(px(4.0))
+                    .border_1()
+                    .border_color(cx.theme().border.opacity(0.3))
+                    .child(TextInput::new(&row.bounded).w(px(120.0)).with_size(ui::Size::Small))
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground).child(":"))
+                    .child(TextInput::new(&row.bounds).flex_1().with_size(ui::Size::Small))
+                    .child(
+                        Button::new(SharedString::from(format!("generics-remove-where-{}-{}", id, ix)))
+                            .ghost()
+                            .with_size(ui::Size::XSmall)
+                            .icon(IconName::Close)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.remove_predicate(ix, window, cx);
+                            }))
+                    )
+            }))
+    }
+}
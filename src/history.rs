@@ -0,0 +1,341 @@
+//! Undo/redo history for trait edits
+//!
+//! Every mutation of the shared [`TraitAsset`] made by the editor panels goes through
+//! [`EditHistory::execute`] as a [`TraitCommand`]. Commands know how to apply and revert
+//! themselves, so undo and redo never need a full copy of the asset.
+
+use std::time::{Duration, Instant};
+use ui_types_common::{TraitAsset, TraitMethod, TypeKind};
+use crate::model::{self, MethodMeta, TraitMeta};
+
+/// Consecutive edits to the same text field within this window merge into one undo step
+const COALESCE_WINDOW: Duration = Duration::from_millis(1000);
+
+/// Oldest undo steps are dropped beyond this many entries
+const MAX_UNDO_STEPS: usize = 500;
+
+/// Fields picked from a fixed set of options; changing them is never a typing burst
+const CHOICE_FIELDS: &[&str] = &["receiver", "kind", "visibility"];
+
+/// A single reversible edit of a trait asset
+#[derive(Clone, Debug)]
+pub enum TraitCommand {
+    SetName { old: String, new: String },
+    SetDisplayName { old: String, new: String },
+    SetDescription { old: Option<String>, new: Option<String> },
+    InsertMethod { index: usize, method: TraitMethod, meta: MethodMeta },
+    RemoveMethod { index: usize, method: TraitMethod, meta: MethodMeta },
+    ReplaceMethod {
+        index: usize,
+        old: TraitMethod,
+        new: TraitMethod,
+        old_meta: MethodMeta,
+        new_meta: MethodMeta,
+    },
+    /// Replaces the trait-level editor data (generics and the like)
+    SetTraitMeta { old: TraitMeta, new: TraitMeta },
+    /// Replaces the fields describing the asset file rather than the trait
+    SetFormat { old: AssetFormat, new: AssetFormat },
+    /// Several commands applied in order as one undo step
+    Batch(Vec<TraitCommand>),
+}
+
+/// Schema version and kind of an asset, which no panel edits but a replaced asset may change
+#[derive(Clone, Debug, serde::Serialize)]
+pub struct AssetFormat {
+    pub schema_version: u32,
+    pub type_kind: TypeKind,
+}
+
+impl AssetFormat {
+    pub fn from_asset(asset: &TraitAsset) -> Self {
+        Self { schema_version: asset.schema_version, type_kind: asset.type_kind.clone() }
+    }
+
+    pub fn write_to(&self, asset: &mut TraitAsset) {
+        asset.schema_version = self.schema_version;
+        asset.type_kind = self.type_kind.clone();
+    }
+}
+
+impl TraitCommand {
+    /// A single step turning `current` into a copy of `new`, e.g. for an import
+    pub fn replace_asset(current: &TraitAsset, new: &TraitAsset) -> TraitCommand {
+        let current_meta = TraitMeta::from_asset(current);
+        let new_meta = TraitMeta::from_asset(new);
+
+        let mut commands = vec![
+            TraitCommand::SetFormat { old: AssetFormat::from_asset(current), new: AssetFormat::from_asset(new) },
+            TraitCommand::SetName { old: current.name.clone(), new: new.name.clone() },
+            TraitCommand::SetDisplayName { old: current.display_name.clone(), new: new.display_name.clone() },
+            TraitCommand::SetDescription { old: current.description.clone(), new: new.description.clone() },
+        ];
+        for (index, method) in current.methods.iter().enumerate().rev() {
+            commands.push(TraitCommand::RemoveMethod {
+                index,
+                method: method.clone(),
+                meta: current_meta.method(index),
+            });
+        }
+        for (index, method) in new.methods.iter().enumerate() {
+            commands.push(TraitCommand::InsertMethod { index, method: method.clone(), meta: new_meta.method(index) });
+        }
+        // The method entries are already in place once the inserts have run
+        let old = TraitMeta { methods: new_meta.methods.clone(), ..current_meta };
+        commands.push(TraitCommand::SetTraitMeta { old, new: new_meta });
+        TraitCommand::Batch(commands)
+    }
+
+    pub fn apply(&self, asset: &mut TraitAsset) {
+        match self {
+            TraitCommand::SetName { new, .. } => asset.name = new.clone(),
+            TraitCommand::SetDisplayName { new, .. } => asset.display_name = new.clone(),
+            TraitCommand::SetDescription { new, .. } => asset.description = new.clone(),
+            TraitCommand::InsertMethod { index, method, meta } => {
+                let index = (*index).min(asset.methods.len());
+                asset.methods.insert(index, method.clone());
+                model::insert_method_meta(asset, index, meta.clone());
+            }
+            TraitCommand::RemoveMethod { index, .. } => {
+                if *index < asset.methods.len() {
+                    model::remove_method_meta(asset, *index);
+                    asset.methods.remove(*index);
+                }
+            }
+            TraitCommand::ReplaceMethod { index, new, new_meta, .. } => {
+                if let Some(method) = asset.methods.get_mut(*index) {
+                    *method = new.clone();
+                    model::set_method_meta(asset, *index, new_meta.clone());
+                }
+            }
+            TraitCommand::SetTraitMeta { new, .. } => new.write_to(asset),
+            TraitCommand::SetFormat { new, .. } => new.write_to(asset),
+            TraitCommand::Batch(commands) => {
+                for command in commands {
+                    command.apply(asset);
+                }
+            }
+        }
+    }
+
+    pub fn revert(&self, asset: &mut TraitAsset) {
+        self.inverse().apply(asset);
+    }
+
+    /// The command that undoes this one
+    fn inverse(&self) -> TraitCommand {
+        match self {
+            TraitCommand::SetName { old, new } => TraitCommand::SetName { old: new.clone(), new: old.clone() },
+            TraitCommand::SetDisplayName { old, new } => TraitCommand::SetDisplayName { old: new.clone(), new: old.clone() },
+            TraitCommand::SetDescription { old, new } => TraitCommand::SetDescription { old: new.clone(), new: old.clone() },
+            TraitCommand::InsertMethod { index, method, meta } => TraitCommand::RemoveMethod { index: *index, method: method.clone(), meta: meta.clone() },
+            TraitCommand::RemoveMethod { index, method, meta } => TraitCommand::InsertMethod { index: *index, method: method.clone(), meta: meta.clone() },
+            TraitCommand::ReplaceMethod { index, old, new, old_meta, new_meta } => TraitCommand::ReplaceMethod {
+                index: *index,
+                old: new.clone(),
+                new: old.clone(),
+                old_meta: new_meta.clone(),
+                new_meta: old_meta.clone(),
+            },
+            TraitCommand::SetTraitMeta { old, new } => TraitCommand::SetTraitMeta { old: new.clone(), new: old.clone() },
+            TraitCommand::SetFormat { old, new } => TraitCommand::SetFormat { old: new.clone(), new: old.clone() },
+            TraitCommand::Batch(commands) => TraitCommand::Batch(commands.iter().rev().map(TraitCommand::inverse).collect()),
+        }
+    }
+
+    /// Whether applying the command would leave the asset unchanged
+    fn is_noop(&self) -> bool {
+        match self {
+            TraitCommand::SetName { old, new } | TraitCommand::SetDisplayName { old, new } => old == new,
+            TraitCommand::SetDescription { old, new } => old == new,
+            TraitCommand::InsertMethod { .. } | TraitCommand::RemoveMethod { .. } => false,
+            TraitCommand::ReplaceMethod { old, new, old_meta, new_meta, .. } => {
+                old_meta == new_meta && to_json(old) == to_json(new)
+            }
+            TraitCommand::SetTraitMeta { old, new } => old == new,
+            TraitCommand::SetFormat { old, new } => to_json(old) == to_json(new),
+            TraitCommand::Batch(commands) => commands.iter().all(TraitCommand::is_noop),
+        }
+    }
+
+    /// Identifies the text field a command edits, if it is a plain text edit.
+    ///
+    /// Commands with equal keys issued in quick succession are typing bursts and
+    /// coalesce into a single undo step. Structural edits have no key.
+    fn coalesce_key(&self) -> Option<String> {
+        match self {
+            TraitCommand::SetName { .. } => Some("name".to_string()),
+            TraitCommand::SetDisplayName { .. } => Some("display_name".to_string()),
+            TraitCommand::SetDescription { .. } => Some("description".to_string()),
+            TraitCommand::InsertMethod { .. }
+            | TraitCommand::RemoveMethod { .. }
+            | TraitCommand::SetFormat { .. }
+            | TraitCommand::Batch(_) => None,
+            TraitCommand::ReplaceMethod { index, old, new, old_meta, new_meta } => {
+                text_edit_path(&to_json(&(old, old_meta)), &to_json(&(new, new_meta)), String::new())
+                    .map(|path| format!("methods/{}{}", index, path))
+            }
+            TraitCommand::SetTraitMeta { old, new } => {
+                text_edit_path(&to_json(old), &to_json(new), String::new()).map(|path| format!("meta{}", path))
+            }
+        }
+    }
+
+    /// Folds a later edit of the same field into this one, keeping the original old value
+    fn absorb(&mut self, next: TraitCommand) {
+        match (self, next) {
+            (TraitCommand::SetName { new, .. }, TraitCommand::SetName { new: next, .. })
+            | (TraitCommand::SetDisplayName { new, .. }, TraitCommand::SetDisplayName { new: next, .. }) => *new = next,
+            (TraitCommand::SetDescription { new, .. }, TraitCommand::SetDescription { new: next, .. }) => *new = next,
+            (
+                TraitCommand::ReplaceMethod { new, new_meta, .. },
+                TraitCommand::ReplaceMethod { new: next, new_meta: next_meta, .. },
+            ) => {
+                *new = next;
+                *new_meta = next_meta;
+            }
+            (TraitCommand::SetTraitMeta { new, .. }, TraitCommand::SetTraitMeta { new: next, .. }) => *new = next,
+            _ => {}
+        }
+    }
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> serde_json::Value {
+    serde_json::to_value(value).unwrap_or(serde_json::Value::Null)
+}
+
+/// Returns the path of the single text leaf that differs between `old` and `new`,
+/// or `None` if anything other than exactly one string changed.
+fn text_edit_path(old: &serde_json::Value, new: &serde_json::Value, path: String) -> Option<String> {
+    use serde_json::Value;
+
+    match (old, new) {
+        (Value::String(_), Value::String(_))
+        | (Value::Null, Value::String(_))
+        | (Value::String(_), Value::Null) => {
+            let field = path.rsplit('/').next().unwrap_or_default();
+            (!CHOICE_FIELDS.contains(&field)).then_some(path)
+        }
+        (Value::Object(old), Value::Object(new)) => {
+            if old.len() != new.len() || old.keys().any(|key| !new.contains_key(key)) {
+                return None;
+            }
+            let mut changed = old.iter().filter(|(key, value)| new.get(key.as_str()) != Some(*value));
+            let (key, value) = changed.next()?;
+            if changed.next().is_some() {
+                return None;
+            }
+            text_edit_path(value, &new[key.as_str()], format!("{}/{}", path, key))
+        }
+        (Value::Array(old), Value::Array(new)) => {
+            if old.len() != new.len() {
+                return None;
+            }
+            let mut changed = old.iter().zip(new.iter()).enumerate().filter(|(_, (a, b))| a != b);
+            let (index, (a, b)) = changed.next()?;
+            if changed.next().is_some() {
+                return None;
+            }
+            text_edit_path(a, b, format!("{}/{}", path, index))
+        }
+        _ => None,
+    }
+}
+
+struct HistoryEntry {
+    command: TraitCommand,
+    coalesce_key: Option<String>,
+    at: Instant,
+}
+
+/// Undo and redo stacks of [`TraitCommand`]s
+#[derive(Default)]
+pub struct EditHistory {
+    undo_stack: Vec<HistoryEntry>,
+    redo_stack: Vec<TraitCommand>,
+    // Set after undo/redo so the next edit always starts a fresh step
+    sealed: bool,
+}
+
+impl EditHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies `command` to `asset` and records it. Returns `false` if it changed nothing.
+    pub fn execute(&mut self, command: TraitCommand, asset: &mut TraitAsset) -> bool {
+        if command.is_noop() {
+            return false;
+        }
+
+        command.apply(asset);
+        self.redo_stack.clear();
+
+        let coalesce_key = command.coalesce_key();
+        let now = Instant::now();
+        if !self.sealed {
+            if let Some(top) = self.undo_stack.last_mut() {
+                if coalesce_key.is_some()
+                    && top.coalesce_key == coalesce_key
+                    && now.duration_since(top.at) < COALESCE_WINDOW
+                {
+                    top.command.absorb(command);
+                    top.at = now;
+                    if top.command.is_noop() {
+                        self.undo_stack.pop();
+                    }
+                    return true;
+                }
+            }
+        }
+
+        self.sealed = false;
+        self.undo_stack.push(HistoryEntry { command, coalesce_key, at: now });
+        if self.undo_stack.len() > MAX_UNDO_STEPS {
+            self.undo_stack.remove(0);
+        }
+        true
+    }
+
+    /// Reverts the most recent step. Returns `false` if there was nothing to undo.
+    pub fn undo(&mut self, asset: &mut TraitAsset) -> bool {
+        let Some(entry) = self.undo_stack.pop() else {
+            return false;
+        };
+        entry.command.revert(asset);
+        self.redo_stack.push(entry.command);
+        self.sealed = true;
+        true
+    }
+
+    /// Re-applies the most recently undone step. Returns `false` if there was nothing to redo.
+    pub fn redo(&mut self, asset: &mut TraitAsset) -> bool {
+        let Some(command) = self.redo_stack.pop() else {
+            return false;
+        };
+        command.apply(asset);
+        let coalesce_key = command.coalesce_key();
+        self.undo_stack.push(HistoryEntry { command, coalesce_key, at: Instant::now() });
+        self.sealed = true;
+        true
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Forces the next edit to start a new undo step even if it continues a typing burst
+    pub fn seal(&mut self) {
+        self.sealed = true;
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.sealed = false;
+    }
+}
@@ -0,0 +1,96 @@
+//! Rust identifier and keyword rules
+
+/// Keywords that can never be used as plain identifiers, including reserved ones
+pub const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "gen", "if", "impl", "in", "let", "loop", "match", "mod",
+    "move", "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super",
+    "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+/// Keywords that stay invalid even in raw form (`r#self` is not allowed)
+const NON_RAW_KEYWORDS: &[&str] = &["crate", "self", "Self", "super", "_"];
+
+pub fn is_keyword(name: &str) -> bool {
+    RUST_KEYWORDS.contains(&name)
+}
+
+/// Whether `name` is usable as an identifier: XID rules approximated with Unicode
+/// alphanumerics, no keyword collisions, and raw identifiers (`r#type`) allowed.
+pub fn is_valid_identifier(name: &str) -> bool {
+    if let Some(raw) = name.strip_prefix("r#") {
+        return has_identifier_shape(raw) && !NON_RAW_KEYWORDS.contains(&raw);
+    }
+    name != "_" && has_identifier_shape(name) && !is_keyword(name)
+}
+
+/// Whether `name` is a valid pattern for a parameter: an identifier or a `_` placeholder
+pub fn is_valid_param_name(name: &str) -> bool {
+    name == "_" || is_valid_identifier(name)
+}
+
+fn has_identifier_shape(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(first) if first == '_' || first.is_alphabetic() => {}
+        _ => return false,
+    }
+    chars.all(|c| c == '_' || c.is_alphanumeric())
+}
+
+/// Human-readable reason `name` is not a valid identifier, if it isn't
+pub fn identifier_error(name: &str) -> Option<String> {
+    if name.is_empty() {
+        Some("Name cannot be empty".to_string())
+    } else if is_keyword(name) {
+        Some(format!("`{}` is a Rust keyword", name))
+    } else if !is_valid_identifier(name) {
+        Some(format!("`{}` is not a valid Rust identifier", name))
+    } else {
+        None
+    }
+}
+
+/// Whether `name` follows the `UpperCamelCase` convention for types and traits
+pub fn is_upper_camel_case(name: &str) -> bool {
+    let name = name.strip_prefix("r#").unwrap_or(name).trim_matches('_');
+    name.chars().next().is_some_and(|first| !first.is_lowercase()) && !name.contains('_')
+}
+
+/// Whether `name` follows the `snake_case` convention for functions and variables
+pub fn is_snake_case(name: &str) -> bool {
+    let name = name.strip_prefix("r#").unwrap_or(name);
+    !name.chars().any(char::is_uppercase) && !name.trim_start_matches('_').contains("__")
+}
+
+/// `doThing` and `DoThing` become `do_thing`
+pub fn to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() {
+            if prev_lower {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+            prev_lower = false;
+        } else {
+            prev_lower = c.is_lowercase() || c.is_ascii_digit();
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+/// `do_thing` becomes `DoThing`
+pub fn to_upper_camel_case(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            chars.next().map(|first| first.to_uppercase().chain(chars).collect::<String>()).unwrap_or_default()
+        })
+        .collect()
+}
@@ -0,0 +1,287 @@
+use gpui::{prelude::FluentBuilder, *};
+use ui::{v_flex, h_flex, ActiveTheme, StyledExt, Sizable, button::{Button, ButtonVariants}, input::{InputState, TextInput}};
+use ui_types_common::TraitAsset;
+use std::path::PathBuf;
+use std::sync::Arc;
+use crate::rust_import::{find_traits, ImportedTrait};
+use crate::workspace_panels::rust_code_input;
+
+actions!(import_dialog, [
+    Dismiss,
+]);
+
+/// Key context set on the import dialog
+pub const IMPORT_DIALOG_CONTEXT: &str = "RustImportDialog";
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("escape", Dismiss, Some(IMPORT_DIALOG_CONTEXT)),
+    ]);
+}
+
+#[derive(Clone, Debug)]
+pub enum RustImportEvent {
+    Imported(Arc<TraitAsset>),
+    Dismissed,
+}
+
+/// Dialog that converts a trait from Rust source into a trait asset.
+///
+/// The source is pasted into the editor or loaded from a `.rs` file, and re-parsed on
+/// every change. Every trait found is listed along with what its import would lose.
+pub struct RustImportDialog {
+    path_input: Entity<InputState>,
+    source_input: Entity<InputState>,
+    found: Vec<ImportedTrait>,
+    selected: usize,
+    /// Why the file couldn't be read or the source couldn't be parsed
+    error: Option<String>,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl RustImportDialog {
+    /// `directory` pre-fills the path input, usually the project root
+    pub fn new(directory: Option<PathBuf>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let path_input = cx.new(|cx| InputState::new(window, cx).placeholder("path/to/file.rs"));
+        if let Some(directory) = directory {
+            let mut prefix = directory.to_string_lossy().to_string();
+            prefix.push(std::path::MAIN_SEPARATOR);
+            path_input.update(cx, |input, cx| input.set_value(prefix, window, cx));
+        }
+        let source_input = cx.new(|cx| rust_code_input(window, cx));
+
+        let path_subscription = cx.subscribe_in(&path_input, window, |this, _state, event: &ui::input::InputEvent, window, cx| {
+            if let ui::input::InputEvent::PressEnter { .. } = event {
+                this.load_file(window, cx);
+            }
+        });
+        let source_subscription = cx.subscribe_in(&source_input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                this.reparse(cx);
+            }
+        });
+
+        Self {
+            path_input,
+            source_input,
+            found: Vec::new(),
+            selected: 0,
+            error: None,
+            focus_handle: cx.focus_handle(),
+            _subscriptions: vec![path_subscription, source_subscription],
+        }
+    }
+
+    pub fn focus(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let handle = self.source_input.read(cx).focus_handle(cx);
+        window.focus(&handle);
+    }
+
+    fn load_file(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let path = PathBuf::from(self.path_input.read(cx).text().to_string().trim());
+        match std::fs::read_to_string(&path) {
+            Ok(source) => {
+                self.source_input.update(cx, |input, cx| input.set_value(source, window, cx));
+                self.reparse(cx);
+            }
+            Err(e) => {
+                self.error = Some(format!("Failed to read {}: {}", path.display(), e));
+                cx.notify();
+            }
+        }
+    }
+
+    fn reparse(&mut self, cx: &mut Context<Self>) {
+        let source = self.source_input.read(cx).text().to_string();
+        match find_traits(&source) {
+            Ok(found) => {
+                self.error = (found.is_empty() && !source.trim().is_empty()).then(|| "No traits found".to_string());
+                self.found = found;
+            }
+            Err(e) => {
+                self.error = Some(e);
+                self.found.clear();
+            }
+        }
+        self.selected = self.selected.min(self.found.len().saturating_sub(1));
+        cx.notify();
+    }
+
+    fn import(&mut self, cx: &mut Context<Self>) {
+        if self.selected < self.found.len() {
+            let imported = self.found.remove(self.selected);
+            for warning in &imported.warnings {
+                tracing::warn!("Import of `{}`: {}", imported.name, warning);
+            }
+            cx.emit(RustImportEvent::Imported(Arc::new(imported.asset)));
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(RustImportEvent::Dismissed);
+    }
+}
+
+impl EventEmitter<RustImportEvent> for RustImportDialog {}
+
+impl Focusable for RustImportDialog {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for RustImportDialog {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected_bg = cx.theme().accent.opacity(0.2);
+        let hover_bg = cx.theme().secondary;
+
+        let rows = self.found.iter().enumerate().map(|(ix, imported)| {
+            let is_selected = ix == self.selected;
+            let method_count = imported.asset.methods.len();
+            h_flex()
+                .id(("imported-trait", ix))
+                .px_2()
+                .py_1()
+                .gap_2()
+                .rounded(px(4.0))
+                .cursor_pointer()
+                .when(is_selected, |this| this.bg(selected_bg))
+                .hover(|this| this.bg(hover_bg))
+                .on_click(cx.listener(move |this, _, _window, cx| {
+                    this.selected = ix;
+                    cx.notify();
+                }))
+                .child(
+                    div()
+                        .flex_1()
+                        .text_sm()
+                        .font_family("monospace")
+                        .text_color(cx.theme().foreground)
+                        .child(imported.name.clone())
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(format!("{} method{}", method_count, if method_count == 1 { "" } else { "s" }))
+                )
+                .when(!imported.warnings.is_empty(), |this| {
+                    this.child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().warning)
+                            .child(format!("{} warning{}", imported.warnings.len(), if imported.warnings.len() == 1 { "" } else { "s" }))
+                    )
+                })
+        }).collect::<Vec<_>>();
+        let warnings = self.found.get(self.selected).map(|imported| imported.warnings.clone()).unwrap_or_default();
+
+        v_flex()
+            .key_context(IMPORT_DIALOG_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::dismiss))
+            .w(px(560.0))
+            .max_h(px(720.0))
+            .p_3()
+            .gap_2()
+            .bg(cx.theme().popover)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(px(8.0))
+            .shadow_lg()
+            .child(
+                div()
+                    .text_sm()
+                    .font_semibold()
+                    .text_color(cx.theme().foreground)
+                    .child("Import from Rust")
+            )
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(div().flex_1().child(TextInput::new(&self.path_input).with_size(ui::Size::Small)))
+                    .child(
+                        Button::new("import-load-file")
+                            .ghost()
+                            .label("Load")
+                            .with_size(ui::Size::Small)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.load_file(window, cx);
+                            }))
+                    )
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Or paste Rust source:")
+            )
+            .child(
+                TextInput::new(&self.source_input)
+                    .w_full()
+                    .h(px(200.0))
+            )
+            .when_some(self.error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().danger)
+                        .child(error)
+                )
+            })
+            .child(
+                v_flex()
+                    .id("imported-traits")
+                    .max_h(px(160.0))
+                    .overflow_y_scroll()
+                    .children(rows)
+            )
+            .when(!warnings.is_empty(), |this| {
+                this.child(
+                    v_flex()
+                        .id("import-warnings")
+                        .max_h(px(120.0))
+                        .overflow_y_scroll()
+                        .gap_1()
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("Kept in the trait's meta rather than the editor fields:")
+                        )
+                        .children(warnings.into_iter().map(|warning| {
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().warning)
+                                .child(warning)
+                        }))
+                )
+            })
+            .child(
+                h_flex()
+                    .justify_end()
+                    .gap_1()
+                    .child(
+                        Button::new("import-cancel")
+                            .ghost()
+                            .label("Cancel")
+                            .with_size(ui::Size::Small)
+                            .on_click(cx.listener(|_this, _, _window, cx| {
+                                cx.emit(RustImportEvent::Dismissed);
+                            }))
+                    )
+                    .when(!self.found.is_empty(), |this| {
+                        this.child(
+                            Button::new("import-confirm")
+                                .primary()
+                                .label("Import")
+                                .with_size(ui::Size::Small)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.import(cx);
+                                }))
+                        )
+                    })
+            )
+    }
+}
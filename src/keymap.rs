@@ -0,0 +1,128 @@
+//! Key bindings for the trait editor
+//!
+//! All bindings are scoped to the [`KEY_CONTEXT`] set on the editor's root element.
+//! Users can rebind or unbind any action with a `trait_editor_keymap.json` file in their
+//! Pulsar config directory (`$PULSAR_CONFIG_DIR`, or `~/.pulsar` when unset):
+//!
+//! ```json
+//! {
+//!     "Save": "ctrl-alt-s",
+//!     "TogglePreview": null
+//! }
+//! ```
+//!
+//! A `null` entry removes the default binding for that action.
+
+use gpui::{App, KeyBinding, Keystroke};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::editor::{AddMethod, ImportFromRust, Redo, RegenerateAllTraits, Save, TogglePreview, Undo};
+use crate::method_editor::{MoveParamDown, MoveParamUp};
+
+/// Key context set on the trait editor's root element
+pub const KEY_CONTEXT: &str = "TraitEditor";
+
+/// Name of the user keymap file inside the Pulsar config directory
+pub const KEYMAP_FILE_NAME: &str = "trait_editor_keymap.json";
+
+/// Default keystrokes, keyed by action name
+pub const DEFAULT_KEYMAP: &[(&str, &str)] = &[
+    ("Save", "secondary-s"),
+    ("AddMethod", "secondary-shift-m"),
+    ("TogglePreview", "secondary-shift-p"),
+    ("Undo", "secondary-z"),
+    ("Redo", "secondary-shift-z"),
+    ("ImportFromRust", "secondary-shift-i"),
+    ("RegenerateAllTraits", "secondary-shift-g"),
+    ("MoveParamUp", "alt-up"),
+    ("MoveParamDown", "alt-down"),
+];
+
+/// Registers the trait editor's key bindings, applying the user's overrides on top of
+/// [`DEFAULT_KEYMAP`]. Only the first call has an effect.
+pub fn init(cx: &mut App) {
+    static INIT: std::sync::Once = std::sync::Once::new();
+    INIT.call_once(|| {
+        let overrides = keymap_file_path()
+            .map(|path| load_overrides(&path))
+            .unwrap_or_default();
+        cx.bind_keys(resolve_bindings(&overrides));
+        crate::type_picker::init(cx);
+        crate::import_dialog::init(cx);
+        crate::template_wizard::init(cx);
+    });
+}
+
+/// Merges `overrides` into the defaults and builds the resulting bindings
+fn resolve_bindings(overrides: &HashMap<String, Option<String>>) -> Vec<KeyBinding> {
+    let mut keymap: Vec<(String, Option<String>)> = DEFAULT_KEYMAP
+        .iter()
+        .map(|(action, keystrokes)| (action.to_string(), Some(keystrokes.to_string())))
+        .collect();
+
+    for (action, keystrokes) in overrides {
+        match keymap.iter_mut().find(|(name, _)| name == action) {
+            Some(entry) => entry.1 = keystrokes.clone(),
+            None => tracing::warn!("Unknown trait editor action '{}' in {}", action, KEYMAP_FILE_NAME),
+        }
+    }
+
+    keymap
+        .into_iter()
+        .filter_map(|(action, keystrokes)| {
+            let keystrokes = keystrokes?;
+            let binding = key_binding(&action, &keystrokes);
+            if binding.is_none() {
+                tracing::warn!("Invalid keystrokes '{}' for trait editor action '{}'", keystrokes, action);
+            }
+            binding
+        })
+        .collect()
+}
+
+fn key_binding(action: &str, keystrokes: &str) -> Option<KeyBinding> {
+    if keystrokes.split_whitespace().next().is_none()
+        || keystrokes.split_whitespace().any(|keystroke| Keystroke::parse(keystroke).is_err())
+    {
+        return None;
+    }
+
+    let context = Some(KEY_CONTEXT);
+    Some(match action {
+        "Save" => KeyBinding::new(keystrokes, Save, context),
+        "AddMethod" => KeyBinding::new(keystrokes, AddMethod, context),
+        "TogglePreview" => KeyBinding::new(keystrokes, TogglePreview, context),
+        "Undo" => KeyBinding::new(keystrokes, Undo, context),
+        "Redo" => KeyBinding::new(keystrokes, Redo, context),
+        "ImportFromRust" => KeyBinding::new(keystrokes, ImportFromRust, context),
+        "RegenerateAllTraits" => KeyBinding::new(keystrokes, RegenerateAllTraits, context),
+        "MoveParamUp" => KeyBinding::new(keystrokes, MoveParamUp, context),
+        "MoveParamDown" => KeyBinding::new(keystrokes, MoveParamDown, context),
+        _ => return None,
+    })
+}
+
+fn keymap_file_path() -> Option<PathBuf> {
+    let config_dir = std::env::var_os("PULSAR_CONFIG_DIR")
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME")
+                .or_else(|| std::env::var_os("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".pulsar"))
+        })?;
+    Some(config_dir.join(KEYMAP_FILE_NAME))
+}
+
+fn load_overrides(path: &Path) -> HashMap<String, Option<String>> {
+    let Ok(content) = std::fs::read_to_string(path) else {
+        return HashMap::new();
+    };
+
+    match serde_json::from_str(&content) {
+        Ok(overrides) => overrides,
+        Err(e) => {
+            tracing::warn!("Ignoring invalid keymap file {:?}: {}", path, e);
+            HashMap::new()
+        }
+    }
+}
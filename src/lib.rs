@@ -14,7 +14,6 @@
 //! - **Trait Editor**: Multi-panel editor with properties, methods, and code preview
 
 use plugin_editor_api::*;
-use serde_json::json;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex;
@@ -23,14 +22,50 @@ use gpui::*;
 use ui::dock::PanelView;
 
 // Trait Editor modules
+mod broken_file;
+mod codegen;
+mod disk_sync;
 mod editor;
+mod generics_editor;
+mod history;
+mod ident;
+mod import_dialog;
+mod keymap;
 mod method_editor;
+mod model;
+mod object_safety;
+mod output;
+mod persistence;
+mod rust_import;
+mod schema;
+mod template_wizard;
+mod templates;
+mod type_expr;
+mod type_picker;
+mod validation;
 mod workspace_panels;
 
 // Re-export main types
+pub use broken_file::{BrokenFileView, BrokenFileEvent};
+pub use codegen::{generate, generate_trait, CodegenMode, CodegenOptions};
+pub use disk_sync::{merge, Merge};
 pub use editor::TraitEditor;
+pub use generics_editor::{GenericsEditor, GenericsEditorEvent};
+pub use history::{AssetFormat, EditHistory, TraitCommand};
+pub use import_dialog::{RustImportDialog, RustImportEvent};
 pub use method_editor::{MethodEditorView, MethodEditorEvent};
-pub use workspace_panels::{PropertiesPanel, MethodsPanel, CodePreviewPanel};
+pub use model::{asset_snapshot, AssociatedConst, AssociatedType, GenericParam, Generics, MethodMeta, Receiver, TraitMeta, Visibility, WherePredicate};
+pub use object_safety::{violations, QuickFix, Violation, ViolationKind};
+pub use output::{output_file, write_output, OutputStatus, RegenerateSummary};
+pub use persistence::{backups, pending_recovery, recovery_path, save, write_atomic, write_recovery, MAX_BACKUPS};
+pub use rust_import::{find_traits, ImportedTrait};
+pub use schema::{asset_to_json, load_asset, syntax_error_location, LoadError, CURRENT_SCHEMA_VERSION, PRESERVED_FIELDS_KEY};
+pub use template_wizard::{NewTraitEvent, NewTraitWizard};
+pub use templates::TraitTemplate;
+pub use type_expr::TypeExpr;
+pub use type_picker::{TypePicker, TypePickerEvent, TypeSlot};
+pub use validation::{Diagnostic, DiagnosticTarget, Severity};
+pub use workspace_panels::{PropertiesPanel, MethodsPanel, AssociatedItemsPanel, CodePreviewPanel, DiagnosticsPanel};
 
 /// Storage for editor instances owned by the plugin
 struct EditorStorage {
@@ -76,10 +111,7 @@ impl EditorPlugin for TraitEditorPlugin {
                     marker_file: "trait.json".to_string(),
                     template_structure: vec![],
                 },
-                default_content: json!({
-                    "name": "NewTrait",
-                    "methods": []
-                }),
+                default_content: serde_json::to_value(templates::empty_asset()).unwrap_or_default(),
                 categories: vec!["Types".to_string()],
             }
         ]
@@ -103,6 +135,8 @@ impl EditorPlugin for TraitEditorPlugin {
     ) -> Result<(Arc<dyn PanelView>, Box<dyn EditorInstance>), PluginError> {
         logger.info("TRAIT EDITOR LOADED!!");
         if editor_id.as_str() == "trait-editor" {
+            keymap::init(cx);
+
             let actual_path = if file_path.is_dir() {
                 file_path.join("trait.json")
             } else {
@@ -111,9 +145,11 @@ impl EditorPlugin for TraitEditorPlugin {
 
             let panel = cx.new(|cx| TraitEditor::new_with_file(actual_path.clone(), window, cx));
             let panel_arc: Arc<dyn ui::dock::PanelView> = Arc::new(panel.clone());
+            let modified = panel.read(cx).modified_flag();
             let wrapper = Box::new(TraitEditorWrapper {
                 panel: panel.into(),
                 file_path: file_path.clone(),
+                modified,
             });
 
             let id = {
@@ -151,6 +187,7 @@ impl EditorPlugin for TraitEditorPlugin {
 pub struct TraitEditorWrapper {
     panel: Entity<TraitEditor>,
     file_path: std::path::PathBuf,
+    modified: Arc<parking_lot::Mutex<bool>>,
 }
 
 impl plugin_editor_api::EditorInstance for TraitEditorWrapper {
@@ -171,7 +208,7 @@ impl plugin_editor_api::EditorInstance for TraitEditorWrapper {
     }
 
     fn is_dirty(&self) -> bool {
-        false
+        *self.modified.lock()
     }
 
     fn as_any(&self) -> &dyn std::any::Any {
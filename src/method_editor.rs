@@ -1,36 +1,87 @@
 use gpui::{prelude::*, InteractiveElement as _, StatefulInteractiveElement as _, *};
-use ui::{v_flex, h_flex, ActiveTheme, StyledExt, IconName, Icon, Sizable, button::{Button, ButtonVariants}, input::{InputState, TextInput}};
+use ui::{v_flex, h_flex, ActiveTheme, StyledExt, IconName, Icon, Sizable, button::{Button, ButtonVariants}, checkbox::Checkbox, input::{InputState, TextInput}};
 use ui_types_common::{TraitMethod, MethodSignature, MethodParam, TypeRef};
+use crate::ident::{identifier_error, is_valid_param_name};
+use crate::generics_editor::{GenericsEditor, GenericsEditorEvent};
+use crate::model::{attribute_body, MethodMeta, Receiver};
+use crate::workspace_panels::rust_code_input;
+use crate::type_picker::TypeSlot;
+
+actions!(method_editor, [
+    MoveParamUp,
+    MoveParamDown,
+]);
+
+/// Key context set on each parameter row
+pub const PARAM_CONTEXT: &str = "MethodParam";
+
+/// Common method attributes offered as presets
+const ATTRIBUTE_PRESETS: &[&str] = &["must_use", "inline", "deprecated(note = \"\")", "cfg(feature = \"\")"];
+
+/// Payload carried while a parameter row is dragged
+#[derive(Clone)]
+pub struct DraggedParam {
+    method_index: usize,
+    param_index: usize,
+    label: SharedString,
+}
+
+impl Render for DraggedParam {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_1()
+            .rounded(px(4.0))
+            .bg(cx.theme().secondary)
+            .border_1()
+            .border_color(cx.theme().accent)
+            .text_sm()
+            .text_color(cx.theme().foreground)
+            .child(self.label.clone())
+    }
+}
 
 /// Component for editing a single trait method
 pub struct MethodEditorView {
     pub method: TraitMethod,
+    pub meta: MethodMeta,
     pub index: usize,
 
     // Input states
     name_input: Entity<InputState>,
     doc_input: Entity<InputState>,
     return_type_input: Entity<InputState>,
+    body_input: Entity<InputState>,
+    // One name input per parameter, in signature order
+    param_inputs: Vec<Entity<InputState>>,
+    generics_editor: Entity<GenericsEditor>,
+    abi_input: Entity<InputState>,
+    // One input per attribute, in declaration order
+    attribute_inputs: Vec<Entity<InputState>>,
 
     // Editing state
     editing_name: bool,
     editing_doc: bool,
     editing_return_type: bool,
-    
+    receiver_menu_open: bool,
+    attribute_menu_open: bool,
+
     // Subscriptions
     _subscriptions: Vec<gpui::Subscription>,
+    _param_subscriptions: Vec<gpui::Subscription>,
+    _attribute_subscriptions: Vec<gpui::Subscription>,
 }
 
 #[derive(Clone, Debug)]
 pub enum MethodEditorEvent {
-    MethodChanged(usize, TraitMethod),
+    MethodChanged(usize, TraitMethod, MethodMeta),
     RemoveRequested(usize),
     AddParameterRequested(usize),
-    TypePickerRequested(usize),
+    TypePickerRequested(usize, TypeSlot),
 }
 
 impl MethodEditorView {
-    pub fn new(method: TraitMethod, index: usize, window: &mut Window, cx: &mut Context<Self>) -> Self {
+    pub fn new(method: TraitMethod, meta: MethodMeta, index: usize, window: &mut Window, cx: &mut Context<Self>) -> Self {
         let name_input = cx.new(|cx| InputState::new(window, cx).placeholder("method_name"));
         let doc_input = cx.new(|cx| InputState::new(window, cx).placeholder("Method documentation..."));
         let return_type_input = cx.new(|cx| InputState::new(window, cx).placeholder("ReturnType"));
@@ -59,7 +110,7 @@ impl MethodEditorView {
                         this.name_input.update(cx, |input, _cx| {
                             this.method.name = input.text().to_string();
                         });
-                        cx.emit(MethodEditorEvent::MethodChanged(this.index, this.method.clone()));
+                        this.emit_changed(cx);
                         cx.notify();
                     }
                 }
@@ -69,7 +120,7 @@ impl MethodEditorView {
                         this.name_input.update(cx, |input, _cx| {
                             this.method.name = input.text().to_string();
                         });
-                        cx.emit(MethodEditorEvent::MethodChanged(this.index, this.method.clone()));
+                        this.emit_changed(cx);
                         cx.notify();
                     }
                 }
@@ -85,7 +136,7 @@ impl MethodEditorView {
                             let doc = input.text().to_string();
                             this.method.doc = if doc.is_empty() { None } else { Some(doc) };
                         });
-                        cx.emit(MethodEditorEvent::MethodChanged(this.index, this.method.clone()));
+                        this.emit_changed(cx);
                         cx.notify();
                     }
                 }
@@ -96,7 +147,7 @@ impl MethodEditorView {
                             let doc = input.text().to_string();
                             this.method.doc = if doc.is_empty() { None } else { Some(doc) };
                         });
-                        cx.emit(MethodEditorEvent::MethodChanged(this.index, this.method.clone()));
+                        this.emit_changed(cx);
                         cx.notify();
                     }
                 }
@@ -104,21 +155,340 @@ impl MethodEditorView {
             }
         });
 
-        Self {
+        let body_input = cx.new(|cx| rust_code_input(window, cx));
+        if let Some(body) = &method.default_body {
+            body_input.update(cx, |input, cx| {
+                input.set_value(body.clone(), window, cx);
+            });
+        }
+
+        let sub3 = cx.subscribe_in(&body_input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                // The editor keeps its text while the default is switched off
+                if this.method.default_body.is_some() {
+                    let body = this.body_input.read(cx).text().to_string();
+                    if this.method.default_body.as_deref() != Some(body.as_str()) {
+                        this.method.default_body = Some(body);
+                        this.emit_changed(cx);
+                        cx.notify();
+                    }
+                }
+            }
+        });
+
+        let generics_editor = cx.new(|cx| GenericsEditor::new(meta.generics.clone(), window, cx));
+        let sub4 = cx.subscribe_in(&generics_editor, window, |this, _, event: &GenericsEditorEvent, _window, cx| {
+            let GenericsEditorEvent::Changed(generics) = event;
+            this.meta.generics = generics.clone();
+            this.emit_changed(cx);
+            cx.notify();
+        });
+
+        let abi_input = cx.new(|cx| InputState::new(window, cx).placeholder("C"));
+        if let Some(abi) = &meta.abi {
+            abi_input.update(cx, |input, cx| input.set_value(abi.clone(), window, cx));
+        }
+        let sub5 = cx.subscribe_in(&abi_input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                let abi = this.abi_input.read(cx).text().to_string().trim().trim_matches('"').to_string();
+                if this.meta.abi.is_some() && this.meta.abi.as_deref() != Some(abi.as_str()) {
+                    this.meta.abi = Some(abi);
+                    this.emit_changed(cx);
+                    cx.notify();
+                }
+            }
+        });
+
+        let mut editor = Self {
             method,
+            meta,
             index,
             name_input,
             doc_input,
             return_type_input,
+            body_input,
+            param_inputs: Vec::new(),
+            generics_editor,
+            abi_input,
+            attribute_inputs: Vec::new(),
             editing_name: false,
             editing_doc: false,
             editing_return_type: false,
-            _subscriptions: vec![sub1, sub2],
+            receiver_menu_open: false,
+            attribute_menu_open: false,
+            _subscriptions: vec![sub1, sub2, sub3, sub4, sub5],
+            _param_subscriptions: Vec::new(),
+            _attribute_subscriptions: Vec::new(),
+        };
+        editor.rebuild_param_inputs(window, cx);
+        editor.rebuild_attribute_inputs(window, cx);
+        editor
+    }
+
+    /// Recreates the parameter name inputs from the current signature
+    fn rebuild_param_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.param_inputs.clear();
+        self._param_subscriptions.clear();
+
+        for param in &self.method.signature.params {
+            let input = cx.new(|cx| InputState::new(window, cx).placeholder("name"));
+            input.update(cx, |input, cx| {
+                input.set_value(param.name.clone(), window, cx);
+            });
+
+            let subscription = cx.subscribe_in(&input, window, |this, state, event: &ui::input::InputEvent, _window, cx| {
+                if let ui::input::InputEvent::Change = event {
+                    let Some(param_idx) = this.param_inputs.iter().position(|input| input == state) else {
+                        return;
+                    };
+                    let name = state.read(cx).text().to_string();
+                    let Some(param) = this.method.signature.params.get_mut(param_idx) else {
+                        return;
+                    };
+                    // Invalid names stay in the input, flagged, until they are fixed
+                    if param.name != name && is_valid_param_name(&name) {
+                        param.name = name;
+                        this.emit_changed(cx);
+                    }
+                    cx.notify();
+                }
+            });
+
+            self.param_inputs.push(input);
+            self._param_subscriptions.push(subscription);
+        }
+    }
+
+    /// Brings the parameter inputs in line with the signature without recreating them
+    fn sync_param_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.param_inputs.len() != self.method.signature.params.len() {
+            self.rebuild_param_inputs(window, cx);
+            return;
+        }
+        for (input, param) in self.param_inputs.iter().zip(&self.method.signature.params) {
+            if input.read(cx).text().to_string() != param.name {
+                input.update(cx, |input, cx| input.set_value(param.name.clone(), window, cx));
+            }
+        }
+    }
+
+    /// Recreates the attribute inputs from the method meta
+    fn rebuild_attribute_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.attribute_inputs.clear();
+        self._attribute_subscriptions.clear();
+
+        for attribute in &self.meta.attributes {
+            let input = cx.new(|cx| InputState::new(window, cx).placeholder("inline"));
+            input.update(cx, |input, cx| {
+                input.set_value(attribute.clone(), window, cx);
+            });
+
+            let subscription = cx.subscribe_in(&input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+                if let ui::input::InputEvent::Change = event {
+                    let attributes = this.read_attributes(cx);
+                    if attributes != this.meta.attributes {
+                        this.meta.attributes = attributes;
+                        this.emit_changed(cx);
+                        cx.notify();
+                    }
+                }
+            });
+
+            self.attribute_inputs.push(input);
+            self._attribute_subscriptions.push(subscription);
+        }
+    }
+
+    fn read_attributes(&self, cx: &App) -> Vec<String> {
+        self.attribute_inputs
+            .iter()
+            .map(|input| attribute_body(&input.read(cx).text().to_string()).to_string())
+            .collect()
+    }
+
+    fn add_attribute(&mut self, attribute: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.attribute_menu_open = false;
+        self.meta.attributes.push(attribute.to_string());
+        self.rebuild_attribute_inputs(window, cx);
+        self.emit_changed(cx);
+        cx.notify();
+    }
+
+    fn remove_attribute(&mut self, attribute_idx: usize, window: &mut Window, cx: &mut Context<Self>) {
+        if attribute_idx < self.meta.attributes.len() {
+            self.meta.attributes.remove(attribute_idx);
+            self.rebuild_attribute_inputs(window, cx);
+            self.emit_changed(cx);
+            cx.notify();
+        }
+    }
+
+    /// Applies a change to the qualifiers stored in the method meta
+    fn update_meta(&mut self, edit: impl FnOnce(&mut MethodMeta), cx: &mut Context<Self>) {
+        let mut meta = self.meta.clone();
+        edit(&mut meta);
+        if meta != self.meta {
+            self.meta = meta;
+            self.emit_changed(cx);
+        }
+        cx.notify();
+    }
+
+    fn toggle_extern(&mut self, cx: &mut Context<Self>) {
+        let abi = self.abi_input.read(cx).text().to_string().trim().trim_matches('"').to_string();
+        self.update_meta(|meta| {
+            meta.abi = match meta.abi {
+                Some(_) => None,
+                None if abi.is_empty() => Some("C".to_string()),
+                None => Some(abi),
+            };
+        }, cx);
+    }
+
+    /// Turns the provided default implementation on or off
+    fn set_default_body_enabled(&mut self, enabled: bool, window: &mut Window, cx: &mut Context<Self>) {
+        if enabled == self.method.default_body.is_some() {
+            return;
+        }
+        if enabled {
+            let mut body = self.body_input.read(cx).text().to_string();
+            if body.trim().is_empty() {
+                body = String::from("todo!()");
+                self.body_input.update(cx, |input, cx| input.set_value(body.clone(), window, cx));
+            }
+            self.method.default_body = Some(body);
+        } else {
+            self.method.default_body = None;
+        }
+        self.emit_changed(cx);
+        cx.notify();
+    }
+
+    fn param_name_error(&self, param_idx: usize, cx: &App) -> Option<String> {
+        let name = self.param_inputs.get(param_idx)?.read(cx).text().to_string();
+        if is_valid_param_name(&name) {
+            None
+        } else {
+            identifier_error(&name)
+        }
+    }
+
+    fn remove_param(&mut self, param_idx: usize, cx: &mut Context<Self>) {
+        if param_idx < self.method.signature.params.len() {
+            self.method.signature.params.remove(param_idx);
+            if param_idx < self.param_inputs.len() {
+                self.param_inputs.remove(param_idx);
+                self._param_subscriptions.remove(param_idx);
+            }
+            self.emit_changed(cx);
+            cx.notify();
+        }
+    }
+
+    /// Moves a parameter to a new position, keeping its name input with it
+    pub fn move_param(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        let params = &mut self.method.signature.params;
+        if from == to || from >= params.len() || to >= params.len() {
+            return;
         }
+        let param = params.remove(from);
+        params.insert(to, param);
+        if from < self.param_inputs.len() && to < self.param_inputs.len() {
+            let input = self.param_inputs.remove(from);
+            self.param_inputs.insert(to, input);
+            let subscription = self._param_subscriptions.remove(from);
+            self._param_subscriptions.insert(to, subscription);
+        }
+        self.emit_changed(cx);
+        cx.notify();
+    }
+
+    fn emit_changed(&self, cx: &mut Context<Self>) {
+        cx.emit(MethodEditorEvent::MethodChanged(self.index, self.method.clone(), self.meta.clone()));
     }
 
-    pub fn update_method(&mut self, method: TraitMethod, cx: &mut Context<Self>) {
-        self.method = method.clone();
+    fn set_receiver(&mut self, receiver: Receiver, cx: &mut Context<Self>) {
+        self.receiver_menu_open = false;
+        if self.meta.receiver != receiver {
+            self.meta.receiver = receiver;
+            self.emit_changed(cx);
+        }
+        cx.notify();
+    }
+
+    /// Replaces the edited method and refreshes the inputs, e.g. after an undo
+    pub fn set_method(&mut self, method: TraitMethod, meta: MethodMeta, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let return_type_str = Self::type_ref_to_string(&method.signature.return_type);
+        let doc = method.doc.clone().unwrap_or_default();
+        for (input, value) in [
+            (self.name_input.clone(), method.name.clone()),
+            (self.doc_input.clone(), doc),
+            (self.return_type_input.clone(), return_type_str),
+        ] {
+            if input.read(cx).text().to_string() != value {
+                input.update(cx, |input, cx| input.set_value(value, window, cx));
+            }
+        }
+
+        if let Some(body) = &method.default_body {
+            if self.body_input.read(cx).text().to_string() != *body {
+                self.body_input.update(cx, |input, cx| input.set_value(body.clone(), window, cx));
+            }
+        }
+
+        self.generics_editor.update(cx, |editor, cx| editor.set_generics(meta.generics.clone(), window, cx));
+        if let Some(abi) = &meta.abi {
+            if self.abi_input.read(cx).text().to_string() != *abi {
+                self.abi_input.update(cx, |input, cx| input.set_value(abi.clone(), window, cx));
+            }
+        }
+        let attributes_changed = self.read_attributes(cx) != meta.attributes;
+
+        self.method = method;
+        self.meta = meta;
+        self.index = index;
+        self.sync_param_inputs(window, cx);
+        if attributes_changed {
+            self.rebuild_attribute_inputs(window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Writes a type chosen in the type picker into the signature
+    pub fn apply_type(&mut self, slot: TypeSlot, type_ref: TypeRef, window: &mut Window, cx: &mut Context<Self>) {
+        let params = &mut self.method.signature.params;
+        match slot {
+            TypeSlot::Return => self.method.signature.return_type = type_ref,
+            TypeSlot::Param(param_idx) => {
+                let Some(param) = params.get_mut(param_idx) else {
+                    return;
+                };
+                param.type_ref = type_ref;
+            }
+            TypeSlot::NewParam => {
+                let name = (params.len() + 1..)
+                    .map(|n| format!("param{}", n))
+                    .find(|name| !params.iter().any(|param| &param.name == name))
+                    .unwrap_or_default();
+                params.push(MethodParam { name, type_ref });
+                self.sync_param_inputs(window, cx);
+            }
+        }
+        self.emit_changed(cx);
+        cx.notify();
+    }
+
+    /// Focuses the method name, or the name of parameter `param`, e.g. to reveal a diagnostic
+    pub fn focus_target(&mut self, param: Option<usize>, window: &mut Window, cx: &mut Context<Self>) {
+        let input = match param.and_then(|param_idx| self.param_inputs.get(param_idx)) {
+            Some(input) => input.clone(),
+            None => {
+                self.editing_name = true;
+                self.name_input.clone()
+            }
+        };
+        let handle = input.read(cx).focus_handle(cx);
+        window.focus(&handle);
         cx.notify();
     }
 
@@ -136,6 +506,8 @@ impl EventEmitter<MethodEditorEvent> for MethodEditorView {}
 impl Render for MethodEditorView {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let index = self.index;
+        let hover_bg = cx.theme().secondary.opacity(0.5);
+        let drop_bg = cx.theme().accent.opacity(0.15);
 
         v_flex()
             .w_full()
@@ -150,6 +522,34 @@ impl Render for MethodEditorView {
                 h_flex()
                     .items_center()
                     .gap_3()
+                    .child(
+                        // Qualifiers, in the order they are emitted
+                        h_flex()
+                            .gap_1()
+                            .children([
+                                ("async", self.meta.is_async),
+                                ("unsafe", self.meta.is_unsafe),
+                                ("extern", self.meta.abi.is_some()),
+                            ].into_iter().map(|(qualifier, enabled)| {
+                                Button::new(SharedString::from(format!("qualifier-{}-{}", qualifier, index)))
+                                    .with_size(ui::Size::XSmall)
+                                    .label(qualifier)
+                                    .when(enabled, |button| button.primary())
+                                    .when(!enabled, |button| button.ghost())
+                                    .on_click(cx.listener(move |this, _, _window, cx| match qualifier {
+                                        "async" => this.update_meta(|meta| meta.is_async = !meta.is_async, cx),
+                                        "unsafe" => this.update_meta(|meta| meta.is_unsafe = !meta.is_unsafe, cx),
+                                        _ => this.toggle_extern(cx),
+                                    }))
+                            }))
+                            .when(self.meta.abi.is_some(), |this| {
+                                this.child(
+                                    TextInput::new(&self.abi_input)
+                                        .w(px(72.0))
+                                        .with_size(ui::Size::XSmall)
+                                )
+                            })
+                    )
                     .child(
                         div()
                             .text_xs()
@@ -191,6 +591,29 @@ impl Render for MethodEditorView {
                                 .into_any_element()
                         }
                     )
+                    .child(
+                        // Receiver selector
+                        Button::new(("receiver", index))
+                            .ghost()
+                            .with_size(ui::Size::XSmall)
+                            .label(self.meta.receiver.label())
+                            .icon(IconName::ChevronDown)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.receiver_menu_open = !this.receiver_menu_open;
+                                cx.notify();
+                            }))
+                    )
+                    .child(
+                        // Attribute presets
+                        Button::new(("attributes", index))
+                            .ghost()
+                            .with_size(ui::Size::XSmall)
+                            .label("#[]")
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.attribute_menu_open = !this.attribute_menu_open;
+                                cx.notify();
+                            }))
+                    )
                     .child(
                         // Remove button
                         Button::new(("remove", index))
@@ -202,6 +625,71 @@ impl Render for MethodEditorView {
                             }))
                     )
             )
+            .when(self.receiver_menu_open, |this| {
+                let current = self.meta.receiver;
+                this.child(
+                    h_flex()
+                        .flex_wrap()
+                        .gap_1()
+                        .children(Receiver::ALL.into_iter().map(|receiver| {
+                            Button::new(SharedString::from(format!("receiver-{}-{:?}", index, receiver)))
+                                .with_size(ui::Size::XSmall)
+                                .label(receiver.label())
+                                .when(receiver == current, |button| button.primary())
+                                .when(receiver != current, |button| button.ghost())
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_receiver(receiver, cx);
+                                }))
+                        }))
+                )
+            })
+            .when(self.attribute_menu_open, |this| {
+                this.child(
+                    h_flex()
+                        .flex_wrap()
+                        .gap_1()
+                        .children(ATTRIBUTE_PRESETS.iter().enumerate().map(|(preset_idx, preset)| {
+                            Button::new(SharedString::from(format!("attribute-preset-{}-{}", index, preset_idx)))
+                                .ghost()
+                                .with_size(ui::Size::XSmall)
+                                .icon(IconName::Plus)
+                                .label(*preset)
+                                .on_click(cx.listener(move |this, _, window, cx| {
+                                    this.add_attribute(preset, window, cx);
+                                }))
+                        }))
+                        .child(
+                            Button::new(("attribute-custom", index))
+                                .ghost()
+                                .with_size(ui::Size::XSmall)
+                                .icon(IconName::Plus)
+                                .label("Custom")
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.add_attribute("", window, cx);
+                                }))
+                        )
+                )
+            })
+            // Attributes
+            .children(self.attribute_inputs.iter().enumerate().map(|(attribute_idx, input)| {
+                h_flex()
+                    .gap_1()
+                    .items_center()
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground).child("#["))
+                    .child(TextInput::new(input).flex_1().with_size(ui::Size::Small))
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground).child("]"))
+                    .child(
+                        Button::new(SharedString::from(format!("remove-attribute-{}-{}", index, attribute_idx)))
+                            .ghost()
+                            .with_size(ui::Size::XSmall)
+                            .icon(IconName::Close)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.remove_attribute(attribute_idx, window, cx);
+                            }))
+                    )
+            }))
+            // Generic parameters and where clause
+            .child(self.generics_editor.clone())
             // Parameters section
             .child(
                 v_flex()
@@ -223,12 +711,7 @@ impl Render for MethodEditorView {
                                     .with_size(ui::Size::XSmall)
                                     .icon(IconName::Plus)
                                     .on_click(cx.listener(move |this, _, _window, cx| {
-                                        this.method.signature.params.push(MethodParam {
-                                            name: format!("param{}", this.method.signature.params.len() + 1),
-                                            type_ref: TypeRef::Primitive { name: "String".to_string() },
-                                        });
-                                        cx.emit(MethodEditorEvent::MethodChanged(index, this.method.clone()));
-                                        cx.notify();
+                                        cx.emit(MethodEditorEvent::AddParameterRequested(this.index));
                                     }))
                             )
                     )
@@ -237,33 +720,121 @@ impl Render for MethodEditorView {
                             .gap_2()
                             .children(
                                 self.method.signature.params.iter().enumerate().map(|(param_idx, param)| {
-                                    h_flex()
-                                        .gap_2()
-                                        .p_2()
-                                        .rounded(px(4.0))
-                                        .bg(cx.theme().secondary.opacity(0.2))
-                                        .border_1()
-                                        .border_color(cx.theme().border.opacity(0.3))
-                                        .child(
-                                            div()
-                                                .flex_1()
-                                                .text_sm()
-                                                .text_color(cx.theme().foreground)
-                                                .child(format!("{}: {}", param.name, Self::type_ref_to_string(&param.type_ref)))
-                                        )
+                                    let name_error = self.param_name_error(param_idx, cx);
+                                    let dragged = DraggedParam {
+                                        method_index: index,
+                                        param_index: param_idx,
+                                        label: format!("{}: {}", param.name, Self::type_ref_to_string(&param.type_ref)).into(),
+                                    };
+
+                                    v_flex()
+                                        .id(SharedString::from(format!("param-row-{}-{}", index, param_idx)))
+                                        .key_context(PARAM_CONTEXT)
+                                        .gap_1()
+                                        .on_action(cx.listener(move |this, _: &MoveParamUp, _window, cx| {
+                                            if param_idx > 0 {
+                                                this.move_param(param_idx, param_idx - 1, cx);
+                                            }
+                                        }))
+                                        .on_action(cx.listener(move |this, _: &MoveParamDown, _window, cx| {
+                                            this.move_param(param_idx, param_idx + 1, cx);
+                                        }))
+                                        .drag_over::<DraggedParam>(move |style, _, _, _| style.bg(drop_bg))
+                                        .on_drop(cx.listener(move |this, dragged: &DraggedParam, _window, cx| {
+                                            if dragged.method_index == this.index {
+                                                this.move_param(dragged.param_index, param_idx, cx);
+                                            }
+                                        }))
                                         .child(
-                                            Button::new(SharedString::from(format!("remove-param-{}-{}", index, param_idx)))
-                                                .ghost()
-                                                .with_size(ui::Size::XSmall)
-                                                .icon(IconName::Close)
-                                                .on_click(cx.listener(move |this, _, _window, cx| {
-                                                    if param_idx < this.method.signature.params.len() {
-                                                        this.method.signature.params.remove(param_idx);
-                                                        cx.emit(MethodEditorEvent::MethodChanged(index, this.method.clone()));
-                                                        cx.notify();
-                                                    }
+                                            h_flex()
+                                                .gap_2()
+                                                .p_2()
+                                                .rounded(px(4.0))
+                                                .bg(cx.theme().secondary.opacity(0.2))
+                                                .border_1()
+                                                .border_color(if name_error.is_some() {
+                                                    cx.theme().danger
+                                                } else {
+                                                    cx.theme().border.opacity(0.3)
+                                                })
+                                                .items_center()
+                                                .child(
+                                                    // Drag handle
+                                                    div()
+                                                        .id(SharedString::from(format!("param-grip-{}-{}", index, param_idx)))
+                                                        .cursor_grab()
+                                                        .text_sm()
+                                                        .text_color(cx.theme().muted_foreground)
+                                                        .on_drag(dragged, |dragged, _offset, _window, cx| {
+                                                            cx.new(|_| dragged.clone())
+                                                        })
+                                                        .child("⋮⋮")
+                                                )
+                                                .children(self.param_inputs.get(param_idx).map(|input| {
+                                                    TextInput::new(input)
+                                                        .w(px(140.0))
+                                                        .with_size(ui::Size::Small)
                                                 }))
+                                                .child(
+                                                    div()
+                                                        .text_sm()
+                                                        .text_color(cx.theme().muted_foreground)
+                                                        .child(":")
+                                                )
+                                                .child(
+                                                    div()
+                                                        .id(SharedString::from(format!("param-type-{}-{}", index, param_idx)))
+                                                        .flex_1()
+                                                        .px_1()
+                                                        .rounded(px(3.0))
+                                                        .cursor_pointer()
+                                                        .text_sm()
+                                                        .text_color(cx.theme().accent)
+                                                        .hover(|this| this.bg(hover_bg))
+                                                        .on_click(cx.listener(move |this, _, _window, cx| {
+                                                            cx.emit(MethodEditorEvent::TypePickerRequested(this.index, TypeSlot::Param(param_idx)));
+                                                        }))
+                                                        .child(Self::type_ref_to_string(&param.type_ref))
+                                                )
+                                                .child(
+                                                    Button::new(SharedString::from(format!("move-param-up-{}-{}", index, param_idx)))
+                                                        .ghost()
+                                                        .with_size(ui::Size::XSmall)
+                                                        .icon(IconName::ArrowUp)
+                                                        .on_click(cx.listener(move |this, _, _window, cx| {
+                                                            if param_idx > 0 {
+                                                                this.move_param(param_idx, param_idx - 1, cx);
+                                                            }
+                                                        }))
+                                                )
+                                                .child(
+                                                    Button::new(SharedString::from(format!("move-param-down-{}-{}", index, param_idx)))
+                                                        .ghost()
+                                                        .with_size(ui::Size::XSmall)
+                                                        .icon(IconName::ArrowDown)
+                                                        .on_click(cx.listener(move |this, _, _window, cx| {
+                                                            this.move_param(param_idx, param_idx + 1, cx);
+                                                        }))
+                                                )
+                                                .child(
+                                                    Button::new(SharedString::from(format!("remove-param-{}-{}", index, param_idx)))
+                                                        .ghost()
+                                                        .with_size(ui::Size::XSmall)
+                                                        .icon(IconName::Close)
+                                                        .on_click(cx.listener(move |this, _, _window, cx| {
+                                                            this.remove_param(param_idx, cx);
+                                                        }))
+                                                )
                                         )
+                                        .when_some(name_error, |this, error| {
+                                            this.child(
+                                                div()
+                                                    .px_2()
+                                                    .text_xs()
+                                                    .text_color(cx.theme().danger)
+                                                    .child(error)
+                                            )
+                                        })
                                 })
                             )
                             .when(self.method.signature.params.is_empty(), |this| {
@@ -294,7 +865,7 @@ impl Render for MethodEditorView {
                             .w_full()
                             .ghost()
                             .on_click(cx.listener(move |this, _, _window, cx| {
-                                cx.emit(MethodEditorEvent::TypePickerRequested(index));
+                                cx.emit(MethodEditorEvent::TypePickerRequested(this.index, TypeSlot::Return));
                             }))
                             .child(
                                 h_flex()
@@ -328,28 +899,25 @@ impl Render for MethodEditorView {
                     )
             )
             // Default body section (optional)
-            .when(self.method.default_body.is_some(), |this| {
-                this.child(
-                    v_flex()
-                        .gap_2()
-                        .child(
-                            div()
-                                .text_xs()
-                                .font_semibold()
-                                .text_color(cx.theme().muted_foreground)
-                                .child("Default Implementation")
-                        )
-                        .child(
-                            div()
-                                .p_2()
-                                .rounded(px(4.0))
-                                .bg(cx.theme().secondary.opacity(0.2))
-                                .text_sm()
-                                .text_color(cx.theme().muted_foreground)
-                                .child(self.method.default_body.as_ref().unwrap().clone())
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        Checkbox::new(("provide-default", index))
+                            .label("Provide default implementation")
+                            .checked(self.method.default_body.is_some())
+                            .on_click(cx.listener(|this, checked: &bool, window, cx| {
+                                this.set_default_body_enabled(*checked, window, cx);
+                            }))
+                    )
+                    .when(self.method.default_body.is_some(), |this| {
+                        this.child(
+                            TextInput::new(&self.body_input)
+                                .w_full()
+                                .h(px(160.0))
                         )
-                )
-            })
+                    })
+            )
             // Documentation section
             .when(self.method.doc.is_some() || self.editing_doc, |this| {
                 this.child(
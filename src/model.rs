@@ -0,0 +1,337 @@
+//! Editor data persisted in [`TraitAsset::meta`]
+//!
+//! `ui_types_common` models only names, parameters and return types, so everything else
+//! the editor needs to produce real Rust (receivers, generics, associated items, and so on)
+//! lives in the asset's free-form `meta` object. Per-method data is kept in `meta.methods`,
+//! parallel to `TraitAsset::methods`. Keys this editor doesn't know are preserved on write.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use ui_types_common::TraitAsset;
+
+/// How a method takes `self`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Receiver {
+    /// Associated function without a receiver, e.g. `fn new() -> Self`
+    None,
+    #[default]
+    Ref,
+    RefMut,
+    Value,
+    Box,
+    Rc,
+    Arc,
+    PinMut,
+}
+
+impl Receiver {
+    pub const ALL: [Receiver; 8] = [
+        Receiver::None,
+        Receiver::Ref,
+        Receiver::RefMut,
+        Receiver::Value,
+        Receiver::Box,
+        Receiver::Rc,
+        Receiver::Arc,
+        Receiver::PinMut,
+    ];
+
+    /// Short label shown in the receiver selector
+    pub fn label(&self) -> &'static str {
+        match self {
+            Receiver::None => "no self",
+            Receiver::Ref => "&self",
+            Receiver::RefMut => "&mut self",
+            Receiver::Value => "self",
+            Receiver::Box => "Box<Self>",
+            Receiver::Rc => "Rc<Self>",
+            Receiver::Arc => "Arc<Self>",
+            Receiver::PinMut => "Pin<&mut Self>",
+        }
+    }
+
+    /// The receiver as written in a parameter list, or `None` for associated functions
+    pub fn to_rust(self) -> Option<&'static str> {
+        match self {
+            Receiver::None => None,
+            Receiver::Ref => Some("&self"),
+            Receiver::RefMut => Some("&mut self"),
+            Receiver::Value => Some("self"),
+            Receiver::Box => Some("self: Box<Self>"),
+            Receiver::Rc => Some("self: std::rc::Rc<Self>"),
+            Receiver::Arc => Some("self: std::sync::Arc<Self>"),
+            Receiver::PinMut => Some("self: std::pin::Pin<&mut Self>"),
+        }
+    }
+}
+
+/// Visibility of the generated trait
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Visibility {
+    #[default]
+    Public,
+    Crate,
+    Private,
+}
+
+impl Visibility {
+    pub const ALL: [Visibility; 3] = [Visibility::Public, Visibility::Crate, Visibility::Private];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Visibility::Public => "pub",
+            Visibility::Crate => "pub(crate)",
+            Visibility::Private => "private",
+        }
+    }
+
+    /// The visibility keyword followed by a space, or nothing for private items
+    pub fn to_rust(self) -> &'static str {
+        match self {
+            Visibility::Public => "pub ",
+            Visibility::Crate => "pub(crate) ",
+            Visibility::Private => "",
+        }
+    }
+}
+
+/// Strips `#[` and `]` from an attribute typed in full, leaving the part inside the brackets
+pub fn attribute_body(text: &str) -> &str {
+    let text = text.trim();
+    text.strip_prefix("#[")
+        .and_then(|inner| inner.strip_suffix(']'))
+        .map(str::trim)
+        .unwrap_or(text)
+}
+
+/// A generic parameter of a trait or method
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum GenericParam {
+    /// `'a: 'b`
+    Lifetime {
+        name: String,
+        #[serde(default)]
+        bounds: Vec<String>,
+    },
+    /// `T: Clone + Send = String`
+    Type {
+        name: String,
+        #[serde(default)]
+        bounds: Vec<String>,
+        #[serde(default)]
+        default: Option<String>,
+    },
+    /// `const N: usize = 4`
+    Const {
+        name: String,
+        ty: String,
+        #[serde(default)]
+        default: Option<String>,
+    },
+}
+
+impl GenericParam {
+    pub fn name(&self) -> &str {
+        match self {
+            GenericParam::Lifetime { name, .. }
+            | GenericParam::Type { name, .. }
+            | GenericParam::Const { name, .. } => name,
+        }
+    }
+
+    pub fn is_lifetime(&self) -> bool {
+        matches!(self, GenericParam::Lifetime { .. })
+    }
+}
+
+/// A `where` clause predicate such as `T::Item: Into<String>`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WherePredicate {
+    pub bounded: String,
+    pub bounds: Vec<String>,
+}
+
+/// Generic parameters and `where` predicates of a trait or method
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Generics {
+    pub params: Vec<GenericParam>,
+    pub where_clause: Vec<WherePredicate>,
+}
+
+impl Generics {
+    pub fn is_empty(&self) -> bool {
+        self.params.is_empty() && self.where_clause.is_empty()
+    }
+}
+
+/// Splits a bound list such as `Clone + Fn(u8) -> u8 + 'a` at its top-level `+` signs
+pub fn split_bounds(text: &str) -> Vec<String> {
+    let mut bounds = Vec::new();
+    let mut depth = 0i32;
+    let mut current = String::new();
+    for c in text.chars() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' if current.ends_with('-') => {}
+            '>' | ')' | ']' => depth -= 1,
+            '+' if depth <= 0 => {
+                bounds.push(std::mem::take(&mut current));
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    bounds.push(current);
+    bounds
+        .into_iter()
+        .map(|bound| bound.trim().to_string())
+        .filter(|bound| !bound.is_empty())
+        .collect()
+}
+
+/// An associated type such as `type Output: Clone;`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssociatedType {
+    pub name: String,
+    pub bounds: Vec<String>,
+    /// Needs the unstable `associated_type_defaults` feature when set
+    pub default: Option<String>,
+}
+
+/// An associated constant such as `const ID: u32 = 0;`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AssociatedConst {
+    pub name: String,
+    pub ty: String,
+    pub default: Option<String>,
+}
+
+/// Editor data for one method, stored in `meta.methods[index]`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MethodMeta {
+    pub receiver: Receiver,
+    pub generics: Generics,
+    pub is_async: bool,
+    pub is_unsafe: bool,
+    /// ABI of an `extern` method without quotes, e.g. `C`. Empty for a bare `extern`.
+    pub abi: Option<String>,
+    /// Attribute bodies without the surrounding `#[...]`, e.g. `inline`
+    pub attributes: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// Editor data for the whole trait, stored in `meta`
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraitMeta {
+    pub visibility: Visibility,
+    pub is_unsafe: bool,
+    /// Attribute bodies without the surrounding `#[...]`, e.g. `must_use`
+    pub attributes: Vec<String>,
+    pub supertraits: Vec<String>,
+    pub generics: Generics,
+    pub associated_types: Vec<AssociatedType>,
+    pub associated_consts: Vec<AssociatedConst>,
+    /// Trait items the editor can't model, such as macro invocations or generic associated
+    /// types, kept as source and emitted verbatim
+    pub verbatim_items: Vec<String>,
+    /// File the generated code is written to on save, relative to the project root
+    pub output_path: Option<String>,
+    /// Module the trait belongs to, e.g. `crate::traits::shape`. Names the output file
+    /// when no `output_path` is set.
+    pub module_path: Option<String>,
+    pub methods: Vec<MethodMeta>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+impl TraitMeta {
+    /// Reads the editor data from `asset.meta`, with one method entry per method.
+    /// Malformed data falls back to defaults; files holding it are refused on load (see
+    /// [`TraitMeta::try_from_asset`]), so it only shows up here if the asset was changed
+    /// behind the editor's back.
+    pub fn from_asset(asset: &TraitAsset) -> Self {
+        Self::try_from_asset(asset).unwrap_or_else(|e| {
+            tracing::warn!("Ignoring malformed trait editor meta: {}", e);
+            let mut meta = TraitMeta::default();
+            meta.methods.resize_with(asset.methods.len(), MethodMeta::default);
+            meta
+        })
+    }
+
+    /// Like [`TraitMeta::from_asset`], but fails on data of the wrong shape instead of
+    /// dropping it, since the next edit would write the defaults back over it
+    pub fn try_from_asset(asset: &TraitAsset) -> Result<Self, String> {
+        let mut meta = match &asset.meta {
+            Value::Null => TraitMeta::default(),
+            value => serde_json::from_value::<TraitMeta>(value.clone()).map_err(|e| e.to_string())?,
+        };
+        meta.methods.resize_with(asset.methods.len(), MethodMeta::default);
+        Ok(meta)
+    }
+
+    pub fn write_to(&self, asset: &mut TraitAsset) {
+        match serde_json::to_value(self) {
+            Ok(value) => asset.meta = value,
+            Err(e) => tracing::error!("Failed to serialize trait editor meta: {}", e),
+        }
+    }
+
+    pub fn method(&self, index: usize) -> MethodMeta {
+        self.methods.get(index).cloned().unwrap_or_default()
+    }
+}
+
+/// `asset` as JSON with its meta in the form [`TraitMeta::write_to`] leaves it. Commands
+/// rewrite a sparse meta such as `{}` in full, so comparing plain JSON would tell an edit
+/// undone back to the loaded asset apart from it.
+pub fn asset_snapshot(asset: &TraitAsset) -> Value {
+    let mut snapshot = serde_json::to_value(asset).unwrap_or(Value::Null);
+    if let (Value::Object(fields), Ok(meta)) = (&mut snapshot, serde_json::to_value(TraitMeta::from_asset(asset))) {
+        fields.insert("meta".to_string(), meta);
+    }
+    snapshot
+}
+
+pub fn method_meta(asset: &TraitAsset, index: usize) -> MethodMeta {
+    TraitMeta::from_asset(asset).method(index)
+}
+
+pub fn set_method_meta(asset: &mut TraitAsset, index: usize, method_meta: MethodMeta) {
+    let mut meta = TraitMeta::from_asset(asset);
+    if let Some(entry) = meta.methods.get_mut(index) {
+        *entry = method_meta;
+        meta.write_to(asset);
+    }
+}
+
+/// Inserts the meta entry for a method just inserted at `index` in `asset.methods`
+pub fn insert_method_meta(asset: &mut TraitAsset, index: usize, method_meta: MethodMeta) {
+    let mut meta = TraitMeta::from_asset(asset);
+    // from_asset already padded an entry for the new method at the end
+    meta.methods.pop();
+    let index = index.min(meta.methods.len());
+    meta.methods.insert(index, method_meta);
+    meta.write_to(asset);
+}
+
+/// Removes the meta entry of a method about to be removed from `asset.methods`
+pub fn remove_method_meta(asset: &mut TraitAsset, index: usize) -> MethodMeta {
+    let mut meta = TraitMeta::from_asset(asset);
+    if index >= meta.methods.len() {
+        return MethodMeta::default();
+    }
+    let removed = meta.methods.remove(index);
+    meta.write_to(asset);
+    removed
+}
@@ -0,0 +1,209 @@
+//! Dyn compatibility (object safety) analysis
+//!
+//! A trait can only be used as `dyn Trait` if it doesn't require `Self: Sized` and every
+//! method is dispatchable through a vtable. Methods that aren't can opt out with
+//! `where Self: Sized`, which keeps them callable on concrete types only.
+
+use ui_types_common::{TraitAsset, TypeRef};
+use crate::model::{GenericParam, Generics, MethodMeta, Receiver, TraitMeta, WherePredicate};
+use crate::type_expr::TypeExpr;
+
+/// Standard traits that require `Sized` or mention `Self` in their own signatures, so a
+/// trait inheriting them can't be a trait object either
+const INCOMPATIBLE_SUPERTRAITS: &[&str] = &["Clone", "Copy", "Default", "PartialEq", "Eq", "PartialOrd", "Ord", "Hash", "From", "Into"];
+
+/// Why a trait isn't dyn compatible
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ViolationKind {
+    /// `Sized` as a supertrait, or `Self: Sized` on the trait
+    SizedSupertrait,
+    /// A supertrait such as `Clone` that isn't dyn compatible itself
+    IncompatibleSupertrait,
+    AssociatedConst,
+    /// Method with type or const parameters, including `impl Trait` arguments
+    GenericMethod,
+    /// `Self` used outside the receiver
+    SelfInSignature,
+    /// Associated function without a receiver
+    NoReceiver,
+    AsyncMethod,
+    /// `-> impl Trait` return type
+    ReturnsImplTrait,
+}
+
+#[derive(Clone, Debug)]
+pub struct Violation {
+    pub kind: ViolationKind,
+    /// The method causing the violation, if it comes from a method
+    pub method: Option<usize>,
+    /// The offending supertrait, for supertrait violations
+    pub supertrait: Option<String>,
+    pub message: String,
+}
+
+impl Violation {
+    /// The edit that removes this violation, if there is a mechanical one
+    pub fn quick_fix(&self) -> Option<QuickFix> {
+        match (self.kind, self.method) {
+            (ViolationKind::SizedSupertrait, _) => Some(QuickFix::RemoveSizedBound),
+            (ViolationKind::IncompatibleSupertrait, _) => self.supertrait.clone().map(QuickFix::RemoveSupertrait),
+            (ViolationKind::AssociatedConst, _) => None,
+            (_, Some(method)) => Some(QuickFix::RequireSized(method)),
+            (_, None) => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuickFix {
+    /// Adds `where Self: Sized` to a method, excluding it from the vtable
+    RequireSized(usize),
+    /// Drops `Sized` from the supertraits and `Self: Sized` from the trait's where clause
+    RemoveSizedBound,
+    RemoveSupertrait(String),
+}
+
+impl QuickFix {
+    pub fn label(&self) -> String {
+        match self {
+            QuickFix::RequireSized(_) => "Add `where Self: Sized`".to_string(),
+            QuickFix::RemoveSizedBound => "Remove `Sized` bound".to_string(),
+            QuickFix::RemoveSupertrait(bound) => format!("Remove `{}`", bound),
+        }
+    }
+
+    pub fn apply(&self, meta: &mut TraitMeta) {
+        match self {
+            QuickFix::RequireSized(method) => {
+                if let Some(method_meta) = meta.methods.get_mut(*method) {
+                    if !requires_sized(&method_meta.generics) {
+                        method_meta.generics.where_clause.push(WherePredicate {
+                            bounded: "Self".to_string(),
+                            bounds: vec!["Sized".to_string()],
+                        });
+                    }
+                }
+            }
+            QuickFix::RemoveSizedBound => {
+                meta.supertraits.retain(|bound| !is_sized(bound));
+                for predicate in &mut meta.generics.where_clause {
+                    if predicate.bounded.trim() == "Self" {
+                        predicate.bounds.retain(|bound| !is_sized(bound));
+                    }
+                }
+                meta.generics
+                    .where_clause
+                    .retain(|predicate| predicate.bounded.trim() != "Self" || !predicate.bounds.is_empty());
+            }
+            QuickFix::RemoveSupertrait(bound) => meta.supertraits.retain(|supertrait| supertrait != bound),
+        }
+    }
+}
+
+/// Whether `bound` names one of [`INCOMPATIBLE_SUPERTRAITS`], with or without a path or arguments
+fn is_incompatible_supertrait(bound: &str) -> bool {
+    let path = bound.split('<').next().unwrap_or_default().trim();
+    let name = path.rsplit("::").next().unwrap_or_default();
+    INCOMPATIBLE_SUPERTRAITS.contains(&name)
+}
+
+fn is_sized(bound: &str) -> bool {
+    matches!(bound.trim(), "Sized" | "std::marker::Sized" | "core::marker::Sized")
+}
+
+/// Whether the where clause contains `Self: Sized`
+pub(crate) fn requires_sized(generics: &Generics) -> bool {
+    generics
+        .where_clause
+        .iter()
+        .any(|predicate| predicate.bounded.trim() == "Self" && predicate.bounds.iter().any(|bound| is_sized(bound)))
+}
+
+pub fn is_dyn_compatible(asset: &TraitAsset) -> bool {
+    violations(asset).is_empty()
+}
+
+/// Every reason `dyn Trait` would be rejected, in declaration order
+pub fn violations(asset: &TraitAsset) -> Vec<Violation> {
+    let meta = TraitMeta::from_asset(asset);
+    let mut violations = Vec::new();
+
+    if meta.supertraits.iter().any(|bound| is_sized(bound)) || requires_sized(&meta.generics) {
+        violations.push(Violation {
+            kind: ViolationKind::SizedSupertrait,
+            method: None,
+            supertrait: None,
+            message: "The trait requires `Self: Sized`".to_string(),
+        });
+    }
+    for bound in meta.supertraits.iter().filter(|bound| is_incompatible_supertrait(bound)) {
+        violations.push(Violation {
+            kind: ViolationKind::IncompatibleSupertrait,
+            method: None,
+            supertrait: Some(bound.clone()),
+            message: format!("Supertrait `{}` is not dyn compatible", bound),
+        });
+    }
+    for item in &meta.associated_consts {
+        violations.push(Violation {
+            kind: ViolationKind::AssociatedConst,
+            method: None,
+            supertrait: None,
+            message: format!("Associated constant `{}`", item.name.trim()),
+        });
+    }
+
+    for (index, method) in asset.methods.iter().enumerate() {
+        let method_meta = meta.method(index);
+        if requires_sized(&method_meta.generics) {
+            continue;
+        }
+        let mut push = |kind, message: String| {
+            violations.push(Violation {
+                kind,
+                method: Some(index),
+                supertrait: None,
+                message: format!("`{}`: {}", method.name, message),
+            });
+        };
+
+        if method_meta.receiver == Receiver::None {
+            push(ViolationKind::NoReceiver, "has no `self` receiver".to_string());
+        }
+        if has_type_params(&method_meta) || method.signature.params.iter().any(|param| contains_impl(&param.type_ref)) {
+            push(ViolationKind::GenericMethod, "has generic type parameters".to_string());
+        }
+        if method_meta.is_async {
+            push(ViolationKind::AsyncMethod, "is `async`".to_string());
+        }
+        if contains_impl(&method.signature.return_type) {
+            push(ViolationKind::ReturnsImplTrait, "returns `impl Trait`".to_string());
+        }
+        let self_param = method.signature.params.iter().find(|param| contains_self(&param.type_ref));
+        if let Some(param) = self_param {
+            push(ViolationKind::SelfInSignature, format!("parameter `{}` uses `Self`", param.name));
+        }
+        if contains_self(&method.signature.return_type) {
+            push(ViolationKind::SelfInSignature, "returns `Self`".to_string());
+        }
+    }
+
+    violations
+}
+
+/// Lifetime parameters don't affect dispatch; type and const parameters do
+fn has_type_params(meta: &MethodMeta) -> bool {
+    meta.generics.params.iter().any(|param| !matches!(param, GenericParam::Lifetime { .. }))
+}
+
+fn contains_self(type_ref: &TypeRef) -> bool {
+    any_node(&TypeExpr::from_type_ref(type_ref), &|node| matches!(node, TypeExpr::SelfType))
+}
+
+fn contains_impl(type_ref: &TypeRef) -> bool {
+    any_node(&TypeExpr::from_type_ref(type_ref), &|node| matches!(node, TypeExpr::TraitObject { is_impl: true, .. }))
+}
+
+fn any_node(expr: &TypeExpr, predicate: &dyn Fn(&TypeExpr) -> bool) -> bool {
+    predicate(expr) || expr.children().into_iter().any(|child| any_node(child, predicate))
+}
@@ -0,0 +1,252 @@
+//! Writes generated trait code into the project's source tree
+//!
+//! A trait asset with an output path or module path in its meta gets its generated code
+//! written to that `.rs` file on save. The file starts with a header carrying a hash of
+//! the code below it; a file whose hash no longer matches was edited by hand and is not
+//! overwritten unless the user forces it.
+
+use std::path::{Path, PathBuf};
+use ui_types_common::TraitAsset;
+use crate::codegen::{self, CodegenOptions};
+use crate::model::TraitMeta;
+use crate::persistence;
+use crate::schema;
+use crate::type_picker::{project_root_for, MAX_SCAN_DEPTH, SKIPPED_DIRS};
+
+/// First line of every generated file
+const GENERATED_MARKER: &str = "// @generated by the Pulsar trait editor";
+
+/// Prefix of the header line holding the hash of the generated code
+const HASH_PREFIX: &str = "// content-hash: ";
+
+/// What happened to a trait's output file
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OutputStatus {
+    /// The asset has no output file configured
+    Disabled,
+    Unchanged(PathBuf),
+    Written(PathBuf),
+    /// The file was edited since it was generated and was left alone
+    ManuallyEdited(PathBuf),
+    /// A file not written by the trait editor is in the way and was left alone
+    NotGenerated(PathBuf),
+}
+
+impl OutputStatus {
+    /// Whether the file was left alone to protect content the editor didn't write
+    pub fn is_conflict(&self) -> bool {
+        matches!(self, OutputStatus::ManuallyEdited(_) | OutputStatus::NotGenerated(_))
+    }
+
+    pub fn message(&self) -> Option<String> {
+        match self {
+            OutputStatus::Disabled | OutputStatus::Unchanged(_) => None,
+            OutputStatus::Written(path) => Some(format!("Generated {}", path.display())),
+            OutputStatus::ManuallyEdited(path) => {
+                Some(format!("{} was edited by hand and was not regenerated", path.display()))
+            }
+            OutputStatus::NotGenerated(path) => {
+                Some(format!("{} was not generated by the trait editor and was not overwritten", path.display()))
+            }
+        }
+    }
+}
+
+/// The file `asset` generates code into, if it has one. `asset_file` is the asset's
+/// `trait.json`; relative paths are resolved against its project root.
+pub fn output_file(asset: &TraitAsset, asset_file: &Path) -> Option<PathBuf> {
+    let meta = TraitMeta::from_asset(asset);
+    let relative = match (meta.output_path.as_deref().map(str::trim), meta.module_path.as_deref().map(str::trim)) {
+        (Some(path), _) if !path.is_empty() => PathBuf::from(path),
+        (_, Some(module)) if !module.is_empty() => module_file(module)?,
+        _ => return None,
+    };
+    let root = project_root_for(asset_file).unwrap_or_default();
+    Some(root.join(relative))
+}
+
+/// `crate::traits::shape` lives in `src/traits/shape.rs`
+fn module_file(module_path: &str) -> Option<PathBuf> {
+    let mut segments = module_path.split("::").map(str::trim);
+    if segments.next() != Some("crate") {
+        return None;
+    }
+    let mut path = PathBuf::from("src");
+    let mut has_segments = false;
+    for segment in segments {
+        if segment.is_empty() {
+            return None;
+        }
+        path.push(segment);
+        has_segments = true;
+    }
+    has_segments.then(|| path.with_extension("rs"))
+}
+
+/// The full file content for `asset`, header included
+pub fn generated_file(asset: &TraitAsset, asset_file: &Path) -> String {
+    let code = codegen::generate_trait(asset, &CodegenOptions::default());
+    let source = asset_file
+        .parent()
+        .and_then(Path::file_name)
+        .unwrap_or(asset_file.as_os_str())
+        .to_string_lossy();
+    format!(
+        "{} from `{}`. Do not edit.\n\
+         // Edits are detected by this hash and stop the file from being regenerated.\n\
+         {}{}\n\
+         \n\
+         {}",
+        GENERATED_MARKER,
+        source,
+        HASH_PREFIX,
+        content_hash(&code),
+        code
+    )
+}
+
+/// FNV-1a over the code with line endings normalized, so a checkout that converts them
+/// doesn't count as an edit
+fn content_hash(code: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in code.bytes().filter(|byte| *byte != b'\r') {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("fnv1a64:{:016x}", hash)
+}
+
+/// Whether `content` is a generated file whose code still matches its hash. `None` if it
+/// wasn't generated by the trait editor at all.
+fn is_pristine(content: &str) -> Option<bool> {
+    if !content.starts_with(GENERATED_MARKER) {
+        return None;
+    }
+    let hash_start = content.find(HASH_PREFIX)?;
+    let after_prefix = &content[hash_start + HASH_PREFIX.len()..];
+    let line_end = after_prefix.find('\n').unwrap_or(after_prefix.len());
+    let recorded = after_prefix[..line_end].trim();
+    // The code follows the blank line closing the header
+    let code = after_prefix[line_end..].trim_start_matches(['\r', '\n']);
+    Some(recorded == content_hash(code))
+}
+
+/// Regenerates the output file of `asset`, if it has one. With `force`, hand-edited and
+/// foreign files are overwritten too.
+pub fn write_output(asset: &TraitAsset, asset_file: &Path, force: bool) -> Result<OutputStatus, String> {
+    let Some(path) = output_file(asset, asset_file) else {
+        return Ok(OutputStatus::Disabled);
+    };
+    let content = generated_file(asset, asset_file);
+
+    if let Ok(existing) = std::fs::read_to_string(&path) {
+        if existing.replace("\r\n", "\n") == content {
+            return Ok(OutputStatus::Unchanged(path));
+        }
+        if !force {
+            match is_pristine(&existing) {
+                Some(true) => {}
+                Some(false) => return Ok(OutputStatus::ManuallyEdited(path)),
+                None => return Ok(OutputStatus::NotGenerated(path)),
+            }
+        }
+    }
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    }
+    // Never left half-written, which would read as a hand edit from then on
+    persistence::write_atomic(&path, &content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(OutputStatus::Written(path))
+}
+
+/// The `trait.json` of every trait asset below `root`
+pub fn trait_asset_files(root: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    scan_trait_assets(root, 0, &mut files);
+    files.sort();
+    files
+}
+
+fn scan_trait_assets(dir: &Path, depth: usize, files: &mut Vec<PathBuf>) {
+    if depth > MAX_SCAN_DEPTH {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name.starts_with('.') || SKIPPED_DIRS.contains(&file_name.as_str()) {
+            continue;
+        }
+        if path.extension().is_some_and(|ext| ext == "trait") {
+            let marker = path.join("trait.json");
+            if marker.is_file() {
+                files.push(marker);
+            }
+        } else {
+            scan_trait_assets(&path, depth + 1, files);
+        }
+    }
+}
+
+/// Outcome of regenerating every trait in a project
+#[derive(Debug, Default)]
+pub struct RegenerateSummary {
+    pub written: usize,
+    pub unchanged: usize,
+    /// Messages for files left alone and assets that failed
+    pub problems: Vec<String>,
+}
+
+impl RegenerateSummary {
+    pub fn message(&self) -> String {
+        let mut message = format!("Regenerated {} trait file(s), {} unchanged", self.written, self.unchanged);
+        if !self.problems.is_empty() {
+            message.push_str(&format!(", {} skipped", self.problems.len()));
+        }
+        message
+    }
+}
+
+/// Regenerates the output file of every trait asset below `root`. `current` overrides
+/// the on-disk version of one asset, so an open editor's unsaved state is used.
+pub fn regenerate_all(root: &Path, current: Option<(&Path, &TraitAsset)>) -> RegenerateSummary {
+    let mut summary = RegenerateSummary::default();
+    for file in trait_asset_files(root) {
+        let loaded;
+        let asset = match current {
+            Some((path, asset)) if path == file => asset,
+            _ => {
+                let parsed = std::fs::read_to_string(&file)
+                    .map_err(|e| e.to_string())
+                    .and_then(|json| schema::load_asset(&json).map_err(|e| e.to_string()));
+                match parsed {
+                    Ok(asset) => {
+                        loaded = asset;
+                        &loaded
+                    }
+                    Err(e) => {
+                        summary.problems.push(format!("{}: {}", file.display(), e));
+                        continue;
+                    }
+                }
+            }
+        };
+
+        match write_output(asset, &file, false) {
+            Ok(OutputStatus::Written(_)) => summary.written += 1,
+            Ok(OutputStatus::Unchanged(_)) => summary.unchanged += 1,
+            Ok(OutputStatus::Disabled) => {}
+            Ok(status) => summary.problems.extend(status.message()),
+            Err(e) => summary.problems.push(e),
+        }
+    }
+    summary
+}
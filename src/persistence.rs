@@ -0,0 +1,123 @@
+//! Crash-safe writing of `trait.json`
+//!
+//! Saves go to a temporary file that is renamed over `trait.json`, so a crash leaves either
+//! the old or the new version and never half of one. The version being replaced is copied
+//! to [`BACKUP_DIR`] first. Dirty editors also autosave to [`RECOVERY_FILE`], which is
+//! offered back when the trait is next opened and removed by every save.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Folder inside the `.trait` folder holding previous versions of `trait.json`
+pub const BACKUP_DIR: &str = ".backups";
+
+/// Number of backups kept, oldest dropped first
+pub const MAX_BACKUPS: usize = 10;
+
+/// Unsaved edits, next to `trait.json`
+pub const RECOVERY_FILE: &str = "trait.recovery.json";
+
+/// How often a dirty editor writes its recovery file
+pub const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Saves `content` to `path`, backing up the version it replaces, and drops the recovery
+/// file that the save makes obsolete
+pub fn save(path: &Path, content: &str) -> std::io::Result<()> {
+    if let Ok(previous) = std::fs::read_to_string(path) {
+        if previous != content {
+            if let Err(e) = backup(path, &previous) {
+                // A missing backup is no reason to lose the save
+                tracing::warn!("Failed to back up {:?}: {}", path, e);
+            }
+        }
+    }
+    write_atomic(path, content)?;
+    clear_recovery(path);
+    Ok(())
+}
+
+/// Writes `content` to a temporary file next to `path` and renames it into place
+pub fn write_atomic(path: &Path, content: &str) -> std::io::Result<()> {
+    let file_name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+    let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+
+    let result = std::fs::File::create(&temp_path).and_then(|mut file| {
+        file.write_all(content.as_bytes())?;
+        file.sync_all()
+    });
+    let result = result.and_then(|()| std::fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// Copies `previous`, the content of `path` about to be replaced, into the backup folder
+fn backup(path: &Path, previous: &str) -> std::io::Result<()> {
+    let dir = backup_dir(path);
+    std::fs::create_dir_all(&dir)?;
+    let millis = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    // Zero-padded so names sort by age, numbered for saves within the same millisecond
+    let name = (0..)
+        .map(|n| dir.join(format!("trait.{:016}.{:04}.json", millis, n)))
+        .find(|name| !name.exists())
+        .unwrap_or_default();
+    write_atomic(&name, previous)?;
+
+    let mut backups = backups(path);
+    while backups.len() > MAX_BACKUPS {
+        std::fs::remove_file(backups.remove(0))?;
+    }
+    Ok(())
+}
+
+pub fn backup_dir(path: &Path) -> PathBuf {
+    path.with_file_name(BACKUP_DIR)
+}
+
+/// Backups of `path`, oldest first
+pub fn backups(path: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(backup_dir(path)) else {
+        return Vec::new();
+    };
+    let mut backups: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("trait.") && name.ends_with(".json"))
+        })
+        .collect();
+    backups.sort();
+    backups
+}
+
+pub fn recovery_path(path: &Path) -> PathBuf {
+    path.with_file_name(RECOVERY_FILE)
+}
+
+pub fn write_recovery(path: &Path, content: &str) -> std::io::Result<()> {
+    write_atomic(&recovery_path(path), content)
+}
+
+pub fn clear_recovery(path: &Path) {
+    let recovery = recovery_path(path);
+    if recovery.exists() {
+        if let Err(e) = std::fs::remove_file(&recovery) {
+            tracing::warn!("Failed to remove {:?}: {}", recovery, e);
+        }
+    }
+}
+
+/// Content of the recovery file of `path`, if it was written after `path` was last saved
+pub fn pending_recovery(path: &Path) -> Option<(String, SystemTime)> {
+    let recovery = recovery_path(path);
+    let recovered_at = std::fs::metadata(&recovery).and_then(|metadata| metadata.modified()).ok()?;
+    let saved_at = std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    if saved_at.is_some_and(|saved_at| saved_at >= recovered_at) {
+        return None;
+    }
+    let content = std::fs::read_to_string(&recovery).ok()?;
+    Some((content, recovered_at))
+}
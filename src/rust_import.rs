@@ -0,0 +1,933 @@
+//! Imports hand-written Rust traits into trait assets
+//!
+//! A small tokenizer and item parser, enough for trait declarations: [`find_traits`] finds
+//! every `trait` item in a source file, including those nested in modules, and converts
+//! each into a [`TraitAsset`]. Syntax the asset can't represent is kept as source text in
+//! `meta` and reported as a warning rather than dropped.
+
+use serde_json::Value;
+use ui_types_common::{MethodParam, MethodSignature, TraitAsset, TraitMethod, TypeKind, TypeRef};
+use crate::ident::is_valid_param_name;
+use crate::model::{
+    attribute_body, split_bounds, AssociatedConst, AssociatedType, GenericParam, MethodMeta, Receiver,
+    TraitMeta, Visibility, WherePredicate,
+};
+use crate::schema::CURRENT_SCHEMA_VERSION;
+use crate::type_expr::TypeExpr;
+use crate::type_picker::PRIMITIVE_TYPES;
+
+/// Key in a method's meta holding the original signature when parts of it couldn't be imported
+pub const IMPORT_SOURCE_KEY: &str = "import_source";
+
+/// A trait found in Rust source, already converted
+pub struct ImportedTrait {
+    pub name: String,
+    pub asset: TraitAsset,
+    /// Everything that was imported lossily or kept verbatim
+    pub warnings: Vec<String>,
+}
+
+/// Finds and converts every trait declared in `source`.
+///
+/// Fails only on source that isn't even tokenizable or whose brackets don't balance;
+/// anything else that can't be imported becomes a warning.
+pub fn find_traits(source: &str) -> Result<Vec<ImportedTrait>, String> {
+    let tokens = tokenize(source)?;
+    check_delimiters(source, &tokens)?;
+    let mut parser = Parser { src: source, tokens, pos: 0 };
+    Ok(parser.parse_file())
+}
+
+/// Copies what Rust source can't express from `current` into `parsed`, for source that
+/// was generated from `current` and edited: the display name, unless the trait was
+/// renamed, the output settings, and meta keys unknown to the editor, matching methods
+/// by name.
+pub fn carry_over_editor_data(current: &TraitAsset, parsed: &mut TraitAsset) {
+    if parsed.name == current.name {
+        parsed.display_name = current.display_name.clone();
+    }
+    parsed.schema_version = current.schema_version;
+
+    let current_meta = TraitMeta::from_asset(current);
+    let mut meta = TraitMeta::from_asset(parsed);
+    meta.output_path = current_meta.output_path.clone();
+    meta.module_path = current_meta.module_path.clone();
+    for (key, value) in &current_meta.extra {
+        meta.extra.entry(key.clone()).or_insert_with(|| value.clone());
+    }
+    for (index, method) in parsed.methods.iter().enumerate() {
+        let Some(current_index) = current.methods.iter().position(|m| m.name == method.name) else {
+            continue;
+        };
+        let extra = current_meta.method(current_index).extra;
+        for (key, value) in extra.into_iter().filter(|(key, _)| key != IMPORT_SOURCE_KEY) {
+            meta.methods[index].extra.entry(key).or_insert(value);
+        }
+    }
+    meta.write_to(parsed);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum TokenKind {
+    Ident,
+    Lifetime,
+    Literal,
+    /// An outer `///` or `/** */` doc comment
+    Doc,
+    Punct(char),
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+fn tokenize(src: &str) -> Result<Vec<Token>, String> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = src[i..].chars().next().unwrap_or_default();
+        let rest = &src[i..];
+        let start = i;
+
+        if c.is_whitespace() {
+            i += c.len_utf8();
+        } else if rest.starts_with("//") {
+            let end = rest.find('\n').map(|n| i + n).unwrap_or(bytes.len());
+            if rest.starts_with("///") && !rest.starts_with("////") {
+                tokens.push(Token { kind: TokenKind::Doc, start, end });
+            }
+            i = end;
+        } else if rest.starts_with("/*") {
+            let mut depth = 0;
+            let mut j = i;
+            loop {
+                if j >= bytes.len() {
+                    return Err(format!("Line {}: block comment is never closed", src[..start].matches('\n').count() + 1));
+                }
+                if src[j..].starts_with("/*") {
+                    depth += 1;
+                    j += 2;
+                } else if src[j..].starts_with("*/") {
+                    depth -= 1;
+                    j += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    j += 1;
+                }
+            }
+            let is_doc = rest.starts_with("/**") && !rest.starts_with("/***") && !rest.starts_with("/**/");
+            if is_doc {
+                tokens.push(Token { kind: TokenKind::Doc, start, end: j });
+            }
+            i = j;
+        } else if let Some(len) = string_literal_len(rest) {
+            i += len;
+            tokens.push(Token { kind: TokenKind::Literal, start, end: i });
+        } else if c == '\'' {
+            let mut chars = rest[1..].chars();
+            let first = chars.next().ok_or("Unexpected end of input after `'`")?;
+            let second = chars.next();
+            if first == '\\' || second == Some('\'') {
+                // Character literal
+                let mut j = i + 1 + first.len_utf8();
+                if first == '\\' {
+                    j += second.map(char::len_utf8).unwrap_or(0);
+                }
+                while j < bytes.len() && bytes[j] != b'\'' {
+                    j += 1;
+                }
+                i = (j + 1).min(bytes.len());
+                tokens.push(Token { kind: TokenKind::Literal, start, end: i });
+            } else {
+                i += 1;
+                i += ident_len(&src[i..]);
+                tokens.push(Token { kind: TokenKind::Lifetime, start, end: i });
+            }
+        } else if c == '_' || c.is_alphabetic() {
+            i += match rest.strip_prefix("r#") {
+                Some(raw) => 2 + ident_len(raw),
+                None => ident_len(rest),
+            };
+            tokens.push(Token { kind: TokenKind::Ident, start, end: i });
+        } else if c.is_ascii_digit() {
+            i += 1;
+            while i < bytes.len() {
+                let b = bytes[i];
+                let fraction = b == b'.' && bytes.get(i + 1).is_some_and(u8::is_ascii_digit);
+                if b.is_ascii_alphanumeric() || b == b'_' || fraction {
+                    i += 1;
+                } else {
+                    break;
+                }
+            }
+            tokens.push(Token { kind: TokenKind::Literal, start, end: i });
+        } else {
+            i += c.len_utf8();
+            tokens.push(Token { kind: TokenKind::Punct(c), start, end: i });
+        }
+    }
+    Ok(tokens)
+}
+
+/// Checks that every `(`, `[` and `{` is closed by its matching bracket
+fn check_delimiters(src: &str, tokens: &[Token]) -> Result<(), String> {
+    let line = |offset: usize| src[..offset].matches('\n').count() + 1;
+    let mut open: Vec<(char, usize)> = Vec::new();
+    for token in tokens {
+        let TokenKind::Punct(c) = token.kind else {
+            continue;
+        };
+        let expected = match c {
+            '(' | '[' | '{' => {
+                open.push((c, token.start));
+                continue;
+            }
+            ')' => '(',
+            ']' => '[',
+            '}' => '{',
+            _ => continue,
+        };
+        match open.pop() {
+            Some((opener, _)) if opener == expected => {}
+            Some((opener, start)) => {
+                return Err(format!(
+                    "Line {}: `{}` doesn't match the `{}` on line {}",
+                    line(token.start),
+                    c,
+                    opener,
+                    line(start)
+                ));
+            }
+            None => return Err(format!("Line {}: unexpected `{}`", line(token.start), c)),
+        }
+    }
+    match open.pop() {
+        Some((opener, start)) => Err(format!("Line {}: `{}` is never closed", line(start), opener)),
+        None => Ok(()),
+    }
+}
+
+fn ident_len(text: &str) -> usize {
+    text.char_indices()
+        .find(|(_, c)| !(*c == '_' || c.is_alphanumeric()))
+        .map(|(ix, _)| ix)
+        .unwrap_or(text.len())
+}
+
+/// Length of the string literal (plain, byte, C or raw) at the start of `text`, if any
+fn string_literal_len(text: &str) -> Option<usize> {
+    let prefix_len = ["br", "cr", "b", "c", "r", ""]
+        .iter()
+        .find(|prefix| {
+            text.strip_prefix(**prefix)
+                .is_some_and(|rest| rest.starts_with('"') || (prefix.ends_with('r') && rest.starts_with('#')))
+        })?
+        .len();
+    let raw = text[..prefix_len].ends_with('r');
+    let rest = &text[prefix_len..];
+
+    if raw {
+        let hashes = rest.len() - rest.trim_start_matches('#').len();
+        let body = rest[hashes..].strip_prefix('"')?;
+        let closing = format!("\"{}", "#".repeat(hashes));
+        let end = body.find(&closing)?;
+        return Some(prefix_len + hashes + 1 + end + closing.len());
+    }
+
+    let mut escaped = false;
+    for (ix, c) in rest.char_indices().skip(1) {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '"' => return Some(prefix_len + ix + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Doc comments, attributes and modifiers read ahead of an item
+#[derive(Default)]
+struct ItemPrefix {
+    start: Option<usize>,
+    docs: Vec<String>,
+    attributes: Vec<String>,
+    visibility: Option<String>,
+    is_unsafe: bool,
+    is_auto: bool,
+}
+
+impl ItemPrefix {
+    fn doc(&self) -> Option<String> {
+        if self.docs.is_empty() {
+            return None;
+        }
+        // Drop the single space conventionally written after `///`
+        let strip_space = self.docs.iter().all(|line| line.trim_end().is_empty() || line.starts_with(' '));
+        let text: Vec<&str> = self
+            .docs
+            .iter()
+            .map(|line| if strip_space { line.strip_prefix(' ').unwrap_or(line) } else { line.as_str() })
+            .map(str::trim_end)
+            .collect();
+        Some(text.join("\n").trim().to_string()).filter(|doc| !doc.is_empty())
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn kind(&self, index: usize) -> Option<TokenKind> {
+        self.tokens.get(index).map(|token| token.kind)
+    }
+
+    fn text(&self, index: usize) -> &'a str {
+        self.tokens.get(index).map(|token| &self.src[token.start..token.end]).unwrap_or_default()
+    }
+
+    fn is_ident(&self, index: usize, word: &str) -> bool {
+        self.kind(index) == Some(TokenKind::Ident) && self.text(index) == word
+    }
+
+    fn is_punct(&self, index: usize, c: char) -> bool {
+        self.kind(index) == Some(TokenKind::Punct(c))
+    }
+
+    /// Source text of tokens `from..to`, including the comments and spacing between them
+    fn span_text(&self, from: usize, to: usize) -> &'a str {
+        if from >= to || to > self.tokens.len() {
+            return "";
+        }
+        &self.src[self.tokens[from].start..self.tokens[to - 1].end]
+    }
+
+    /// Index of the bracket closing the `(`, `[` or `{` at `open`
+    fn group_end(&self, open: usize) -> usize {
+        let mut depth = 0;
+        for index in open..self.tokens.len() {
+            match self.kind(index) {
+                Some(TokenKind::Punct('(' | '[' | '{')) => depth += 1,
+                Some(TokenKind::Punct(')' | ']' | '}')) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return index;
+                    }
+                }
+                _ => {}
+            }
+        }
+        self.tokens.len()
+    }
+
+    /// Index of the `>` closing the `<` at `open`, skipping `->` arrows
+    fn angle_end(&self, open: usize) -> usize {
+        let mut depth = 0;
+        let mut index = open;
+        while index < self.tokens.len() {
+            match self.kind(index) {
+                Some(TokenKind::Punct('<')) => depth += 1,
+                Some(TokenKind::Punct('>')) if !self.is_arrow_head(index) => {
+                    depth -= 1;
+                    if depth == 0 {
+                        return index;
+                    }
+                }
+                Some(TokenKind::Punct('(' | '[' | '{')) => index = self.group_end(index),
+                _ => {}
+            }
+            index += 1;
+        }
+        self.tokens.len()
+    }
+
+    /// Whether the `>` at `index` is part of `->` or `=>`
+    fn is_arrow_head(&self, index: usize) -> bool {
+        index > 0
+            && matches!(self.kind(index - 1), Some(TokenKind::Punct('-' | '=')))
+            && self.tokens[index - 1].end == self.tokens[index].start
+    }
+
+    /// First index in `from..to` where `stop` holds outside of any brackets, or `to`
+    fn scan(&self, from: usize, to: usize, stop: impl Fn(&Self, usize) -> bool) -> usize {
+        let mut index = from;
+        let mut angle_depth = 0;
+        while index < to {
+            if angle_depth == 0 && stop(self, index) {
+                return index;
+            }
+            match self.kind(index) {
+                Some(TokenKind::Punct('(' | '[' | '{')) => index = self.group_end(index),
+                Some(TokenKind::Punct('<')) => angle_depth += 1,
+                Some(TokenKind::Punct('>')) if !self.is_arrow_head(index) && angle_depth > 0 => angle_depth -= 1,
+                _ => {}
+            }
+            index += 1;
+        }
+        to
+    }
+
+    /// Byte offset of the start of the line `offset` is on, if only whitespace precedes it
+    fn line_start(&self, offset: usize) -> usize {
+        let before = &self.src[..offset];
+        let line_start = before.rfind('\n').map(|n| n + 1).unwrap_or(0);
+        if before[line_start..].trim().is_empty() { line_start } else { offset }
+    }
+
+    /// Reads doc comments, attributes, visibility and `unsafe`/`auto` ahead of an item.
+    /// Inner attributes (`#![...]`) are skipped.
+    fn read_prefix(&mut self, end: usize) -> ItemPrefix {
+        let mut prefix = ItemPrefix::default();
+        while self.pos < end {
+            let start = self.pos;
+            match self.kind(self.pos) {
+                Some(TokenKind::Doc) => {
+                    prefix.docs.extend(doc_lines(self.text(self.pos)));
+                    self.pos += 1;
+                }
+                Some(TokenKind::Punct('#')) if self.is_punct(self.pos + 1, '!') && self.is_punct(self.pos + 2, '[') => {
+                    self.pos = self.group_end(self.pos + 2) + 1;
+                    continue;
+                }
+                Some(TokenKind::Punct('#')) if self.is_punct(self.pos + 1, '[') => {
+                    let close = self.group_end(self.pos + 1);
+                    prefix.attributes.push(collapse_whitespace(self.span_text(self.pos + 2, close)));
+                    self.pos = close + 1;
+                }
+                Some(TokenKind::Ident) if self.text(self.pos) == "pub" => {
+                    let mut visibility = "pub".to_string();
+                    self.pos += 1;
+                    if self.is_punct(self.pos, '(') {
+                        let close = self.group_end(self.pos);
+                        visibility = format!("pub({})", collapse_whitespace(self.span_text(self.pos + 1, close)));
+                        self.pos = close + 1;
+                    }
+                    prefix.visibility = Some(visibility);
+                }
+                _ => break,
+            }
+            prefix.start.get_or_insert(start);
+        }
+        prefix
+    }
+
+    fn parse_file(&mut self) -> Vec<ImportedTrait> {
+        let mut traits = Vec::new();
+        while self.pos < self.tokens.len() {
+            let mut prefix = self.read_prefix(self.tokens.len());
+            let start = prefix.start.unwrap_or(self.pos);
+            for modifier in ["unsafe", "auto"] {
+                if self.is_ident(self.pos, modifier) {
+                    match modifier {
+                        "unsafe" => prefix.is_unsafe = true,
+                        _ => prefix.is_auto = true,
+                    }
+                    self.pos += 1;
+                }
+            }
+            prefix.start = Some(start);
+
+            if self.is_ident(self.pos, "trait") && self.kind(self.pos + 1) == Some(TokenKind::Ident) {
+                self.pos += 1;
+                if let Some(imported) = self.parse_trait(prefix) {
+                    traits.push(imported);
+                }
+            } else {
+                // Anything else is stepped over token by token, so traits nested in
+                // modules or other blocks are still found
+                self.pos += 1;
+            }
+        }
+        traits
+    }
+
+    fn parse_trait(&mut self, prefix: ItemPrefix) -> Option<ImportedTrait> {
+        let name = self.text(self.pos).to_string();
+        self.pos += 1;
+        let mut warnings = Vec::new();
+        let mut meta = TraitMeta::default();
+
+        if self.is_punct(self.pos, '<') {
+            let close = self.angle_end(self.pos);
+            meta.generics.params = parse_generic_params(self.span_text(self.pos + 1, close), &mut warnings);
+            self.pos = close + 1;
+        }
+        if self.is_punct(self.pos, '=') {
+            // Trait alias, which has no trait body to import
+            self.pos = self.scan(self.pos, self.tokens.len(), |p, ix| p.is_punct(ix, ';')) + 1;
+            return None;
+        }
+        if self.is_punct(self.pos, ':') {
+            let end = self.scan(self.pos + 1, self.tokens.len(), |p, ix| p.is_ident(ix, "where") || p.is_punct(ix, '{'));
+            meta.supertraits = split_bounds(&collapse_whitespace(self.span_text(self.pos + 1, end)));
+            self.pos = end;
+        }
+        if self.is_ident(self.pos, "where") {
+            let end = self.scan(self.pos + 1, self.tokens.len(), |p, ix| p.is_punct(ix, '{'));
+            meta.generics.where_clause = parse_where_clause(self.span_text(self.pos + 1, end));
+            self.pos = end;
+        }
+        if !self.is_punct(self.pos, '{') {
+            return None;
+        }
+        let close = self.group_end(self.pos);
+
+        meta.visibility = match prefix.visibility.as_deref() {
+            None => Visibility::Private,
+            Some("pub") => Visibility::Public,
+            Some("pub(crate)") => Visibility::Crate,
+            Some(other) => {
+                warnings.push(format!("`{}` imported as `pub(crate)`", other));
+                Visibility::Crate
+            }
+        };
+        if prefix.is_auto {
+            warnings.push("`auto` trait imported as a regular trait".to_string());
+        }
+        meta.is_unsafe = prefix.is_unsafe;
+        meta.attributes = prefix.attributes.iter().map(|attribute| attribute_body(attribute).to_string()).collect();
+
+        let mut methods = Vec::new();
+        self.pos += 1;
+        while self.pos < close {
+            self.parse_trait_item(close, &name, &mut methods, &mut meta, &mut warnings);
+        }
+        self.pos = close + 1;
+
+        for warning in &mut warnings {
+            *warning = format!("{}: {}", name, warning);
+        }
+
+        let (methods, method_metas): (Vec<_>, Vec<_>) = methods.into_iter().unzip();
+        meta.methods = method_metas;
+        let mut asset = TraitAsset {
+            schema_version: CURRENT_SCHEMA_VERSION,
+            type_kind: TypeKind::Trait,
+            name: name.clone(),
+            display_name: display_name(&name),
+            description: prefix.doc(),
+            methods,
+            meta: Value::Object(Default::default()),
+        };
+        meta.write_to(&mut asset);
+
+        Some(ImportedTrait { name, asset, warnings })
+    }
+
+    fn parse_trait_item(
+        &mut self,
+        close: usize,
+        trait_name: &str,
+        methods: &mut Vec<(TraitMethod, MethodMeta)>,
+        meta: &mut TraitMeta,
+        warnings: &mut Vec<String>,
+    ) {
+        let prefix = self.read_prefix(close);
+        let start = prefix.start.unwrap_or(self.pos);
+        let keyword_pos = (self.pos..close)
+            .find(|&ix| !matches!(self.text(ix), "const" | "async" | "unsafe" | "extern") && self.kind(ix) != Some(TokenKind::Literal))
+            .unwrap_or(close);
+        let is_assoc_const = self.is_ident(self.pos, "const") && keyword_pos == self.pos + 1;
+
+        if self.is_ident(keyword_pos, "fn") && !is_assoc_const {
+            let item = self.parse_method(prefix, keyword_pos, close, warnings);
+            methods.push(item);
+            return;
+        }
+
+        let end = self.item_end(close);
+        let has_extras = !prefix.docs.is_empty() || !prefix.attributes.is_empty();
+        let assoc = if self.is_ident(self.pos, "type") {
+            self.parse_associated_type(end).map(|item| meta.associated_types.push(item))
+        } else if is_assoc_const {
+            self.parse_associated_const(end).map(|item| meta.associated_consts.push(item))
+        } else {
+            None
+        };
+
+        if assoc.is_some() && has_extras {
+            // The item is modeled but its docs or attributes are not; keep the source instead
+            if self.is_ident(self.pos, "type") {
+                meta.associated_types.pop();
+            } else {
+                meta.associated_consts.pop();
+            }
+        }
+        if assoc.is_none() || has_extras {
+            let item = collapse_whitespace(self.span_text(self.pos, end + 1));
+            warnings.push(format!("`{}` in `{}` kept as source", item, trait_name));
+            meta.verbatim_items.push(self.item_source(start, end));
+        }
+        self.pos = end + 1;
+    }
+
+    /// Index of the `;` or `}` ending the item at the current position
+    fn item_end(&self, close: usize) -> usize {
+        let end = self.scan(self.pos, close, |p, ix| p.is_punct(ix, ';') || p.is_punct(ix, '{'));
+        if self.is_punct(end, '{') { self.group_end(end) } else { end }
+    }
+
+    /// Source of tokens `start..=end` with the indentation of the first line kept
+    fn item_source(&self, start: usize, end: usize) -> String {
+        let from = self.line_start(self.tokens[start].start);
+        let to = self.tokens.get(end).map(|token| token.end).unwrap_or(self.src.len());
+        self.src[from..to].trim_end().to_string()
+    }
+
+    /// `type Name: Bounds = Default;`; generic associated types are left to the caller
+    fn parse_associated_type(&self, end: usize) -> Option<AssociatedType> {
+        let name_pos = self.pos + 1;
+        if self.kind(name_pos) != Some(TokenKind::Ident) || self.is_punct(name_pos + 1, '<') {
+            return None;
+        }
+        let mut item = AssociatedType { name: self.text(name_pos).to_string(), ..Default::default() };
+        let mut index = name_pos + 1;
+        if self.is_punct(index, ':') {
+            let bounds_end = self.scan(index + 1, end, |p, ix| p.is_punct(ix, '=') || p.is_ident(ix, "where"));
+            item.bounds = split_bounds(&collapse_whitespace(self.span_text(index + 1, bounds_end)));
+            index = bounds_end;
+        }
+        if self.is_punct(index, '=') {
+            item.default = Some(collapse_whitespace(self.span_text(index + 1, end)));
+            index = end;
+        }
+        (index == end).then_some(item)
+    }
+
+    /// `const NAME: Type = default;`
+    fn parse_associated_const(&self, end: usize) -> Option<AssociatedConst> {
+        let name_pos = self.pos + 1;
+        if !self.is_punct(name_pos + 1, ':') {
+            return None;
+        }
+        let ty_end = self.scan(name_pos + 2, end, |p, ix| p.is_punct(ix, '='));
+        let default = (ty_end < end).then(|| collapse_whitespace(self.span_text(ty_end + 1, end)));
+        Some(AssociatedConst {
+            name: self.text(name_pos).to_string(),
+            ty: collapse_whitespace(self.span_text(name_pos + 2, ty_end)),
+            default,
+        })
+    }
+
+    fn parse_method(
+        &mut self,
+        prefix: ItemPrefix,
+        fn_pos: usize,
+        close: usize,
+        warnings: &mut Vec<String>,
+    ) -> (TraitMethod, MethodMeta) {
+        let mut meta = MethodMeta::default();
+        let mut lossy = Vec::new();
+        let signature_start = self.pos;
+
+        // Qualifiers between the prefix and `fn`
+        let mut index = self.pos;
+        while index < fn_pos {
+            match self.text(index) {
+                // Rejected by rustc in traits (E0379), so there's nothing to keep it in
+                "const" => lossy.push("`const` on a trait method".to_string()),
+                "async" => meta.is_async = true,
+                "unsafe" => meta.is_unsafe = true,
+                "extern" => {
+                    let abi = if self.kind(index + 1) == Some(TokenKind::Literal) {
+                        index += 1;
+                        self.text(index).trim_matches('"').to_string()
+                    } else {
+                        String::new()
+                    };
+                    meta.abi = Some(abi);
+                }
+                _ => {}
+            }
+            index += 1;
+        }
+        if let Some(visibility) = &prefix.visibility {
+            lossy.push(format!("`{}` on a trait method", visibility));
+        }
+        meta.attributes = prefix.attributes.iter().map(|attribute| attribute_body(attribute).to_string()).collect();
+
+        let name = self.text(fn_pos + 1).to_string();
+        index = fn_pos + 2;
+        if self.is_punct(index, '<') {
+            let angle_close = self.angle_end(index);
+            meta.generics.params = parse_generic_params(self.span_text(index + 1, angle_close), &mut lossy);
+            index = angle_close + 1;
+        }
+
+        let mut params = Vec::new();
+        if self.is_punct(index, '(') {
+            let paren_close = self.group_end(index);
+            let mut param_texts = split_top_level(self.span_text(index + 1, paren_close), ',');
+            param_texts.retain(|text| !text.trim().is_empty());
+
+            if let Some(receiver) = param_texts.first().and_then(|text| parse_receiver(text, &mut lossy)) {
+                meta.receiver = receiver;
+                param_texts.remove(0);
+            } else {
+                meta.receiver = Receiver::None;
+            }
+            for (param_index, text) in param_texts.iter().enumerate() {
+                params.push(parse_param(text, param_index, &mut lossy));
+            }
+            index = paren_close + 1;
+        }
+
+        let mut return_type = TypeRef::Primitive { name: "()".to_string() };
+        if self.is_punct(index, '-') && self.is_punct(index + 1, '>') {
+            let end = self.scan(index + 2, close, |p, ix| p.is_ident(ix, "where") || p.is_punct(ix, '{') || p.is_punct(ix, ';'));
+            return_type = type_ref(self.span_text(index + 2, end), &mut lossy);
+            index = end;
+        }
+        if self.is_ident(index, "where") {
+            let end = self.scan(index + 1, close, |p, ix| p.is_punct(ix, '{') || p.is_punct(ix, ';'));
+            meta.generics.where_clause = parse_where_clause(self.span_text(index + 1, end));
+            index = end;
+        }
+
+        let mut default_body = None;
+        let signature_end = index;
+        if self.is_punct(index, '{') {
+            let body_close = self.group_end(index);
+            let body = &self.src[self.tokens[index].end..self.tokens.get(body_close).map(|t| t.start).unwrap_or(self.src.len())];
+            default_body = Some(body.trim_matches('\n').trim_end().to_string());
+            index = body_close;
+        }
+        self.pos = index + 1;
+
+        if !lossy.is_empty() {
+            let signature = collapse_whitespace(self.span_text(signature_start, signature_end));
+            meta.extra.insert(IMPORT_SOURCE_KEY.to_string(), Value::String(signature));
+            for note in lossy {
+                warnings.push(format!("`{}`: {}", name, note));
+            }
+        }
+
+        let method = TraitMethod {
+            name,
+            signature: MethodSignature { params, return_type },
+            default_body,
+            doc: prefix.doc(),
+        };
+        (method, meta)
+    }
+}
+
+/// Recognizes a `self` parameter; other first parameters return `None`
+fn parse_receiver(text: &str, lossy: &mut Vec<String>) -> Option<Receiver> {
+    let text = collapse_whitespace(text);
+    let (pattern, ty) = match split_top_level_colon(&text) {
+        Some((pattern, ty)) => (pattern.trim().to_string(), Some(ty.trim().to_string())),
+        None => (text.clone(), None),
+    };
+    let pattern = pattern.strip_prefix("mut ").unwrap_or(&pattern).trim();
+
+    let ty = match ty {
+        Some(ty) if pattern == "self" => TypeExpr::parse(&ty).map(|expr| expr.to_string()).unwrap_or(ty),
+        Some(_) => return None,
+        None => match pattern.replace(' ', "").as_str() {
+            "self" => "Self".to_string(),
+            "&self" => "&Self".to_string(),
+            "&mutself" => "&mut Self".to_string(),
+            shorthand if shorthand.starts_with("&'") && shorthand.ends_with("self") => {
+                lossy.push(format!("lifetime on the `{}` receiver", pattern));
+                if shorthand.ends_with("mutself") { "&mut Self" } else { "&Self" }.to_string()
+            }
+            _ => return None,
+        },
+    };
+
+    let receiver = match ty.as_str() {
+        "Self" => Receiver::Value,
+        "&Self" => Receiver::Ref,
+        "&mut Self" => Receiver::RefMut,
+        "Box<Self>" | "std::boxed::Box<Self>" => Receiver::Box,
+        "Rc<Self>" | "std::rc::Rc<Self>" => Receiver::Rc,
+        "Arc<Self>" | "std::sync::Arc<Self>" => Receiver::Arc,
+        "Pin<&mut Self>" | "std::pin::Pin<&mut Self>" => Receiver::PinMut,
+        other => {
+            lossy.push(format!("receiver `self: {}` imported as `self`", other));
+            Receiver::Value
+        }
+    };
+    Some(receiver)
+}
+
+fn parse_param(text: &str, index: usize, lossy: &mut Vec<String>) -> MethodParam {
+    let text = collapse_whitespace(text);
+    let (pattern, ty) = split_top_level_colon(&text).unwrap_or((text.as_str(), "_"));
+    let mut name = pattern.trim();
+    if let Some(rest) = name.strip_prefix("mut ") {
+        lossy.push(format!("`mut` on parameter `{}`", rest.trim()));
+        name = rest.trim();
+    }
+    let name = if is_valid_param_name(name) {
+        name.to_string()
+    } else {
+        let fallback = format!("param{}", index + 1);
+        lossy.push(format!("pattern `{}` imported as `{}`", name, fallback));
+        fallback
+    };
+    MethodParam { name, type_ref: type_ref(ty, lossy) }
+}
+
+/// Primitive names become [`TypeRef::Primitive`], everything else a canonical path type
+fn type_ref(text: &str, lossy: &mut Vec<String>) -> TypeRef {
+    let text = collapse_whitespace(text);
+    if PRIMITIVE_TYPES.contains(&text.as_str()) {
+        return TypeRef::Primitive { name: text };
+    }
+    match TypeExpr::parse(&text) {
+        Ok(expr) => TypeRef::Path { path: expr.to_string() },
+        Err(e) => {
+            lossy.push(format!("type `{}` kept as text ({})", text, e));
+            TypeRef::Path { path: text }
+        }
+    }
+}
+
+fn parse_generic_params(text: &str, lossy: &mut Vec<String>) -> Vec<GenericParam> {
+    split_top_level(text, ',')
+        .into_iter()
+        .map(|param| collapse_whitespace(&param))
+        .filter(|param| !param.is_empty())
+        .filter_map(|param| {
+            let (head, default) = match find_top_level(&param, |rest| rest.starts_with('=') && !rest.starts_with("==")) {
+                Some(ix) => (param[..ix].trim(), Some(param[ix + 1..].trim().to_string())),
+                None => (param.as_str(), None),
+            };
+            let (name, bounds) = match split_top_level_colon(head) {
+                Some((name, bounds)) => (name.trim(), bounds.trim()),
+                None => (head.trim(), ""),
+            };
+
+            if let Some(const_name) = name.strip_prefix("const ") {
+                return Some(GenericParam::Const {
+                    name: const_name.trim().to_string(),
+                    ty: bounds.to_string(),
+                    default: default.map(|d| d.trim_start_matches('{').trim_end_matches('}').trim().to_string()),
+                });
+            }
+            if name.starts_with('\'') {
+                return Some(GenericParam::Lifetime { name: name.to_string(), bounds: split_bounds(bounds) });
+            }
+            if name.is_empty() {
+                lossy.push(format!("generic parameter `{}` dropped", param));
+                return None;
+            }
+            Some(GenericParam::Type { name: name.to_string(), bounds: split_bounds(bounds), default })
+        })
+        .collect()
+}
+
+fn parse_where_clause(text: &str) -> Vec<WherePredicate> {
+    split_top_level(text, ',')
+        .into_iter()
+        .map(|predicate| collapse_whitespace(&predicate))
+        .filter(|predicate| !predicate.is_empty())
+        .map(|predicate| match split_top_level_colon(&predicate) {
+            Some((bounded, bounds)) => WherePredicate { bounded: bounded.trim().to_string(), bounds: split_bounds(bounds) },
+            None => WherePredicate { bounded: predicate.clone(), bounds: Vec::new() },
+        })
+        .collect()
+}
+
+/// Byte index of the first position outside brackets where `matches` holds for the rest
+fn find_top_level(text: &str, matches: impl Fn(&str) -> bool) -> Option<usize> {
+    let mut depth = 0i32;
+    let mut prev = '\0';
+    for (ix, c) in text.char_indices() {
+        if depth == 0 && matches(&text[ix..]) {
+            return Some(ix);
+        }
+        match c {
+            '<' | '(' | '[' | '{' => depth += 1,
+            '>' if prev == '-' || prev == '=' => {}
+            '>' | ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+        prev = c;
+    }
+    None
+}
+
+fn split_top_level(text: &str, separator: char) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut rest = text;
+    while let Some(ix) = find_top_level(rest, |tail| tail.starts_with(separator)) {
+        parts.push(rest[..ix].to_string());
+        rest = &rest[ix + separator.len_utf8()..];
+    }
+    parts.push(rest.to_string());
+    parts
+}
+
+/// Splits `name: Type` at its first top-level single colon, ignoring `::` path separators
+fn split_top_level_colon(text: &str) -> Option<(&str, &str)> {
+    let mut search_from = 0;
+    loop {
+        let ix = search_from + find_top_level(&text[search_from..], |rest| rest.starts_with(':'))?;
+        if text[ix..].starts_with("::") {
+            search_from = ix + 2;
+        } else {
+            return Some((&text[..ix], &text[ix + 1..]));
+        }
+    }
+}
+
+/// The text lines of a doc comment, without the comment markers
+fn doc_lines(comment: &str) -> Vec<String> {
+    if let Some(line) = comment.strip_prefix("///") {
+        return vec![line.trim_end_matches('\r').to_string()];
+    }
+    let inner = comment.trim_start_matches("/**").trim_end_matches("*/");
+    let mut lines: Vec<String> = inner
+        .lines()
+        .map(|line| {
+            // Block comments conventionally start each line with ` * `
+            let trimmed = line.trim_start();
+            match trimmed.strip_prefix('*') {
+                Some(rest) => rest.to_string(),
+                None => line.to_string(),
+            }
+        })
+        .collect();
+    while lines.first().is_some_and(|line| line.trim().is_empty()) {
+        lines.remove(0);
+    }
+    while lines.last().is_some_and(|line| line.trim().is_empty()) {
+        lines.pop();
+    }
+    lines
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// `MyTrait` becomes `My Trait`
+fn display_name(name: &str) -> String {
+    let mut out = String::new();
+    let mut prev_lower = false;
+    for c in name.trim_start_matches("r#").chars() {
+        if c.is_uppercase() && prev_lower {
+            out.push(' ');
+        }
+        if c == '_' {
+            out.push(' ');
+        } else {
+            out.push(c);
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+    }
+    out
+}
@@ -0,0 +1,211 @@
+//! Versioned loading and saving of `trait.json`
+//!
+//! Files are read as plain JSON first and brought up to [`CURRENT_SCHEMA_VERSION`] one
+//! migration at a time, so a format change only needs a new step in [`MIGRATIONS`]. Files
+//! from a newer editor are refused rather than half-read.
+//!
+//! `TraitAsset` drops fields it doesn't know, which would lose data written by a newer
+//! editor or another tool. Unknown fields of the asset and of its methods are stashed in
+//! the editor meta under [`PRESERVED_FIELDS_KEY`] on load and put back in place on save.
+//! Editor meta with known fields of the wrong type is refused, since the first edit would
+//! otherwise replace it with defaults.
+
+use serde_json::{Map, Value};
+use ui_types_common::{TraitAsset, TypeKind};
+use crate::model::TraitMeta;
+
+/// Schema version written by this editor
+pub const CURRENT_SCHEMA_VERSION: u32 = 1;
+
+/// Meta key holding fields the asset types don't model, in `meta` for the asset's own
+/// fields and in `meta.methods[i]` for those of a method
+pub const PRESERVED_FIELDS_KEY: &str = "preserved_fields";
+
+/// One migration step, from the version at its index in [`MIGRATIONS`] to the next
+type Migration = fn(&mut Map<String, Value>) -> Result<(), String>;
+
+/// `MIGRATIONS[n]` upgrades a version `n` document to version `n + 1`
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Why a `trait.json` couldn't be loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadError {
+    /// Not a JSON object
+    Syntax(String),
+    /// Written by a newer version of the editor
+    NewerVersion { found: u64 },
+    /// Valid JSON that doesn't describe a trait, even after migration
+    Invalid(String),
+}
+
+impl std::fmt::Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Syntax(e) => write!(f, "Not valid JSON: {}", e),
+            LoadError::NewerVersion { found } => write!(
+                f,
+                "This trait uses schema version {}, but this editor only understands up to version {}. Update the trait editor plugin to open it.",
+                found, CURRENT_SCHEMA_VERSION
+            ),
+            LoadError::Invalid(e) => write!(f, "Not a valid trait: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+/// Parses a `trait.json` of any supported schema version into a current asset
+pub fn load_asset(json: &str) -> Result<TraitAsset, LoadError> {
+    let mut document = match serde_json::from_str::<Value>(json) {
+        Ok(Value::Object(document)) => document,
+        Ok(_) => return Err(LoadError::Syntax("expected an object".to_string())),
+        Err(e) => return Err(LoadError::Syntax(e.to_string())),
+    };
+
+    // Files from before versioning have no version field
+    let version = match document.get("schema_version") {
+        None => 0,
+        Some(value) => value
+            .as_u64()
+            .ok_or_else(|| LoadError::Invalid(format!("schema_version must be a whole number, found {}", value)))?,
+    };
+    if version > u64::from(CURRENT_SCHEMA_VERSION) {
+        return Err(LoadError::NewerVersion { found: version });
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(&mut document).map_err(|e| LoadError::Invalid(format!("migrating from version {}: {}", from, e)))?;
+        document.insert("schema_version".to_string(), Value::from(from + 1));
+    }
+
+    let mut asset = serde_json::from_value::<TraitAsset>(Value::Object(document.clone()))
+        .map_err(|e| LoadError::Invalid(e.to_string()))?;
+    preserve_unknown_fields(&document, &mut asset);
+    TraitMeta::try_from_asset(&asset).map_err(|e| LoadError::Invalid(format!("malformed editor data in meta: {}", e)))?;
+    Ok(asset)
+}
+
+/// Line and column, both starting at 1, of the JSON syntax error in `json`, if it has one
+pub fn syntax_error_location(json: &str) -> Option<(usize, usize)> {
+    serde_json::from_str::<Value>(json).err().map(|e| (e.line(), e.column()))
+}
+
+/// Serializes `asset` for `trait.json`, with the fields preserved on load back in place
+pub fn asset_to_json(asset: &TraitAsset) -> Result<String, String> {
+    let mut value = serde_json::to_value(asset).map_err(|e| e.to_string())?;
+    restore_unknown_fields(&mut value);
+    serde_json::to_string_pretty(&value).map_err(|e| e.to_string())
+}
+
+/// Version 0 is any file without `schema_version`, such as those created from the plugin's
+/// `{"name", "methods"}` default content. The fields it lacks get the values a new asset
+/// starts with.
+fn migrate_v0_to_v1(document: &mut Map<String, Value>) -> Result<(), String> {
+    let name = match document.get("name") {
+        Some(Value::String(name)) => name.clone(),
+        _ => return Err("the trait has no name".to_string()),
+    };
+    if !document.contains_key("type_kind") {
+        let kind = serde_json::to_value(TypeKind::Trait).map_err(|e| e.to_string())?;
+        document.insert("type_kind".to_string(), kind);
+    }
+    document.entry("display_name").or_insert(Value::String(name));
+    document.entry("methods").or_insert_with(|| Value::Array(Vec::new()));
+    if document.get("meta").is_none_or(Value::is_null) {
+        document.insert("meta".to_string(), Value::Object(Map::new()));
+    }
+    Ok(())
+}
+
+/// Moves the fields of `document` that didn't survive deserializing into `asset`'s meta
+fn preserve_unknown_fields(document: &Map<String, Value>, asset: &mut TraitAsset) {
+    let Ok(Value::Object(known)) = serde_json::to_value(&*asset) else {
+        return;
+    };
+
+    let asset_fields = unknown_fields(document, &known);
+    let method_fields: Vec<Map<String, Value>> = match (document.get("methods"), known.get("methods")) {
+        (Some(Value::Array(original)), Some(Value::Array(known))) => original
+            .iter()
+            .zip(known)
+            .map(|(original, known)| match (original, known) {
+                (Value::Object(original), Value::Object(known)) => unknown_fields(original, known),
+                _ => Map::new(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    if asset_fields.is_empty() && method_fields.iter().all(Map::is_empty) {
+        return;
+    }
+
+    if !asset.meta.is_object() {
+        asset.meta = Value::Object(Map::new());
+    }
+    let Value::Object(meta) = &mut asset.meta else {
+        return;
+    };
+    if !asset_fields.is_empty() {
+        meta.insert(PRESERVED_FIELDS_KEY.to_string(), Value::Object(asset_fields));
+    }
+    if method_fields.iter().any(|fields| !fields.is_empty()) {
+        let entries = meta.entry("methods").or_insert_with(|| Value::Array(Vec::new()));
+        if !entries.is_array() {
+            *entries = Value::Array(Vec::new());
+        }
+        let Value::Array(entries) = entries else {
+            return;
+        };
+        if entries.len() < method_fields.len() {
+            entries.resize_with(method_fields.len(), || Value::Object(Map::new()));
+        }
+        for (entry, fields) in entries.iter_mut().zip(method_fields) {
+            if let (Value::Object(entry), false) = (entry, fields.is_empty()) {
+                entry.insert(PRESERVED_FIELDS_KEY.to_string(), Value::Object(fields));
+            }
+        }
+    }
+}
+
+fn unknown_fields(original: &Map<String, Value>, known: &Map<String, Value>) -> Map<String, Value> {
+    original
+        .iter()
+        .filter(|(key, _)| !known.contains_key(*key))
+        .map(|(key, value)| (key.clone(), value.clone()))
+        .collect()
+}
+
+/// Puts the fields stashed by [`preserve_unknown_fields`] back where they came from. Fields
+/// the asset now models itself win over stale copies.
+fn restore_unknown_fields(value: &mut Value) {
+    let Value::Object(document) = value else {
+        return;
+    };
+
+    let mut method_fields = Vec::new();
+    let mut asset_fields = None;
+    if let Some(Value::Object(meta)) = document.get_mut("meta") {
+        asset_fields = meta.remove(PRESERVED_FIELDS_KEY);
+        if let Some(Value::Array(entries)) = meta.get_mut("methods") {
+            method_fields = entries
+                .iter_mut()
+                .map(|entry| entry.as_object_mut().and_then(|entry| entry.remove(PRESERVED_FIELDS_KEY)))
+                .collect();
+        }
+    }
+
+    if let Some(Value::Object(fields)) = asset_fields {
+        for (key, field) in fields {
+            document.entry(key).or_insert(field);
+        }
+    }
+    if let Some(Value::Array(methods)) = document.get_mut("methods") {
+        for (method, fields) in methods.iter_mut().zip(method_fields) {
+            if let (Value::Object(method), Some(Value::Object(fields))) = (method, fields) {
+                for (key, field) in fields {
+                    method.entry(key).or_insert(field);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use gpui::{prelude::FluentBuilder, *};
+use ui::{v_flex, h_flex, ActiveTheme, StyledExt, Sizable, button::{Button, ButtonVariants}, input::{InputState, TextInput}};
+use crate::codegen::{self, CodegenOptions};
+use crate::ident::identifier_error;
+use crate::templates::TraitTemplate;
+
+actions!(template_wizard, [
+    Dismiss,
+]);
+
+/// Key context set on the wizard
+pub const TEMPLATE_WIZARD_CONTEXT: &str = "NewTraitWizard";
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("escape", Dismiss, Some(TEMPLATE_WIZARD_CONTEXT)),
+    ]);
+}
+
+#[derive(Clone, Debug)]
+pub enum NewTraitEvent {
+    Created { template: TraitTemplate, name: String },
+    Dismissed,
+}
+
+/// "New Trait" wizard: picks a starter template and a name for the trait
+pub struct NewTraitWizard {
+    name_input: Entity<InputState>,
+    selected: TraitTemplate,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl NewTraitWizard {
+    pub fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let selected = TraitTemplate::default();
+        let name_input = cx.new(|cx| {
+            let mut input = InputState::new(window, cx).placeholder("TraitName");
+            input.set_value(selected.default_name(), window, cx);
+            input
+        });
+
+        let name_subscription = cx.subscribe_in(&name_input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+            match event {
+                ui::input::InputEvent::Change => cx.notify(),
+                ui::input::InputEvent::PressEnter { .. } => this.create(cx),
+                _ => {}
+            }
+        });
+
+        Self {
+            name_input,
+            selected,
+            focus_handle: cx.focus_handle(),
+            _subscriptions: vec![name_subscription],
+        }
+    }
+
+    pub fn focus(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let handle = self.name_input.read(cx).focus_handle(cx);
+        window.focus(&handle);
+    }
+
+    fn select(&mut self, template: TraitTemplate, window: &mut Window, cx: &mut Context<Self>) {
+        // A name the user typed is kept; the previous template's default is replaced
+        if self.name(cx) == self.selected.default_name() {
+            self.name_input.update(cx, |input, cx| input.set_value(template.default_name(), window, cx));
+        }
+        self.selected = template;
+        cx.notify();
+    }
+
+    fn name(&self, cx: &App) -> String {
+        self.name_input.read(cx).text().to_string().trim().to_string()
+    }
+
+    fn create(&mut self, cx: &mut Context<Self>) {
+        let name = self.name(cx);
+        if identifier_error(&name).is_none() {
+            cx.emit(NewTraitEvent::Created { template: self.selected, name });
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(NewTraitEvent::Dismissed);
+    }
+}
+
+impl EventEmitter<NewTraitEvent> for NewTraitWizard {}
+
+impl Focusable for NewTraitWizard {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for NewTraitWizard {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected_bg = cx.theme().accent.opacity(0.2);
+        let hover_bg = cx.theme().secondary;
+        let name = self.name(cx);
+        let name_error = identifier_error(&name);
+        let preview = codegen::generate_trait(&self.selected.asset(&name), &CodegenOptions::default());
+
+        let rows = TraitTemplate::ALL.into_iter().map(|template| {
+            v_flex()
+                .id(SharedString::from(format!("trait-template-{:?}", template)))
+                .px_2()
+                .py_1()
+                .rounded(px(4.0))
+                .cursor_pointer()
+                .when(template == self.selected, |this| this.bg(selected_bg))
+                .hover(|this| this.bg(hover_bg))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.select(template, window, cx);
+                }))
+                .child(
+                    div()
+                        .text_sm()
+                        .text_color(cx.theme().foreground)
+                        .child(template.label())
+                )
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(template.description())
+                )
+        }).collect::<Vec<_>>();
+
+        v_flex()
+            .key_context(TEMPLATE_WIZARD_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::dismiss))
+            .w(px(640.0))
+            .max_h(px(640.0))
+            .p_3()
+            .gap_2()
+            .bg(cx.theme().popover)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(px(8.0))
+            .shadow_lg()
+            .child(
+                div()
+                    .text_sm()
+                    .font_semibold()
+                    .text_color(cx.theme().foreground)
+                    .child("New Trait")
+            )
+            .child(TextInput::new(&self.name_input).with_size(ui::Size::Small))
+            .when_some(name_error.clone().filter(|_| !name.is_empty()), |this, error| {
+                this.child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().danger)
+                        .child(error)
+                )
+            })
+            .child(
+                h_flex()
+                    .items_start()
+                    .gap_2()
+                    .child(
+                        v_flex()
+                            .w(px(220.0))
+                            .gap_1()
+                            .children(rows)
+                    )
+                    .child(
+                        div()
+                            .id("trait-template-preview")
+                            .flex_1()
+                            .h(px(320.0))
+                            .p_2()
+                            .overflow_y_scroll()
+                            .rounded(px(4.0))
+                            .bg(cx.theme().secondary)
+                            .text_xs()
+                            .font_family("monospace")
+                            .text_color(cx.theme().foreground)
+                            .child(preview)
+                    )
+            )
+            .child(
+                h_flex()
+                    .justify_end()
+                    .gap_1()
+                    .child(
+                        Button::new("template-cancel")
+                            .ghost()
+                            .label("Cancel")
+                            .with_size(ui::Size::Small)
+                            .on_click(cx.listener(|_this, _, _window, cx| {
+                                cx.emit(NewTraitEvent::Dismissed);
+                            }))
+                    )
+                    .when(name_error.is_none(), |this| {
+                        this.child(
+                            Button::new("template-create")
+                                .primary()
+                                .label("Create")
+                                .with_size(ui::Size::Small)
+                                .on_click(cx.listener(|this, _, _window, cx| {
+                                    this.create(cx);
+                                }))
+                        )
+                    })
+            )
+    }
+}
@@ -0,0 +1,207 @@
+//! Starter templates for new trait assets
+//!
+//! Every template is written as Rust source and imported like a trait pasted into the
+//! import dialog, so templates can use anything the importer understands.
+
+use serde_json::{Map, Value};
+use ui_types_common::{TraitAsset, TypeKind};
+use crate::codegen::{self, CodegenOptions};
+use crate::ident::to_snake_case;
+use crate::rust_import::{find_traits, IMPORT_SOURCE_KEY};
+use crate::schema::CURRENT_SCHEMA_VERSION;
+
+/// Name of a trait that was never edited
+pub const NEW_TRAIT_NAME: &str = "NewTrait";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TraitTemplate {
+    #[default]
+    Empty,
+    Component,
+    System,
+    EventHandler,
+    Serializer,
+}
+
+impl TraitTemplate {
+    pub const ALL: [TraitTemplate; 5] = [
+        TraitTemplate::Empty,
+        TraitTemplate::Component,
+        TraitTemplate::System,
+        TraitTemplate::EventHandler,
+        TraitTemplate::Serializer,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            TraitTemplate::Empty => "Empty",
+            TraitTemplate::Component => "Component",
+            TraitTemplate::System => "System",
+            TraitTemplate::EventHandler => "Event handler",
+            TraitTemplate::Serializer => "Serializer",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            TraitTemplate::Empty => "A trait with no items",
+            TraitTemplate::Component => "Data attached to an entity, with attach and detach hooks",
+            TraitTemplate::System => "Logic the engine runs every frame",
+            TraitTemplate::EventHandler => "Receives events of one type and reports whether it consumed them",
+            TraitTemplate::Serializer => "Converts values to and from bytes",
+        }
+    }
+
+    /// Name the template's trait gets unless the user picks another
+    pub fn default_name(&self) -> &'static str {
+        match self {
+            TraitTemplate::Empty => NEW_TRAIT_NAME,
+            TraitTemplate::Component => "Component",
+            TraitTemplate::System => "System",
+            TraitTemplate::EventHandler => "EventHandler",
+            TraitTemplate::Serializer => "Serializer",
+        }
+    }
+
+    fn source(&self) -> &'static str {
+        match self {
+            TraitTemplate::Empty => "pub trait NewTrait {}",
+            TraitTemplate::Component => COMPONENT_SOURCE,
+            TraitTemplate::System => SYSTEM_SOURCE,
+            TraitTemplate::EventHandler => EVENT_HANDLER_SOURCE,
+            TraitTemplate::Serializer => SERIALIZER_SOURCE,
+        }
+    }
+
+    /// The template's trait, named `name`
+    pub fn asset(&self, name: &str) -> TraitAsset {
+        let mut asset = find_traits(self.source())
+            .ok()
+            .and_then(|mut traits| (!traits.is_empty()).then(|| traits.remove(0).asset))
+            .unwrap_or_else(empty_asset);
+        // The importer records the source of each method, which means nothing here
+        if let Some(Value::Array(methods)) = asset.meta.get_mut("methods") {
+            for method in methods.iter_mut().filter_map(Value::as_object_mut) {
+                method.remove(IMPORT_SOURCE_KEY);
+            }
+        }
+        asset.name = name.to_string();
+        asset.display_name = display_name_for(name);
+        asset
+    }
+
+    /// Files created in the asset folder next to `trait.json`, as paths relative to it
+    /// and their content
+    pub fn extra_files(&self, asset: &TraitAsset) -> Vec<(String, String)> {
+        if *self == TraitTemplate::Empty {
+            return Vec::new();
+        }
+        let skeleton = codegen::generate_impl_skeleton(asset, &format!("My{}", asset.name), &CodegenOptions::default());
+        let readme = format!(
+            "# {}\n\n{}.\n\n## Implementing\n\n```rust\n{}```\n",
+            asset.display_name,
+            self.description(),
+            skeleton
+        );
+        vec![("README.md".to_string(), readme)]
+    }
+}
+
+/// The asset a new `.trait` file starts as
+pub fn empty_asset() -> TraitAsset {
+    TraitAsset {
+        schema_version: CURRENT_SCHEMA_VERSION,
+        type_kind: TypeKind::Trait,
+        name: NEW_TRAIT_NAME.to_string(),
+        display_name: display_name_for(NEW_TRAIT_NAME),
+        description: None,
+        methods: Vec::new(),
+        meta: Value::Object(Map::new()),
+    }
+}
+
+/// Whether `asset` is a new trait nobody has edited yet, so picking a template loses nothing
+pub fn is_untouched(asset: &TraitAsset) -> bool {
+    let empty_meta = match &asset.meta {
+        Value::Null => true,
+        Value::Object(meta) => meta.is_empty(),
+        _ => false,
+    };
+    asset.name == NEW_TRAIT_NAME && asset.methods.is_empty() && asset.description.is_none() && empty_meta
+}
+
+/// `EventHandler` is shown as "Event Handler"
+fn display_name_for(name: &str) -> String {
+    to_snake_case(name)
+        .split('_')
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+const COMPONENT_SOURCE: &str = "
+/// Data attached to an entity
+pub trait Component: Send + Sync + 'static {
+    /// Name shown in the inspector
+    fn type_name(&self) -> &'static str;
+
+    /// Called once the component is added to an entity
+    fn on_attach(&mut self) {}
+
+    /// Called before the component is removed from its entity
+    fn on_detach(&mut self) {}
+}
+";
+
+const SYSTEM_SOURCE: &str = "
+/// Logic the engine runs every frame
+pub trait System: Send {
+    fn name(&self) -> &str;
+
+    /// Runs once before the first update
+    fn init(&mut self) {}
+
+    /// Advances the system by `delta` seconds
+    fn update(&mut self, delta: f32);
+
+    /// Runs once when the system is removed
+    fn shutdown(&mut self) {}
+}
+";
+
+const EVENT_HANDLER_SOURCE: &str = "
+/// Receives events of one type
+pub trait EventHandler {
+    type Event;
+
+    /// Handles `event`, returning whether it was consumed
+    fn handle(&mut self, event: &Self::Event) -> bool;
+
+    /// Handlers with a higher priority see events first
+    fn priority(&self) -> i32 {
+        0
+    }
+}
+";
+
+const SERIALIZER_SOURCE: &str = "
+/// Converts values to and from bytes
+pub trait Serializer {
+    type Value;
+    type Error: std::fmt::Display;
+
+    /// Name of the format, such as `json`
+    fn format_name(&self) -> &'static str;
+
+    fn serialize(&self, value: &Self::Value) -> Result<Vec<u8>, Self::Error>;
+
+    fn deserialize(&self, bytes: &[u8]) -> Result<Self::Value, Self::Error>;
+}
+";
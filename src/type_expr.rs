@@ -0,0 +1,687 @@
+//! Structured Rust type expressions
+//!
+//! [`TypeRef`] stores a type as a primitive name, a path string or an alias name. A
+//! [`TypeExpr`] is the same type as a tree (references, slices, arrays, tuples, generic
+//! applications, trait objects, fn pointers), so it can be checked and edited piece by
+//! piece. Converting a `TypeRef` to a `TypeExpr` and back is lossless: primitives and
+//! aliases are carried over verbatim, and path strings come back in their canonical
+//! spelling (text the parser doesn't understand is kept as [`TypeExpr::Raw`]).
+
+use std::fmt;
+use ui_types_common::TypeRef;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeExpr {
+    /// A primitive from [`TypeRef::Primitive`], e.g. `u32` or `()`
+    Primitive(String),
+    /// A type alias asset from [`TypeRef::AliasRef`]
+    Alias(String),
+    /// `Self`
+    SelfType,
+    /// `std::vec::Vec<T>`; `args` may hold lifetimes and `Item = T` bindings
+    Path { path: String, args: Vec<TypeExpr> },
+    /// `&'a mut T`
+    Reference { lifetime: Option<String>, mutable: bool, inner: Box<TypeExpr> },
+    /// `*const T` or `*mut T`
+    Pointer { mutable: bool, inner: Box<TypeExpr> },
+    /// `[T]`
+    Slice(Box<TypeExpr>),
+    /// `[T; N]`, with the length kept as written
+    Array { elem: Box<TypeExpr>, len: String },
+    /// `(A, B)`; the empty tuple is `()`
+    Tuple(Vec<TypeExpr>),
+    /// `dyn A + B` or `impl A + B`
+    TraitObject { is_impl: bool, bounds: Vec<TypeExpr> },
+    /// `Fn(A, B) -> C` as used in bounds, also `FnMut` and `FnOnce`
+    FnTrait { name: String, inputs: Vec<TypeExpr>, output: Option<Box<TypeExpr>> },
+    /// `unsafe extern "C" fn(A) -> B`
+    FnPointer { is_unsafe: bool, abi: Option<String>, params: Vec<TypeExpr>, ret: Option<Box<TypeExpr>> },
+    /// `!`
+    Never,
+    /// `'a`, as a generic argument or a bound
+    Lifetime(String),
+    /// `Item = T`, as a generic argument
+    Binding { name: String, ty: Box<TypeExpr> },
+    /// Text kept verbatim because it isn't understood
+    Raw(String),
+}
+
+impl TypeExpr {
+    pub fn unit() -> Self {
+        TypeExpr::Tuple(Vec::new())
+    }
+
+    /// Parses a type written in Rust syntax
+    pub fn parse(text: &str) -> Result<TypeExpr, String> {
+        let mut parser = Parser::new(text);
+        parser.skip_ws();
+        if parser.at_end() {
+            return Err("Type cannot be empty".to_string());
+        }
+        let expr = parser.parse_type()?;
+        parser.skip_ws();
+        if !parser.at_end() {
+            return Err(format!("Unexpected `{}`", parser.rest()));
+        }
+        Ok(expr)
+    }
+
+    pub fn from_type_ref(type_ref: &TypeRef) -> Self {
+        match type_ref {
+            TypeRef::Primitive { name } => TypeExpr::Primitive(name.clone()),
+            TypeRef::AliasRef { alias } => TypeExpr::Alias(alias.clone()),
+            TypeRef::Path { path } => TypeExpr::parse(path).unwrap_or_else(|_| TypeExpr::Raw(path.clone())),
+        }
+    }
+
+    pub fn to_type_ref(&self) -> TypeRef {
+        match self {
+            TypeExpr::Primitive(name) => TypeRef::Primitive { name: name.clone() },
+            TypeExpr::Alias(alias) => TypeRef::AliasRef { alias: alias.clone() },
+            other => TypeRef::Path { path: other.to_string() },
+        }
+    }
+
+    /// Short description of the node itself, without its children
+    pub fn label(&self) -> String {
+        match self {
+            TypeExpr::Primitive(name) => name.clone(),
+            TypeExpr::Alias(name) => format!("{} (alias)", name),
+            TypeExpr::SelfType => "Self".to_string(),
+            TypeExpr::Path { path, args } if args.is_empty() => path.clone(),
+            TypeExpr::Path { path, .. } => format!("{}<…>", path),
+            TypeExpr::Reference { lifetime, mutable, .. } => {
+                let lifetime = lifetime.as_ref().map(|l| format!("{} ", l)).unwrap_or_default();
+                format!("&{}{}", lifetime, if *mutable { "mut " } else { "" })
+            }
+            TypeExpr::Pointer { mutable, .. } => if *mutable { "*mut" } else { "*const" }.to_string(),
+            TypeExpr::Slice(_) => "[ ] slice".to_string(),
+            TypeExpr::Array { len, .. } => format!("[ ; {}] array", len),
+            TypeExpr::Tuple(items) if items.is_empty() => "()".to_string(),
+            TypeExpr::Tuple(_) => "( , ) tuple".to_string(),
+            TypeExpr::TraitObject { is_impl, .. } => if *is_impl { "impl" } else { "dyn" }.to_string(),
+            TypeExpr::FnTrait { name, .. } => format!("{}( )", name),
+            TypeExpr::FnPointer { .. } => "fn( )".to_string(),
+            TypeExpr::Never => "!".to_string(),
+            TypeExpr::Lifetime(name) => name.clone(),
+            TypeExpr::Binding { name, .. } => format!("{} =", name),
+            TypeExpr::Raw(text) => format!("{} (raw)", text),
+        }
+    }
+
+    /// The node's editable text: a path, name, lifetime, array length or raw text
+    pub fn name(&self) -> Option<String> {
+        match self {
+            TypeExpr::Primitive(name)
+            | TypeExpr::Alias(name)
+            | TypeExpr::Lifetime(name)
+            | TypeExpr::Raw(name)
+            | TypeExpr::Path { path: name, .. }
+            | TypeExpr::FnTrait { name, .. }
+            | TypeExpr::Binding { name, .. } => Some(name.clone()),
+            TypeExpr::Reference { lifetime, .. } => Some(lifetime.clone().unwrap_or_default()),
+            TypeExpr::Array { len, .. } => Some(len.clone()),
+            TypeExpr::FnPointer { abi, .. } => Some(abi.clone().unwrap_or_default()),
+            _ => None,
+        }
+    }
+
+    /// What [`TypeExpr::name`] holds for this node, as a field label
+    pub fn name_kind(&self) -> Option<&'static str> {
+        match self {
+            TypeExpr::Primitive(_) => Some("Name"),
+            TypeExpr::Alias(_) => Some("Alias"),
+            TypeExpr::Lifetime(_) | TypeExpr::Reference { .. } => Some("Lifetime"),
+            TypeExpr::Raw(_) => Some("Text"),
+            TypeExpr::Path { .. } => Some("Path"),
+            TypeExpr::FnTrait { .. } => Some("Trait"),
+            TypeExpr::Binding { .. } => Some("Name"),
+            TypeExpr::Array { .. } => Some("Length"),
+            TypeExpr::FnPointer { .. } => Some("ABI"),
+            _ => None,
+        }
+    }
+
+    pub fn set_name(&mut self, text: &str) {
+        let text = text.trim().to_string();
+        match self {
+            TypeExpr::Primitive(name)
+            | TypeExpr::Alias(name)
+            | TypeExpr::Lifetime(name)
+            | TypeExpr::Raw(name)
+            | TypeExpr::Path { path: name, .. }
+            | TypeExpr::FnTrait { name, .. }
+            | TypeExpr::Binding { name, .. } => *name = text,
+            TypeExpr::Reference { lifetime, .. } => *lifetime = Some(text).filter(|l| !l.is_empty()),
+            TypeExpr::Array { len, .. } => *len = text,
+            TypeExpr::FnPointer { abi, .. } => *abi = Some(text).filter(|a| !a.is_empty()),
+            _ => {}
+        }
+    }
+
+    pub fn children(&self) -> Vec<&TypeExpr> {
+        match self {
+            TypeExpr::Reference { inner, .. } | TypeExpr::Pointer { inner, .. } | TypeExpr::Slice(inner) => vec![&**inner],
+            TypeExpr::Array { elem, .. } => vec![&**elem],
+            TypeExpr::Binding { ty, .. } => vec![&**ty],
+            TypeExpr::Tuple(items) | TypeExpr::Path { args: items, .. } | TypeExpr::TraitObject { bounds: items, .. } => {
+                items.iter().collect()
+            }
+            TypeExpr::FnTrait { inputs, output, .. } | TypeExpr::FnPointer { params: inputs, ret: output, .. } => {
+                inputs.iter().chain(output.as_deref()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut TypeExpr> {
+        match self {
+            TypeExpr::Reference { inner, .. } | TypeExpr::Pointer { inner, .. } | TypeExpr::Slice(inner) => vec![&mut **inner],
+            TypeExpr::Array { elem, .. } => vec![&mut **elem],
+            TypeExpr::Binding { ty, .. } => vec![&mut **ty],
+            TypeExpr::Tuple(items) | TypeExpr::Path { args: items, .. } | TypeExpr::TraitObject { bounds: items, .. } => {
+                items.iter_mut().collect()
+            }
+            TypeExpr::FnTrait { inputs, output, .. } | TypeExpr::FnPointer { params: inputs, ret: output, .. } => {
+                inputs.iter_mut().chain(output.as_deref_mut()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether child `index` is the return type of an fn trait or fn pointer
+    pub fn is_output_child(&self, index: usize) -> bool {
+        match self {
+            TypeExpr::FnTrait { inputs, output, .. } | TypeExpr::FnPointer { params: inputs, ret: output, .. } => {
+                output.is_some() && index == inputs.len()
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether [`TypeExpr::push_child`] can add children to this node
+    pub fn accepts_children(&self) -> bool {
+        matches!(
+            self,
+            TypeExpr::Tuple(_)
+                | TypeExpr::Path { .. }
+                | TypeExpr::TraitObject { .. }
+                | TypeExpr::FnTrait { .. }
+                | TypeExpr::FnPointer { .. }
+        )
+    }
+
+    /// The node reached by following child indices from this one
+    pub fn node(&self, path: &[usize]) -> Option<&TypeExpr> {
+        match path.split_first() {
+            None => Some(self),
+            Some((first, rest)) => self.children().get(*first)?.node(rest),
+        }
+    }
+
+    pub fn node_mut(&mut self, path: &[usize]) -> Option<&mut TypeExpr> {
+        match path.split_first() {
+            None => Some(self),
+            Some((first, rest)) => self.children_mut().into_iter().nth(*first)?.node_mut(rest),
+        }
+    }
+
+    /// Appends a child to list-like nodes (tuples, generic arguments, bounds, fn inputs)
+    /// and returns its index, or `None` for nodes with a fixed shape.
+    pub fn push_child(&mut self, child: TypeExpr) -> Option<usize> {
+        match self {
+            TypeExpr::Tuple(items)
+            | TypeExpr::Path { args: items, .. }
+            | TypeExpr::TraitObject { bounds: items, .. }
+            | TypeExpr::FnTrait { inputs: items, .. }
+            | TypeExpr::FnPointer { params: items, .. } => {
+                items.push(child);
+                Some(items.len() - 1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Removes a child from a list-like node, or the return type of an fn node.
+    /// Returns `false` if the child is required.
+    pub fn remove_child(&mut self, index: usize) -> bool {
+        match self {
+            TypeExpr::Tuple(items) | TypeExpr::Path { args: items, .. } | TypeExpr::TraitObject { bounds: items, .. }
+                if index < items.len() =>
+            {
+                items.remove(index);
+                true
+            }
+            TypeExpr::FnTrait { inputs, output, .. } | TypeExpr::FnPointer { params: inputs, ret: output, .. } => {
+                if index < inputs.len() {
+                    inputs.remove(index);
+                    true
+                } else {
+                    index == inputs.len() && output.take().is_some()
+                }
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Trait objects with several bounds need parentheses behind `&` and `*`
+fn write_pointee(f: &mut fmt::Formatter<'_>, inner: &TypeExpr) -> fmt::Result {
+    match inner {
+        TypeExpr::TraitObject { bounds, .. } if bounds.len() > 1 => write!(f, "({})", inner),
+        _ => write!(f, "{}", inner),
+    }
+}
+
+fn write_list(f: &mut fmt::Formatter<'_>, items: &[TypeExpr], separator: &str) -> fmt::Result {
+    for (ix, item) in items.iter().enumerate() {
+        if ix > 0 {
+            f.write_str(separator)?;
+        }
+        write!(f, "{}", item)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for TypeExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TypeExpr::Primitive(name) | TypeExpr::Alias(name) | TypeExpr::Lifetime(name) | TypeExpr::Raw(name) => {
+                f.write_str(name)
+            }
+            TypeExpr::SelfType => f.write_str("Self"),
+            TypeExpr::Path { path, args } => {
+                f.write_str(path)?;
+                if !args.is_empty() {
+                    f.write_str("<")?;
+                    write_list(f, args, ", ")?;
+                    f.write_str(">")?;
+                }
+                Ok(())
+            }
+            TypeExpr::Reference { lifetime, mutable, inner } => {
+                f.write_str("&")?;
+                if let Some(lifetime) = lifetime {
+                    write!(f, "{} ", lifetime)?;
+                }
+                if *mutable {
+                    f.write_str("mut ")?;
+                }
+                write_pointee(f, inner)
+            }
+            TypeExpr::Pointer { mutable, inner } => {
+                f.write_str(if *mutable { "*mut " } else { "*const " })?;
+                write_pointee(f, inner)
+            }
+            TypeExpr::Slice(elem) => write!(f, "[{}]", elem),
+            TypeExpr::Array { elem, len } => write!(f, "[{}; {}]", elem, len),
+            TypeExpr::Tuple(items) => {
+                f.write_str("(")?;
+                write_list(f, items, ", ")?;
+                if items.len() == 1 {
+                    f.write_str(",")?;
+                }
+                f.write_str(")")
+            }
+            TypeExpr::TraitObject { is_impl, bounds } => {
+                f.write_str(if *is_impl { "impl " } else { "dyn " })?;
+                write_list(f, bounds, " + ")
+            }
+            TypeExpr::FnTrait { name, inputs, output } => {
+                write!(f, "{}(", name)?;
+                write_list(f, inputs, ", ")?;
+                f.write_str(")")?;
+                if let Some(output) = output {
+                    write!(f, " -> {}", output)?;
+                }
+                Ok(())
+            }
+            TypeExpr::FnPointer { is_unsafe, abi, params, ret } => {
+                if *is_unsafe {
+                    f.write_str("unsafe ")?;
+                }
+                if let Some(abi) = abi {
+                    write!(f, "extern \"{}\" ", abi)?;
+                }
+                f.write_str("fn(")?;
+                write_list(f, params, ", ")?;
+                f.write_str(")")?;
+                if let Some(ret) = ret {
+                    write!(f, " -> {}", ret)?;
+                }
+                Ok(())
+            }
+            TypeExpr::Never => f.write_str("!"),
+            TypeExpr::Binding { name, ty } => write!(f, "{} = {}", name, ty),
+        }
+    }
+}
+
+const FN_TRAITS: &[&str] = &["Fn", "FnMut", "FnOnce"];
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Self {
+        Self { chars: text.chars().collect(), pos: 0 }
+    }
+
+    fn at_end(&self) -> bool {
+        self.pos >= self.chars.len()
+    }
+
+    fn rest(&self) -> String {
+        self.chars[self.pos..].iter().collect()
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Consumes `token` (after whitespace) if it comes next
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_ws();
+        let end = self.pos + token.chars().count();
+        if end <= self.chars.len() && self.chars[self.pos..end].iter().copied().eq(token.chars()) {
+            self.pos = end;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<(), String> {
+        if self.eat(token) {
+            Ok(())
+        } else if self.at_end() {
+            Err(format!("Expected `{}` at the end", token))
+        } else {
+            Err(format!("Expected `{}` before `{}`", token, self.rest()))
+        }
+    }
+
+    /// Consumes a keyword only if it isn't the start of a longer identifier
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let start = self.pos;
+        if self.eat(keyword) && !self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            return true;
+        }
+        self.pos = start;
+        false
+    }
+
+    fn ident(&mut self) -> Option<String> {
+        self.skip_ws();
+        let start = self.pos;
+        if self.chars[start..].starts_with(&['r', '#']) {
+            self.pos += 2;
+        }
+        match self.peek() {
+            Some(c) if c.is_alphabetic() || c == '_' => {}
+            _ => {
+                self.pos = start;
+                return None;
+            }
+        }
+        while self.peek().is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            self.pos += 1;
+        }
+        Some(self.chars[start..self.pos].iter().collect())
+    }
+
+    fn lifetime(&mut self) -> Option<String> {
+        self.skip_ws();
+        if self.peek() != Some('\'') {
+            return None;
+        }
+        self.pos += 1;
+        match self.ident() {
+            Some(name) => Some(format!("'{}", name)),
+            None => {
+                self.pos -= 1;
+                None
+            }
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<TypeExpr, String> {
+        self.skip_ws();
+        if self.eat("&") {
+            let lifetime = self.lifetime();
+            let mutable = self.eat_keyword("mut");
+            let inner = self.parse_pointee()?;
+            return Ok(TypeExpr::Reference { lifetime, mutable, inner: Box::new(inner) });
+        }
+        if self.eat("*") {
+            let mutable = if self.eat_keyword("mut") {
+                true
+            } else if self.eat_keyword("const") {
+                false
+            } else {
+                return Err("Raw pointers need `const` or `mut`".to_string());
+            };
+            let inner = self.parse_pointee()?;
+            return Ok(TypeExpr::Pointer { mutable, inner: Box::new(inner) });
+        }
+        if self.eat("[") {
+            let elem = Box::new(self.parse_type()?);
+            if self.eat(";") {
+                let len = self.raw_until(']')?;
+                self.expect("]")?;
+                return Ok(TypeExpr::Array { elem, len });
+            }
+            self.expect("]")?;
+            return Ok(TypeExpr::Slice(elem));
+        }
+        if self.eat("(") {
+            let (items, trailing_comma) = self.type_list(')')?;
+            self.expect(")")?;
+            // `(T)` is just a parenthesized `T`
+            if items.len() == 1 && !trailing_comma {
+                return Ok(items.into_iter().next().unwrap_or_else(TypeExpr::unit));
+            }
+            return Ok(TypeExpr::Tuple(items));
+        }
+        if self.eat("!") {
+            return Ok(TypeExpr::Never);
+        }
+        if self.eat_keyword("dyn") {
+            return Ok(TypeExpr::TraitObject { is_impl: false, bounds: self.bounds()? });
+        }
+        if self.eat_keyword("impl") {
+            return Ok(TypeExpr::TraitObject { is_impl: true, bounds: self.bounds()? });
+        }
+
+        let start = self.pos;
+        let is_unsafe = self.eat_keyword("unsafe");
+        let abi = if self.eat_keyword("extern") {
+            self.skip_ws();
+            if self.eat("\"") {
+                let abi = self.raw_until('"')?;
+                self.expect("\"")?;
+                Some(abi)
+            } else {
+                Some("C".to_string())
+            }
+        } else {
+            None
+        };
+        if self.eat_keyword("fn") {
+            self.expect("(")?;
+            let (params, _) = self.type_list(')')?;
+            self.expect(")")?;
+            let ret = self.return_type()?;
+            return Ok(TypeExpr::FnPointer { is_unsafe, abi, params, ret });
+        }
+        if is_unsafe || abi.is_some() {
+            self.pos = start;
+            return Err("Expected `fn` after `unsafe` or `extern`".to_string());
+        }
+
+        self.path_type(false)
+    }
+
+    /// A type behind `&` or `*`, where a bare `dyn A + B` would be ambiguous
+    fn parse_pointee(&mut self) -> Result<TypeExpr, String> {
+        self.skip_ws();
+        if self.eat_keyword("dyn") {
+            let bound = self.bound()?;
+            return Ok(TypeExpr::TraitObject { is_impl: false, bounds: vec![bound] });
+        }
+        self.parse_type()
+    }
+
+    fn path_type(&mut self, in_bound: bool) -> Result<TypeExpr, String> {
+        self.skip_ws();
+        let mut path = String::new();
+        if self.eat("::") {
+            path.push_str("::");
+        }
+        if in_bound && self.eat("?") {
+            path.push('?');
+        }
+        loop {
+            let Some(segment) = self.ident() else {
+                return Err(if self.at_end() {
+                    "Expected a type".to_string()
+                } else {
+                    format!("Expected a type before `{}`", self.rest())
+                });
+            };
+            path.push_str(&segment);
+            let before_separator = self.pos;
+            if self.eat("::") {
+                if self.eat("<") {
+                    // Turbofish and qualified paths aren't modelled
+                    self.pos = before_separator;
+                    return Err("Turbofish syntax is not supported in types".to_string());
+                }
+                path.push_str("::");
+                continue;
+            }
+            break;
+        }
+
+        if path == "Self" {
+            return Ok(TypeExpr::SelfType);
+        }
+
+        let name = path.rsplit("::").next().unwrap_or_default().to_string();
+        if FN_TRAITS.contains(&name.as_str()) && self.eat("(") {
+            let (inputs, _) = self.type_list(')')?;
+            self.expect(")")?;
+            let output = self.return_type()?;
+            return Ok(TypeExpr::FnTrait { name: path, inputs, output });
+        }
+
+        let mut args = Vec::new();
+        if self.eat("<") {
+            loop {
+                self.skip_ws();
+                if self.eat(">") {
+                    break;
+                }
+                args.push(self.generic_arg()?);
+                if !self.eat(",") {
+                    self.expect(">")?;
+                    break;
+                }
+            }
+            self.skip_ws();
+            if self.chars[self.pos..].starts_with(&[':', ':']) {
+                return Err("Associated items of generic types are not supported".to_string());
+            }
+        }
+        Ok(TypeExpr::Path { path, args })
+    }
+
+    fn generic_arg(&mut self) -> Result<TypeExpr, String> {
+        if let Some(lifetime) = self.lifetime() {
+            return Ok(TypeExpr::Lifetime(lifetime));
+        }
+        let start = self.pos;
+        if let Some(name) = self.ident() {
+            // `Item = T`, but not `==` or a path
+            if self.eat("=") {
+                let ty = self.parse_type()?;
+                return Ok(TypeExpr::Binding { name, ty: Box::new(ty) });
+            }
+        }
+        self.pos = start;
+        self.parse_type()
+    }
+
+    fn bounds(&mut self) -> Result<Vec<TypeExpr>, String> {
+        let mut bounds = vec![self.bound()?];
+        while self.eat("+") {
+            bounds.push(self.bound()?);
+        }
+        Ok(bounds)
+    }
+
+    fn bound(&mut self) -> Result<TypeExpr, String> {
+        if let Some(lifetime) = self.lifetime() {
+            return Ok(TypeExpr::Lifetime(lifetime));
+        }
+        self.path_type(true)
+    }
+
+    fn return_type(&mut self) -> Result<Option<Box<TypeExpr>>, String> {
+        if self.eat("->") {
+            Ok(Some(Box::new(self.parse_type()?)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Comma-separated types up to `close`, and whether the list ended with a comma
+    fn type_list(&mut self, close: char) -> Result<(Vec<TypeExpr>, bool), String> {
+        let mut items = Vec::new();
+        let mut trailing_comma = false;
+        loop {
+            self.skip_ws();
+            if self.peek() == Some(close) {
+                break;
+            }
+            items.push(self.parse_type()?);
+            trailing_comma = self.eat(",");
+            if !trailing_comma {
+                break;
+            }
+        }
+        Ok((items, trailing_comma))
+    }
+
+    /// Text up to an unnested `close`, trimmed, e.g. an array length expression
+    fn raw_until(&mut self, close: char) -> Result<String, String> {
+        let start = self.pos;
+        let mut depth = 0usize;
+        while let Some(c) = self.peek() {
+            match c {
+                c if c == close && depth == 0 => break,
+                '(' | '[' | '{' => depth += 1,
+                ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        if self.at_end() {
+            return Err(format!("Expected `{}` at the end", close));
+        }
+        let text: String = self.chars[start..self.pos].iter().collect();
+        let text = text.trim().to_string();
+        if text.is_empty() {
+            return Err("Expected an expression".to_string());
+        }
+        Ok(text)
+    }
+}
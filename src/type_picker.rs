@@ -0,0 +1,819 @@
+use gpui::{prelude::FluentBuilder, *};
+use ui::{v_flex, h_flex, ActiveTheme, StyledExt, Sizable, button::{Button, ButtonVariants}, input::{InputState, TextInput}};
+use ui_types_common::TypeRef;
+use crate::model::TraitMeta;
+use crate::type_expr::TypeExpr;
+use std::path::{Path, PathBuf};
+
+actions!(type_picker, [
+    SelectNext,
+    SelectPrevious,
+    Dismiss,
+]);
+
+/// Key context set on the type picker popover
+pub const TYPE_PICKER_CONTEXT: &str = "TypePicker";
+
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("down", SelectNext, Some(TYPE_PICKER_CONTEXT)),
+        KeyBinding::new("up", SelectPrevious, Some(TYPE_PICKER_CONTEXT)),
+        KeyBinding::new("escape", Dismiss, Some(TYPE_PICKER_CONTEXT)),
+    ]);
+}
+
+/// Which part of a method signature a picked type is written to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeSlot {
+    Return,
+    Param(usize),
+    /// A parameter that will be appended once a type is chosen
+    NewParam,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeCategory {
+    Associated,
+    Primitive,
+    Std,
+    Project,
+    Custom,
+}
+
+impl TypeCategory {
+    fn label(&self) -> &'static str {
+        match self {
+            TypeCategory::Associated => "Associated Types",
+            TypeCategory::Primitive => "Primitives",
+            TypeCategory::Std => "Standard Library",
+            TypeCategory::Project => "Project Types",
+            TypeCategory::Custom => "Custom",
+        }
+    }
+}
+
+/// A type offered by the picker
+#[derive(Clone, Debug)]
+pub struct TypeCandidate {
+    pub label: String,
+    pub category: TypeCategory,
+    pub type_ref: TypeRef,
+}
+
+pub(crate) const PRIMITIVE_TYPES: &[&str] = &[
+    "()", "bool", "char", "str",
+    "u8", "u16", "u32", "u64", "u128", "usize",
+    "i8", "i16", "i32", "i64", "i128", "isize",
+    "f32", "f64",
+];
+
+const STD_TYPES: &[&str] = &[
+    "String", "&str", "&'static str",
+    "Vec<u8>", "Vec<String>", "Option<String>", "Result<(), String>",
+    "Box<dyn std::error::Error>", "std::path::PathBuf", "&std::path::Path",
+    "std::time::Duration", "std::time::Instant",
+    "std::collections::HashMap<String, String>", "std::collections::HashSet<String>",
+    "std::sync::Arc<str>", "std::rc::Rc<str>",
+];
+
+/// Folder extensions of Pulsar type assets and the marker file inside each
+const PROJECT_TYPE_ASSETS: &[(&str, &str)] = &[
+    ("struct", "struct.json"),
+    ("enum", "enum.json"),
+    ("trait", "trait.json"),
+    ("alias", "alias.json"),
+];
+
+/// Directories never scanned for project types
+pub(crate) const SKIPPED_DIRS: &[&str] = &["target", "node_modules"];
+
+pub(crate) const MAX_SCAN_DEPTH: usize = 8;
+
+/// Built-in primitive and standard library candidates
+pub fn builtin_types() -> Vec<TypeCandidate> {
+    let primitives = PRIMITIVE_TYPES.iter().map(|name| TypeCandidate {
+        label: name.to_string(),
+        category: TypeCategory::Primitive,
+        type_ref: TypeRef::Primitive { name: name.to_string() },
+    });
+    let std_types = STD_TYPES.iter().map(|path| TypeCandidate {
+        label: path.to_string(),
+        category: TypeCategory::Std,
+        type_ref: TypeRef::Path { path: path.to_string() },
+    });
+    primitives.chain(std_types).collect()
+}
+
+/// `Self::Name` candidates for the associated types declared by the trait
+pub fn associated_types(meta: &TraitMeta) -> Vec<TypeCandidate> {
+    meta.associated_types
+        .iter()
+        .map(|item| item.name.trim())
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            let path = format!("Self::{}", name);
+            TypeCandidate {
+                label: path.clone(),
+                category: TypeCategory::Associated,
+                type_ref: TypeRef::Path { path },
+            }
+        })
+        .collect()
+}
+
+/// Finds the directory to scan for project types: the nearest ancestor of `file_path`
+/// holding a `Cargo.toml` or `Pulsar.toml`, or the folder around the asset otherwise.
+pub fn project_root_for(file_path: &Path) -> Option<PathBuf> {
+    file_path
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.join("Cargo.toml").is_file() || dir.join("Pulsar.toml").is_file())
+        .or_else(|| file_path.ancestors().nth(2))
+        .map(Path::to_path_buf)
+}
+
+/// Collects the struct, enum, trait and alias assets below `root`
+pub fn project_types(root: &Path) -> Vec<TypeCandidate> {
+    collect_project_types(root, &["struct", "enum", "trait", "alias"])
+}
+
+/// Names of the trait assets below `root`, e.g. to offer as supertraits
+pub fn project_traits(root: &Path) -> Vec<String> {
+    collect_project_types(root, &["trait"])
+        .into_iter()
+        .map(|candidate| candidate.label)
+        .collect()
+}
+
+fn collect_project_types(root: &Path, kinds: &[&str]) -> Vec<TypeCandidate> {
+    let mut candidates = Vec::new();
+    scan_project_types(root, kinds, 0, &mut candidates);
+    candidates.sort_by(|a, b| a.label.cmp(&b.label));
+    candidates.dedup_by(|a, b| a.label == b.label);
+    candidates
+}
+
+fn scan_project_types(dir: &Path, kinds: &[&str], depth: usize, candidates: &mut Vec<TypeCandidate>) {
+    if depth > MAX_SCAN_DEPTH {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if file_name.starts_with('.') || SKIPPED_DIRS.contains(&file_name.as_str()) {
+            continue;
+        }
+
+        let asset = path.extension().and_then(|ext| ext.to_str()).and_then(|ext| {
+            PROJECT_TYPE_ASSETS.iter().find(|(asset_ext, _)| *asset_ext == ext)
+        });
+        match asset {
+            Some((ext, _)) if !kinds.contains(ext) => {}
+            Some((ext, marker)) => {
+                let name = std::fs::read_to_string(path.join(marker))
+                    .ok()
+                    .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+                    .and_then(|value| value.get("name").and_then(|n| n.as_str()).map(String::from))
+                    .or_else(|| path.file_stem().map(|stem| stem.to_string_lossy().to_string()));
+                if let Some(name) = name {
+                    let type_ref = if *ext == "alias" {
+                        TypeRef::AliasRef { alias: name.clone() }
+                    } else {
+                        TypeRef::Path { path: name.clone() }
+                    };
+                    candidates.push(TypeCandidate {
+                        label: name,
+                        category: TypeCategory::Project,
+                        type_ref,
+                    });
+                }
+            }
+            None => scan_project_types(&path, kinds, depth + 1, candidates),
+        }
+    }
+}
+
+/// Ways the type builder can wrap the selected node
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Wrapper {
+    Ref,
+    RefMut,
+    Slice,
+    Array,
+    Tuple,
+    Generic(&'static str),
+    Dyn,
+    Impl,
+    FnReturn,
+}
+
+impl Wrapper {
+    const ALL: [Wrapper; 11] = [
+        Wrapper::Ref,
+        Wrapper::RefMut,
+        Wrapper::Slice,
+        Wrapper::Array,
+        Wrapper::Tuple,
+        Wrapper::Generic("Option"),
+        Wrapper::Generic("Vec"),
+        Wrapper::Generic("Box"),
+        Wrapper::Dyn,
+        Wrapper::Impl,
+        Wrapper::FnReturn,
+    ];
+
+    fn label(&self) -> String {
+        match self {
+            Wrapper::Ref => "&T".to_string(),
+            Wrapper::RefMut => "&mut T".to_string(),
+            Wrapper::Slice => "[T]".to_string(),
+            Wrapper::Array => "[T; N]".to_string(),
+            Wrapper::Tuple => "(T, …)".to_string(),
+            Wrapper::Generic(path) => format!("{}<T>", path),
+            Wrapper::Dyn => "dyn T".to_string(),
+            Wrapper::Impl => "impl T".to_string(),
+            Wrapper::FnReturn => "fn() -> T".to_string(),
+        }
+    }
+
+    fn wrap(&self, inner: TypeExpr) -> TypeExpr {
+        let inner = Box::new(inner);
+        match self {
+            Wrapper::Ref => TypeExpr::Reference { lifetime: None, mutable: false, inner },
+            Wrapper::RefMut => TypeExpr::Reference { lifetime: None, mutable: true, inner },
+            Wrapper::Slice => TypeExpr::Slice(inner),
+            Wrapper::Array => TypeExpr::Array { elem: inner, len: "1".to_string() },
+            Wrapper::Tuple => TypeExpr::Tuple(vec![*inner]),
+            Wrapper::Generic(path) => TypeExpr::Path { path: path.to_string(), args: vec![*inner] },
+            Wrapper::Dyn => TypeExpr::TraitObject { is_impl: false, bounds: vec![*inner] },
+            Wrapper::Impl => TypeExpr::TraitObject { is_impl: true, bounds: vec![*inner] },
+            Wrapper::FnReturn => TypeExpr::FnPointer { is_unsafe: false, abi: None, params: Vec::new(), ret: Some(inner) },
+        }
+    }
+}
+
+/// One row of the type builder's tree
+struct TreeRow {
+    path: Vec<usize>,
+    depth: usize,
+    label: String,
+}
+
+/// Lists `expr` and its descendants depth-first, parents before children
+fn flatten_tree(expr: &TypeExpr, path: Vec<usize>, depth: usize, label: String, rows: &mut Vec<TreeRow>) {
+    rows.push(TreeRow { path: path.clone(), depth, label });
+    for (ix, child) in expr.children().into_iter().enumerate() {
+        let mut child_path = path.clone();
+        child_path.push(ix);
+        let prefix = if expr.is_output_child(ix) { "-> " } else { "" };
+        flatten_tree(child, child_path, depth + 1, format!("{}{}", prefix, child.label()), rows);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum TypePickerEvent {
+    Selected(TypeRef),
+    Dismissed,
+}
+
+/// Searchable popover listing the types a signature can use.
+///
+/// The builder mode edits the type as a [`TypeExpr`] tree: candidates picked from the list
+/// replace the selected node instead of being used directly.
+pub struct TypePicker {
+    search_input: Entity<InputState>,
+    candidates: Vec<TypeCandidate>,
+    filtered: Vec<TypeCandidate>,
+    selected: usize,
+    /// Type currently in the slot being edited, the builder's starting point
+    current: Option<TypeRef>,
+    /// Parse error for the typed text, shown instead of the custom candidate
+    custom_error: Option<String>,
+    tree: Option<TypeExpr>,
+    tree_selection: Vec<usize>,
+    node_input: Entity<InputState>,
+    focus_handle: FocusHandle,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl TypePicker {
+    pub fn new(candidates: Vec<TypeCandidate>, current: Option<TypeRef>, window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_input = cx.new(|cx| InputState::new(window, cx).placeholder("Search types..."));
+        let node_input = cx.new(|cx| InputState::new(window, cx));
+
+        let subscription = cx.subscribe_in(&search_input, window, |this, _state, event: &ui::input::InputEvent, window, cx| {
+            match event {
+                ui::input::InputEvent::Change => {
+                    this.refilter(cx);
+                }
+                ui::input::InputEvent::PressEnter { .. } => {
+                    this.confirm(window, cx);
+                }
+                _ => {}
+            }
+        });
+
+        let node_subscription = cx.subscribe_in(&node_input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                this.rename_node(cx);
+            }
+        });
+
+        let mut picker = Self {
+            search_input,
+            filtered: candidates.clone(),
+            candidates,
+            selected: 0,
+            current,
+            custom_error: None,
+            tree: None,
+            tree_selection: Vec::new(),
+            node_input,
+            focus_handle: cx.focus_handle(),
+            _subscriptions: vec![subscription, node_subscription],
+        };
+        picker.refilter(cx);
+        picker
+    }
+
+    pub fn focus(&self, window: &mut Window, cx: &mut Context<Self>) {
+        let handle = self.search_input.read(cx).focus_handle(cx);
+        window.focus(&handle);
+    }
+
+    fn refilter(&mut self, cx: &mut Context<Self>) {
+        let query = self.search_input.read(cx).text().to_string();
+        let query = query.trim();
+        let needle = query.to_lowercase();
+
+        let mut matches: Vec<TypeCandidate> = self
+            .candidates
+            .iter()
+            .filter(|candidate| candidate.label.to_lowercase().contains(&needle))
+            .cloned()
+            .collect();
+        // Prefix matches first, keeping category order otherwise
+        matches.sort_by_key(|candidate| !candidate.label.to_lowercase().starts_with(&needle));
+
+        // Anything typed that parses as a type can be used as a path type
+        self.custom_error = None;
+        if !query.is_empty() && !matches.iter().any(|candidate| candidate.label == query) {
+            match TypeExpr::parse(query) {
+                Ok(expr) => matches.push(TypeCandidate {
+                    label: expr.to_string(),
+                    category: TypeCategory::Custom,
+                    type_ref: TypeRef::Path { path: expr.to_string() },
+                }),
+                Err(e) => self.custom_error = Some(e),
+            }
+        }
+
+        self.filtered = matches;
+        self.selected = 0;
+        cx.notify();
+    }
+
+    fn select(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(candidate) = self.filtered.get(index) else {
+            return;
+        };
+        if self.tree.is_some() {
+            let expr = TypeExpr::from_type_ref(&candidate.type_ref);
+            self.replace_node(expr, window, cx);
+        } else {
+            cx.emit(TypePickerEvent::Selected(candidate.type_ref.clone()));
+        }
+    }
+
+    /// Opens the tree builder on the slot's current type, or on `()` for a new slot
+    fn open_builder(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let tree = self.current.as_ref().map(TypeExpr::from_type_ref).unwrap_or_else(TypeExpr::unit);
+        self.tree = Some(tree);
+        self.select_node(Vec::new(), window, cx);
+    }
+
+    fn close_builder(&mut self, cx: &mut Context<Self>) {
+        self.tree = None;
+        self.tree_selection.clear();
+        cx.notify();
+    }
+
+    fn use_tree(&mut self, cx: &mut Context<Self>) {
+        if let Some(tree) = &self.tree {
+            cx.emit(TypePickerEvent::Selected(tree.to_type_ref()));
+        }
+    }
+
+    fn selected_node(&self) -> Option<&TypeExpr> {
+        self.tree.as_ref()?.node(&self.tree_selection)
+    }
+
+    fn select_node(&mut self, path: Vec<usize>, window: &mut Window, cx: &mut Context<Self>) {
+        self.tree_selection = path;
+        let name = self.selected_node().and_then(TypeExpr::name).unwrap_or_default();
+        self.node_input.update(cx, |input, cx| input.set_value(name, window, cx));
+        cx.notify();
+    }
+
+    fn rename_node(&mut self, cx: &mut Context<Self>) {
+        let text = self.node_input.read(cx).text().to_string();
+        let Some(node) = self.tree.as_mut().and_then(|tree| tree.node_mut(&self.tree_selection)) else {
+            return;
+        };
+        if node.name().is_some_and(|name| name != text.trim()) {
+            node.set_name(&text);
+            cx.notify();
+        }
+    }
+
+    fn replace_node(&mut self, expr: TypeExpr, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(node) = self.tree.as_mut().and_then(|tree| tree.node_mut(&self.tree_selection)) else {
+            return;
+        };
+        *node = expr;
+        self.select_node(self.tree_selection.clone(), window, cx);
+    }
+
+    fn wrap_node(&mut self, wrapper: Wrapper, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(node) = self.tree.as_mut().and_then(|tree| tree.node_mut(&self.tree_selection)) else {
+            return;
+        };
+        let inner = std::mem::replace(node, TypeExpr::unit());
+        *node = wrapper.wrap(inner);
+        self.select_node(self.tree_selection.clone(), window, cx);
+    }
+
+    /// Replaces the selected node with its first child, undoing a wrap
+    fn unwrap_node(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(child) = self.selected_node().and_then(|node| node.children().first().map(|child| (*child).clone())) else {
+            return;
+        };
+        self.replace_node(child, window, cx);
+    }
+
+    fn add_child(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(node) = self.tree.as_mut().and_then(|tree| tree.node_mut(&self.tree_selection)) else {
+            return;
+        };
+        if let Some(index) = node.push_child(TypeExpr::unit()) {
+            let mut path = self.tree_selection.clone();
+            path.push(index);
+            self.select_node(path, window, cx);
+        }
+    }
+
+    /// Removes the selected node from its parent, or resets it to `()` if the parent needs it
+    fn remove_node(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some((&index, parent_path)) = self.tree_selection.split_last() else {
+            self.replace_node(TypeExpr::unit(), window, cx);
+            return;
+        };
+        let parent_path = parent_path.to_vec();
+        let removed = self
+            .tree
+            .as_mut()
+            .and_then(|tree| tree.node_mut(&parent_path))
+            .is_some_and(|parent| parent.remove_child(index));
+        if removed {
+            self.select_node(parent_path, window, cx);
+        } else {
+            self.replace_node(TypeExpr::unit(), window, cx);
+        }
+    }
+
+    fn confirm(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.select(self.selected, window, cx);
+    }
+
+    fn select_next(&mut self, _: &SelectNext, _window: &mut Window, cx: &mut Context<Self>) {
+        if !self.filtered.is_empty() {
+            self.selected = (self.selected + 1) % self.filtered.len();
+            cx.notify();
+        }
+    }
+
+    fn select_previous(&mut self, _: &SelectPrevious, _window: &mut Window, cx: &mut Context<Self>) {
+        if !self.filtered.is_empty() {
+            self.selected = (self.selected + self.filtered.len() - 1) % self.filtered.len();
+            cx.notify();
+        }
+    }
+
+    fn dismiss(&mut self, _: &Dismiss, _window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(TypePickerEvent::Dismissed);
+    }
+}
+
+impl TypePicker {
+    fn render_builder(&self, tree: &TypeExpr, cx: &mut Context<Self>) -> AnyElement {
+        let selected_bg = cx.theme().accent.opacity(0.2);
+        let hover_bg = cx.theme().secondary;
+        let mut rows = Vec::new();
+        flatten_tree(tree, Vec::new(), 0, tree.label(), &mut rows);
+
+        let tree_rows = rows.into_iter().enumerate().map(|(ix, row)| {
+            let is_selected = row.path == self.tree_selection;
+            let path = row.path;
+            div()
+                .id(("type-tree-node", ix))
+                .pl(px(8.0 + row.depth as f32 * 14.0))
+                .pr_2()
+                .py(px(2.0))
+                .rounded(px(4.0))
+                .cursor_pointer()
+                .text_sm()
+                .font_family("monospace")
+                .text_color(cx.theme().foreground)
+                .when(is_selected, |this| this.bg(selected_bg))
+                .hover(|this| this.bg(hover_bg))
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.select_node(path.clone(), window, cx);
+                }))
+                .child(row.label)
+        });
+
+        let node = self.selected_node();
+        let name_kind = node.and_then(TypeExpr::name_kind);
+        let has_children = node.is_some_and(|node| !node.children().is_empty());
+        let accepts_children = node.is_some_and(TypeExpr::accepts_children);
+
+        let wrap_buttons = Wrapper::ALL.iter().enumerate().map(|(ix, wrapper)| {
+            let wrapper = *wrapper;
+            Button::new(("wrap-type-node", ix))
+                .ghost()
+                .label(wrapper.label())
+                .with_size(ui::Size::XSmall)
+                .on_click(cx.listener(move |this, _, window, cx| {
+                    this.wrap_node(wrapper, window, cx);
+                }))
+        });
+
+        v_flex()
+            .gap_2()
+            .pb_2()
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .font_semibold()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("TYPE BUILDER")
+                    )
+                    .child(
+                        Button::new("close-type-builder")
+                            .ghost()
+                            .label("List")
+                            .with_size(ui::Size::XSmall)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.close_builder(cx);
+                            }))
+                    )
+                    .child(
+                        Button::new("use-built-type")
+                            .primary()
+                            .label("Use")
+                            .with_size(ui::Size::XSmall)
+                            .on_click(cx.listener(|this, _, _window, cx| {
+                                this.use_tree(cx);
+                            }))
+                    )
+            )
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .rounded(px(4.0))
+                    .bg(cx.theme().secondary)
+                    .text_sm()
+                    .font_family("monospace")
+                    .text_color(cx.theme().foreground)
+                    .child(tree.to_string())
+            )
+            .child(
+                v_flex()
+                    .id("type-tree")
+                    .max_h(px(200.0))
+                    .overflow_y_scroll()
+                    .children(tree_rows)
+            )
+            .when_some(name_kind, |this, kind| {
+                this.child(
+                    h_flex()
+                        .gap_2()
+                        .items_center()
+                        .child(
+                            div()
+                                .w(px(56.0))
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(kind)
+                        )
+                        .child(div().flex_1().child(TextInput::new(&self.node_input).with_size(ui::Size::Small)))
+                )
+            })
+            .child(h_flex().flex_wrap().gap_1().children(wrap_buttons))
+            .child(
+                h_flex()
+                    .flex_wrap()
+                    .gap_1()
+                    .child(
+                        Button::new("type-node-self")
+                            .ghost()
+                            .label("Self")
+                            .with_size(ui::Size::XSmall)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.replace_node(TypeExpr::SelfType, window, cx);
+                            }))
+                    )
+                    .child(
+                        Button::new("type-node-never")
+                            .ghost()
+                            .label("!")
+                            .with_size(ui::Size::XSmall)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.replace_node(TypeExpr::Never, window, cx);
+                            }))
+                    )
+                    .when(accepts_children, |this| {
+                        this.child(
+                            Button::new("type-node-add-child")
+                                .ghost()
+                                .label("+ Child")
+                                .with_size(ui::Size::XSmall)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.add_child(window, cx);
+                                }))
+                        )
+                    })
+                    .when(has_children, |this| {
+                        this.child(
+                            Button::new("type-node-unwrap")
+                                .ghost()
+                                .label("Unwrap")
+                                .with_size(ui::Size::XSmall)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.unwrap_node(window, cx);
+                                }))
+                        )
+                    })
+                    .child(
+                        Button::new("type-node-remove")
+                            .ghost()
+                            .label("Remove")
+                            .with_size(ui::Size::XSmall)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.remove_node(window, cx);
+                            }))
+                    )
+            )
+            .child(
+                div()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child("Pick a type below to replace the selected node")
+            )
+            .into_any_element()
+    }
+}
+
+impl EventEmitter<TypePickerEvent> for TypePicker {}
+
+impl Focusable for TypePicker {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TypePicker {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected_bg = cx.theme().accent.opacity(0.2);
+        let hover_bg = cx.theme().secondary;
+        let mut rows: Vec<AnyElement> = Vec::new();
+        let mut last_category = None;
+
+        for (ix, candidate) in self.filtered.iter().enumerate() {
+            if last_category != Some(candidate.category) {
+                last_category = Some(candidate.category);
+                rows.push(
+                    div()
+                        .px_2()
+                        .pt_2()
+                        .pb_1()
+                        .text_xs()
+                        .font_semibold()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(candidate.category.label())
+                        .into_any_element()
+                );
+            }
+
+            let is_selected = ix == self.selected;
+            rows.push(
+                h_flex()
+                    .id(("type-candidate", ix))
+                    .px_2()
+                    .py_1()
+                    .gap_2()
+                    .rounded(px(4.0))
+                    .cursor_pointer()
+                    .when(is_selected, |this| this.bg(selected_bg))
+                    .hover(|this| this.bg(hover_bg))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.select(ix, window, cx);
+                    }))
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_sm()
+                            .text_color(cx.theme().foreground)
+                            .child(match candidate.category {
+                                TypeCategory::Custom => format!("Use `{}`", candidate.label),
+                                _ => candidate.label.clone(),
+                            })
+                    )
+                    .when(matches!(candidate.type_ref, TypeRef::AliasRef { .. }), |this| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("alias")
+                        )
+                    })
+                    .into_any_element()
+            );
+        }
+
+        let builder = self.tree.as_ref().map(|tree| self.render_builder(tree, cx));
+
+        v_flex()
+            .key_context(TYPE_PICKER_CONTEXT)
+            .track_focus(&self.focus_handle)
+            .on_action(cx.listener(Self::select_next))
+            .on_action(cx.listener(Self::select_previous))
+            .on_action(cx.listener(Self::dismiss))
+            .w(px(360.0))
+            .max_h(px(if self.tree.is_some() { 640.0 } else { 420.0 }))
+            .p_2()
+            .gap_2()
+            .bg(cx.theme().popover)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded(px(8.0))
+            .shadow_lg()
+            .children(builder)
+            .child(
+                h_flex()
+                    .gap_1()
+                    .child(div().flex_1().child(TextInput::new(&self.search_input)))
+                    .when(self.tree.is_none(), |this| {
+                        this.child(
+                            Button::new("open-type-builder")
+                                .ghost()
+                                .label("Build…")
+                                .with_size(ui::Size::Small)
+                                .on_click(cx.listener(|this, _, window, cx| {
+                                    this.open_builder(window, cx);
+                                }))
+                        )
+                    })
+            )
+            .when_some(self.custom_error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .px_2()
+                        .text_xs()
+                        .text_color(cx.theme().danger)
+                        .child(error)
+                )
+            })
+            .child(
+                v_flex()
+                    .id("type-picker-candidates")
+                    .flex_1()
+                    .overflow_y_scroll()
+                    .children(rows)
+                    .when(self.filtered.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .p_3()
+                                .text_center()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("No matching types")
+                        )
+                    })
+            )
+    }
+}
@@ -0,0 +1,290 @@
+//! Checks a trait asset for problems the generated Rust would have
+//!
+//! [`validate`] runs over the whole [`TraitAsset`] and returns severity-tagged
+//! [`Diagnostic`]s. Errors would make the generated code fail to compile, warnings break
+//! conventions or point at types that can't be found, and infos are style suggestions.
+
+use std::collections::HashSet;
+use ui_types_common::{TraitAsset, TypeRef};
+use crate::ident::{identifier_error, is_snake_case, is_upper_camel_case, is_valid_param_name, to_snake_case, to_upper_camel_case};
+use crate::model::{GenericParam, Generics, TraitMeta, Visibility};
+use crate::type_expr::TypeExpr;
+use crate::type_picker::TypeCandidate;
+
+/// Types and traits usable without a path in every module
+const PRELUDE_NAMES: &[&str] = &[
+    "Option", "Some", "None", "Result", "Ok", "Err", "String", "Vec", "Box", "ToString", "ToOwned",
+    "Send", "Sync", "Sized", "Unpin", "Copy", "Clone", "Default", "Drop", "Eq", "PartialEq", "Ord",
+    "PartialOrd", "AsRef", "AsMut", "Into", "From", "TryFrom", "TryInto", "Iterator", "IntoIterator",
+    "DoubleEndedIterator", "ExactSizeIterator", "Extend", "FromIterator", "Fn", "FnMut", "FnOnce",
+    "Future", "IntoFuture",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Info => "info",
+        }
+    }
+}
+
+/// The part of the asset a diagnostic is about, used to navigate to its editor
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticTarget {
+    Trait,
+    AssociatedItems,
+    Method(usize),
+    Param { method: usize, param: usize },
+}
+
+impl DiagnosticTarget {
+    pub fn method_index(&self) -> Option<usize> {
+        match self {
+            DiagnosticTarget::Method(method) | DiagnosticTarget::Param { method, .. } => Some(*method),
+            DiagnosticTarget::Trait | DiagnosticTarget::AssociatedItems => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub target: DiagnosticTarget,
+    pub message: String,
+}
+
+/// Type names a signature can refer to without a path
+#[derive(Clone, Debug, Default)]
+pub struct TypeScope {
+    names: HashSet<String>,
+    aliases: HashSet<String>,
+}
+
+impl TypeScope {
+    /// Builds the scope from type picker candidates plus the standard prelude
+    pub fn new(candidates: &[TypeCandidate]) -> Self {
+        let mut scope = TypeScope {
+            names: PRELUDE_NAMES.iter().map(|name| name.to_string()).collect(),
+            aliases: HashSet::new(),
+        };
+        for candidate in candidates {
+            match &candidate.type_ref {
+                TypeRef::Primitive { name } => {
+                    scope.names.insert(name.clone());
+                }
+                TypeRef::AliasRef { alias } => {
+                    scope.aliases.insert(alias.clone());
+                }
+                TypeRef::Path { path } => {
+                    if let Ok(TypeExpr::Path { path, .. }) = TypeExpr::parse(path) {
+                        scope.names.insert(path);
+                    }
+                }
+            }
+        }
+        scope
+    }
+}
+
+/// Runs every check over `asset`
+pub fn validate(asset: &TraitAsset, scope: &TypeScope) -> Vec<Diagnostic> {
+    let meta = TraitMeta::from_asset(asset);
+    let mut validator = Validator { scope, meta: &meta, diagnostics: Vec::new() };
+    validator.check_trait(asset);
+    validator.check_associated_items();
+    validator.check_methods(asset);
+    validator.diagnostics
+}
+
+struct Validator<'a> {
+    scope: &'a TypeScope,
+    meta: &'a TraitMeta,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    fn push(&mut self, severity: Severity, target: DiagnosticTarget, message: String) {
+        self.diagnostics.push(Diagnostic { severity, target, message });
+    }
+
+    fn check_trait(&mut self, asset: &TraitAsset) {
+        let target = DiagnosticTarget::Trait;
+        if let Some(error) = identifier_error(&asset.name) {
+            self.push(Severity::Error, target, format!("Trait name: {}", error));
+        } else if !is_upper_camel_case(&asset.name) {
+            self.push(
+                Severity::Warning,
+                target,
+                format!("Trait `{}` should be UpperCamelCase, e.g. `{}`", asset.name, to_upper_camel_case(&asset.name)),
+            );
+        }
+
+        let described = asset.description.as_deref().is_some_and(|text| !text.trim().is_empty());
+        if self.meta.visibility == Visibility::Public && !described {
+            self.push(Severity::Info, target, format!("Public trait `{}` has no description", asset.name));
+        }
+
+        let meta = self.meta;
+        self.check_generic_names(&meta.generics, target);
+    }
+
+    fn check_associated_items(&mut self) {
+        let target = DiagnosticTarget::AssociatedItems;
+        let meta = self.meta;
+        let types = meta.associated_types.iter().map(|item| (item.name.trim(), "Associated type"));
+        let consts = meta.associated_consts.iter().map(|item| (item.name.trim(), "Associated constant"));
+
+        let mut seen = HashSet::new();
+        for (name, kind) in types.chain(consts) {
+            if let Some(error) = identifier_error(name) {
+                self.push(Severity::Error, target, format!("{} name: {}", kind, error));
+            } else if !seen.insert(name) {
+                self.push(Severity::Error, target, format!("`{}` is defined more than once", name));
+            }
+        }
+    }
+
+    fn check_methods(&mut self, asset: &TraitAsset) {
+        let mut seen = HashSet::new();
+        let public = self.meta.visibility == Visibility::Public;
+
+        for (index, method) in asset.methods.iter().enumerate() {
+            let target = DiagnosticTarget::Method(index);
+            if let Some(error) = identifier_error(&method.name) {
+                self.push(Severity::Error, target, format!("Method name: {}", error));
+            } else if !seen.insert(method.name.as_str()) {
+                self.push(Severity::Error, target, format!("Method `{}` is defined more than once", method.name));
+            } else if !is_snake_case(&method.name) {
+                self.push(
+                    Severity::Warning,
+                    target,
+                    format!("Method `{}` should be snake_case, e.g. `{}`", method.name, to_snake_case(&method.name)),
+                );
+            }
+
+            let documented = method.doc.as_deref().is_some_and(|doc| !doc.trim().is_empty());
+            if public && !documented {
+                self.push(Severity::Info, target, format!("Method `{}` has no documentation", method.name));
+            }
+
+            let method_generics = self.meta.method(index).generics;
+            self.check_generic_names(&method_generics, target);
+
+            let mut param_names = HashSet::new();
+            for (param_index, param) in method.signature.params.iter().enumerate() {
+                let param_target = DiagnosticTarget::Param { method: index, param: param_index };
+                if param.name == "self" {
+                    self.push(
+                        Severity::Error,
+                        param_target,
+                        format!("`{}`: `self` can't be a parameter name, pick a receiver instead", method.name),
+                    );
+                } else if !is_valid_param_name(&param.name) {
+                    let error = identifier_error(&param.name).unwrap_or_else(|| format!("`{}` is not a valid name", param.name));
+                    self.push(Severity::Error, param_target, format!("`{}` parameter: {}", method.name, error));
+                } else if param.name != "_" && !param_names.insert(param.name.as_str()) {
+                    self.push(
+                        Severity::Error,
+                        param_target,
+                        format!("`{}` has more than one parameter named `{}`", method.name, param.name),
+                    );
+                } else if !is_snake_case(&param.name) {
+                    self.push(
+                        Severity::Warning,
+                        param_target,
+                        format!("Parameter `{}` should be snake_case, e.g. `{}`", param.name, to_snake_case(&param.name)),
+                    );
+                }
+
+                let what = format!("Type of `{}`", param.name);
+                self.check_type(&param.type_ref, &method_generics, param_target, &what);
+            }
+
+            let what = format!("Return type of `{}`", method.name);
+            self.check_type(&method.signature.return_type, &method_generics, target, &what);
+        }
+    }
+
+    fn check_generic_names(&mut self, generics: &Generics, target: DiagnosticTarget) {
+        let mut seen = HashSet::new();
+        for param in &generics.params {
+            let name = param.name().trim();
+            let error = match param {
+                GenericParam::Lifetime { .. } => match name.strip_prefix('\'') {
+                    Some(rest) if identifier_error(rest).is_none() || rest == "_" => None,
+                    _ => Some(format!("`{}` is not a valid lifetime", name)),
+                },
+                _ => identifier_error(name).map(|error| format!("Generic parameter: {}", error)),
+            };
+            if let Some(error) = error {
+                self.push(Severity::Error, target, error);
+            } else if !seen.insert(name.to_string()) {
+                self.push(Severity::Error, target, format!("Generic parameter `{}` is declared more than once", name));
+            }
+        }
+    }
+
+    fn check_type(&mut self, type_ref: &TypeRef, method_generics: &Generics, target: DiagnosticTarget, what: &str) {
+        match type_ref {
+            TypeRef::Primitive { name } | TypeRef::Path { path: name } | TypeRef::AliasRef { alias: name }
+                if name.trim().is_empty() =>
+            {
+                self.push(Severity::Error, target, format!("{} is empty", what));
+            }
+            TypeRef::Primitive { name } => {
+                if !self.scope.names.contains(name) {
+                    self.push(Severity::Error, target, format!("{}: `{}` is not a primitive type", what, name));
+                }
+            }
+            TypeRef::AliasRef { alias } => {
+                if !self.scope.aliases.contains(alias) {
+                    self.push(Severity::Warning, target, format!("{}: type alias `{}` was not found", what, alias));
+                }
+            }
+            TypeRef::Path { path } => match TypeExpr::parse(path) {
+                Ok(expr) => {
+                    let mut unresolved = Vec::new();
+                    self.collect_unresolved(&expr, method_generics, &mut unresolved);
+                    for name in unresolved {
+                        self.push(Severity::Warning, target, format!("{}: cannot find type `{}`", what, name));
+                    }
+                }
+                Err(e) => self.push(Severity::Error, target, format!("{}: `{}` is not a valid type: {}", what, path, e)),
+            },
+        }
+    }
+
+    fn collect_unresolved(&self, expr: &TypeExpr, method_generics: &Generics, unresolved: &mut Vec<String>) {
+        if let TypeExpr::Path { path, .. } = expr {
+            if !self.resolves(path, method_generics) && !unresolved.contains(path) {
+                unresolved.push(path.clone());
+            }
+        }
+        for child in expr.children() {
+            self.collect_unresolved(child, method_generics, unresolved);
+        }
+    }
+
+    /// Single-segment paths must be in scope; `Self::Name` must be an associated type.
+    /// Other qualified paths (`std::...`, `crate::...`) are trusted.
+    fn resolves(&self, path: &str, method_generics: &Generics) -> bool {
+        if let Some(name) = path.strip_prefix("Self::") {
+            return !self.meta.supertraits.is_empty()
+                || self.meta.associated_types.iter().any(|item| item.name.trim() == name);
+        }
+        if path.contains("::") {
+            return true;
+        }
+        self.scope.names.contains(path)
+            || self.meta.generics.params.iter().chain(&method_generics.params).any(|param| param.name() == path)
+    }
+}
@@ -1,28 +1,78 @@
 use gpui::{*, prelude::FluentBuilder};
 use ui::{
-    v_flex, h_flex, ActiveTheme, StyledExt, IconName,
+    v_flex, h_flex, ActiveTheme, StyledExt, IconName, Sizable,
     dock::{Panel, PanelEvent},
     button::{Button, ButtonVariants},
+    checkbox::Checkbox,
     divider::Divider,
     input::{InputState, TextInput},
 };
 use ui_types_common::{TraitAsset, TraitMethod, MethodSignature, MethodParam, TypeRef};
+use std::path::PathBuf;
 use std::sync::Arc;
 use crate::method_editor::{MethodEditorView, MethodEditorEvent};
+use crate::codegen::{self, CodegenMode, CodegenOptions};
+use crate::generics_editor::{GenericsEditor, GenericsEditorEvent};
+use crate::history::{EditHistory, TraitCommand};
+use crate::ident::identifier_error;
+use crate::model::{self, attribute_body, split_bounds, AssociatedConst, AssociatedType, MethodMeta, TraitMeta, Visibility};
+use crate::object_safety::{self, QuickFix};
+use crate::rust_import;
+use crate::type_picker::{TypePicker, TypePickerEvent, TypeSlot, associated_types, builtin_types, project_traits, project_types};
+use crate::validation::{self, Diagnostic, DiagnosticTarget, Severity, TypeScope};
+
+/// Emitted by the workspace panels after they mutate the shared trait asset
+#[derive(Clone, Debug)]
+pub struct AssetChanged;
+
+/// Emitted after an edit to data other panels show, so every panel re-reads the asset
+#[derive(Clone, Debug)]
+pub struct SyncRequested;
+
+/// Emitted by the properties panel to open the Rust import dialog
+#[derive(Clone, Debug)]
+pub struct ImportRequested;
+
+/// Emitted by the properties panel to open the New Trait wizard
+#[derive(Clone, Debug)]
+pub struct TemplateRequested;
+
+/// Emitted by the properties panel to regenerate the output files of every trait
+#[derive(Clone, Debug)]
+pub struct RegenerateAllRequested;
+
+/// Supertraits offered as one-click suggestions, next to the project's own traits
+const SUPERTRAIT_SUGGESTIONS: &[&str] = &["Send", "Sync", "Debug", "Clone", "Default", "'static"];
+
+/// Common trait attributes offered as presets
+const ATTRIBUTE_PRESETS: &[&str] = &["must_use", "deprecated", "cfg(feature = \"\")", "doc(hidden)"];
+
+/// Pause in typing after which edits in the code preview are applied
+const CODE_EDIT_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(600);
 
 /// Properties Panel - Edit trait metadata
 pub struct PropertiesPanel {
     asset: Arc<parking_lot::RwLock<TraitAsset>>,
+    history: Arc<parking_lot::Mutex<EditHistory>>,
     name_input: Entity<InputState>,
     display_name_input: Entity<InputState>,
     description_input: Entity<InputState>,
+    supertraits_input: Entity<InputState>,
+    output_path_input: Entity<InputState>,
+    module_path_input: Entity<InputState>,
+    // One input per attribute, in declaration order
+    attribute_inputs: Vec<Entity<InputState>>,
+    generics_editor: Entity<GenericsEditor>,
+    // Trait assets of the project, offered as supertraits
+    project_traits: Vec<String>,
     focus_handle: FocusHandle,
-    on_modified: Arc<parking_lot::Mutex<Option<Box<dyn Fn() + Send + Sync>>>>,
+    _attribute_subscriptions: Vec<Subscription>,
 }
 
 impl PropertiesPanel {
     pub fn new(
         asset: Arc<parking_lot::RwLock<TraitAsset>>,
+        history: Arc<parking_lot::Mutex<EditHistory>>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
@@ -43,84 +93,281 @@ impl PropertiesPanel {
                 input.replace_text_in_range(None, desc, window, cx);
             });
         }
+        let meta = TraitMeta::from_asset(&asset_read);
         drop(asset_read);
+        let generics_editor = cx.new(|cx| GenericsEditor::new(meta.generics.clone(), window, cx));
+        let supertraits_input = cx.new(|cx| InputState::new(window, cx).placeholder("Send + Sync + Debug"));
+        supertraits_input.update(cx, |input, cx| {
+            input.set_value(meta.supertraits.join(" + "), window, cx);
+        });
+        let output_path_input = cx.new(|cx| InputState::new(window, cx).placeholder("src/traits/my_trait.rs"));
+        output_path_input.update(cx, |input, cx| {
+            input.set_value(meta.output_path.clone().unwrap_or_default(), window, cx);
+        });
+        let module_path_input = cx.new(|cx| InputState::new(window, cx).placeholder("crate::traits::my_trait"));
+        module_path_input.update(cx, |input, cx| {
+            input.set_value(meta.module_path.clone().unwrap_or_default(), window, cx);
+        });
 
         // Subscribe to input changes
-        cx.subscribe_in(&name_input, window, |this: &mut Self, _state, event: &ui::input::InputEvent, window, cx| {
+        cx.subscribe_in(&name_input, window, |this: &mut Self, _state, event: &ui::input::InputEvent, _window, cx| {
             match event {
                 ui::input::InputEvent::Change => {
-                    this.name_input.update(cx, |input, _cx| {
-                        this.asset.write().name = input.text().to_string();
-                    });
-                    this.notify_modified();
-                    cx.emit(PanelEvent::LayoutChanged);
-                    cx.notify();
+                    let new = this.name_input.read(cx).text().to_string();
+                    let old = this.asset.read().name.clone();
+                    this.execute(TraitCommand::SetName { old, new }, cx);
                 }
                 _ => {}
             }
         }).detach();
 
-        cx.subscribe_in(&display_name_input, window, |this: &mut Self, _state, event: &ui::input::InputEvent, window, cx| {
+        cx.subscribe_in(&display_name_input, window, |this: &mut Self, _state, event: &ui::input::InputEvent, _window, cx| {
             match event {
                 ui::input::InputEvent::Change => {
-                    this.display_name_input.update(cx, |input, _cx| {
-                        this.asset.write().display_name = input.text().to_string();
-                    });
-                    this.notify_modified();
-                    cx.emit(PanelEvent::LayoutChanged);
-                    cx.notify();
+                    let new = this.display_name_input.read(cx).text().to_string();
+                    let old = this.asset.read().display_name.clone();
+                    this.execute(TraitCommand::SetDisplayName { old, new }, cx);
                 }
                 _ => {}
             }
         }).detach();
 
-        cx.subscribe_in(&description_input, window, |this: &mut Self, _state, event: &ui::input::InputEvent, window, cx| {
+        cx.subscribe_in(&description_input, window, |this: &mut Self, _state, event: &ui::input::InputEvent, _window, cx| {
             match event {
                 ui::input::InputEvent::Change => {
-                    this.description_input.update(cx, |input, _cx| {
-                        let desc = input.text().to_string();
-                        this.asset.write().description = if desc.is_empty() { None } else { Some(desc) };
-                    });
-                    this.notify_modified();
-                    cx.emit(PanelEvent::LayoutChanged);
-                    cx.notify();
+                    let desc = this.description_input.read(cx).text().to_string();
+                    let new = if desc.is_empty() { None } else { Some(desc) };
+                    let old = this.asset.read().description.clone();
+                    this.execute(TraitCommand::SetDescription { old, new }, cx);
                 }
                 _ => {}
             }
         }).detach();
 
-        Self {
+        cx.subscribe_in(&supertraits_input, window, |this: &mut Self, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                let supertraits = split_bounds(&this.supertraits_input.read(cx).text().to_string());
+                this.update_meta(|meta| meta.supertraits = supertraits, cx);
+            }
+        }).detach();
+
+        cx.subscribe_in(&output_path_input, window, |this: &mut Self, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                let path = optional_text(&this.output_path_input, cx);
+                this.update_meta(|meta| meta.output_path = path, cx);
+            }
+        }).detach();
+
+        cx.subscribe_in(&module_path_input, window, |this: &mut Self, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                let module = optional_text(&this.module_path_input, cx);
+                this.update_meta(|meta| meta.module_path = module, cx);
+            }
+        }).detach();
+
+        cx.subscribe_in(&generics_editor, window, |this: &mut Self, _, event: &GenericsEditorEvent, _window, cx| {
+            let GenericsEditorEvent::Changed(generics) = event;
+            let generics = generics.clone();
+            this.update_meta(|meta| meta.generics = generics, cx);
+        }).detach();
+
+        let mut panel = Self {
             asset,
+            history,
             name_input,
             display_name_input,
             description_input,
+            supertraits_input,
+            output_path_input,
+            module_path_input,
+            attribute_inputs: Vec::new(),
+            generics_editor,
+            project_traits: Vec::new(),
             focus_handle: cx.focus_handle(),
-            on_modified: Arc::new(parking_lot::Mutex::new(None)),
+            _attribute_subscriptions: Vec::new(),
+        };
+        panel.rebuild_attribute_inputs(window, cx);
+        panel
+    }
+
+    fn execute(&mut self, command: TraitCommand, cx: &mut Context<Self>) -> bool {
+        let changed = self.history.lock().execute(command, &mut self.asset.write());
+        if changed {
+            self.notify_modified(cx);
+            cx.emit(PanelEvent::LayoutChanged);
+            cx.notify();
         }
+        changed
     }
 
-    fn notify_modified(&self) {
-        if let Some(callback) = self.on_modified.lock().as_ref() {
-            callback();
+    /// Records a change to the trait-level editor data as one undoable command
+    fn update_meta(&mut self, edit: impl FnOnce(&mut TraitMeta), cx: &mut Context<Self>) -> bool {
+        let old = TraitMeta::from_asset(&self.asset.read());
+        let mut new = old.clone();
+        edit(&mut new);
+        self.execute(TraitCommand::SetTraitMeta { old, new }, cx)
+    }
+
+    /// Sets the directory scanned for trait assets offered as supertraits
+    pub fn set_project_root(&mut self, root: Option<PathBuf>) {
+        self.project_traits = root.as_deref().map(project_traits).unwrap_or_default();
+    }
+
+    /// Recreates the attribute inputs from the shared asset
+    fn rebuild_attribute_inputs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.attribute_inputs.clear();
+        self._attribute_subscriptions.clear();
+
+        let attributes = TraitMeta::from_asset(&self.asset.read()).attributes;
+        for attribute in attributes {
+            let input = cx.new(|cx| InputState::new(window, cx).placeholder("must_use"));
+            input.update(cx, |input, cx| input.set_value(attribute, window, cx));
+
+            let subscription = cx.subscribe_in(&input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+                if let ui::input::InputEvent::Change = event {
+                    let attributes = this.read_attributes(cx);
+                    this.update_meta(|meta| meta.attributes = attributes, cx);
+                }
+            });
+            self.attribute_inputs.push(input);
+            self._attribute_subscriptions.push(subscription);
+        }
+        cx.notify();
+    }
+
+    fn read_attributes(&self, cx: &App) -> Vec<String> {
+        self.attribute_inputs
+            .iter()
+            .map(|input| attribute_body(&input.read(cx).text().to_string()).to_string())
+            .collect()
+    }
+
+    fn add_attribute(&mut self, attribute: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let attribute = attribute.to_string();
+        if self.update_meta(|meta| meta.attributes.push(attribute), cx) {
+            self.rebuild_attribute_inputs(window, cx);
+        }
+    }
+
+    fn remove_attribute(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let removed = self.update_meta(|meta| {
+            if index < meta.attributes.len() {
+                meta.attributes.remove(index);
+            }
+        }, cx);
+        if removed {
+            self.rebuild_attribute_inputs(window, cx);
+        }
+    }
+
+    fn add_supertrait(&mut self, supertrait: &str, window: &mut Window, cx: &mut Context<Self>) {
+        let mut supertraits = split_bounds(&self.supertraits_input.read(cx).text().to_string());
+        if supertraits.iter().any(|existing| existing == supertrait) {
+            return;
+        }
+        supertraits.push(supertrait.to_string());
+        let text = supertraits.join(" + ");
+        self.update_meta(|meta| meta.supertraits = supertraits, cx);
+        self.supertraits_input.update(cx, |input, cx| input.set_value(text, window, cx));
+    }
+
+    /// Applies an object-safety quick fix; method fixes also touch the method editors
+    fn apply_quick_fix(&mut self, fix: QuickFix, cx: &mut Context<Self>) {
+        if self.update_meta(|meta| fix.apply(meta), cx) {
+            cx.emit(SyncRequested);
+        }
+    }
+
+    /// Refills the inputs from the shared asset after it was changed elsewhere (undo, reload)
+    pub fn sync_from_asset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let asset = self.asset.read();
+        let values = [
+            (self.name_input.clone(), asset.name.clone()),
+            (self.display_name_input.clone(), asset.display_name.clone()),
+            (self.description_input.clone(), asset.description.clone().unwrap_or_default()),
+        ];
+        let meta = TraitMeta::from_asset(&asset);
+        drop(asset);
+
+        self.generics_editor.update(cx, |editor, cx| editor.set_generics(meta.generics.clone(), window, cx));
+        if split_bounds(&self.supertraits_input.read(cx).text().to_string()) != meta.supertraits {
+            let text = meta.supertraits.join(" + ");
+            self.supertraits_input.update(cx, |input, cx| input.set_value(text, window, cx));
+        }
+        if self.read_attributes(cx) != meta.attributes {
+            if self.attribute_inputs.len() == meta.attributes.len() {
+                for (input, attribute) in self.attribute_inputs.iter().zip(meta.attributes) {
+                    if attribute_body(&input.read(cx).text().to_string()) != attribute {
+                        input.update(cx, |input, cx| input.set_value(attribute, window, cx));
+                    }
+                }
+            } else {
+                self.rebuild_attribute_inputs(window, cx);
+            }
+        }
+
+        let output_values = [
+            (self.output_path_input.clone(), meta.output_path.clone()),
+            (self.module_path_input.clone(), meta.module_path.clone()),
+        ];
+        for (input, value) in output_values {
+            if optional_text(&input, cx) != value {
+                input.update(cx, |input, cx| input.set_value(value.unwrap_or_default(), window, cx));
+            }
+        }
+
+        for (input, value) in values {
+            if input.read(cx).text().to_string() != value {
+                input.update(cx, |input, cx| input.set_value(value, window, cx));
+            }
         }
+        cx.notify();
     }
 
-    pub fn set_on_modified<F>(&self, callback: F)
-    where
-        F: Fn() + Send + Sync + 'static,
-    {
-        *self.on_modified.lock() = Some(Box::new(callback));
+    fn notify_modified(&self, cx: &mut Context<Self>) {
+        cx.emit(AssetChanged);
     }
 }
 
 impl EventEmitter<PanelEvent> for PropertiesPanel {}
+impl EventEmitter<AssetChanged> for PropertiesPanel {}
+impl EventEmitter<SyncRequested> for PropertiesPanel {}
+impl EventEmitter<ImportRequested> for PropertiesPanel {}
+impl EventEmitter<TemplateRequested> for PropertiesPanel {}
+impl EventEmitter<RegenerateAllRequested> for PropertiesPanel {}
+
+/// The trimmed text of `input`, or `None` when it is blank
+fn optional_text(input: &Entity<InputState>, cx: &App) -> Option<String> {
+    let text = input.read(cx).text().to_string();
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
 
 impl Render for PropertiesPanel {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let (meta, trait_name, violations) = {
+            let asset = self.asset.read();
+            (TraitMeta::from_asset(&asset), asset.name.clone(), object_safety::violations(&asset))
+        };
+        let dyn_compatible = violations.is_empty();
+        let (badge_color, badge_label) = if dyn_compatible {
+            (cx.theme().success, "dyn-compatible")
+        } else {
+            (cx.theme().warning, "not dyn-compatible")
+        };
+        let suggestions: Vec<String> = SUPERTRAIT_SUGGESTIONS
+            .iter()
+            .map(|name| name.to_string())
+            .chain(self.project_traits.iter().filter(|name| **name != trait_name).cloned())
+            .filter(|name| !meta.supertraits.contains(name))
+            .collect();
+
         v_flex()
+            .id("trait-properties")
             .size_full()
             .p_4()
             .gap_4()
+            .overflow_y_scroll()
             .bg(cx.theme().sidebar)
             .child(
                 v_flex()
@@ -158,6 +405,234 @@ impl Render for PropertiesPanel {
                     )
                     .child(TextInput::new(&self.description_input))
             )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(cx.theme().foreground)
+                            .child("Visibility")
+                    )
+                    .child(
+                        h_flex()
+                            .gap_1()
+                            .children(Visibility::ALL.into_iter().map(|visibility| {
+                                Button::new(SharedString::from(format!("visibility-{:?}", visibility)))
+                                    .with_size(ui::Size::Small)
+                                    .label(visibility.label())
+                                    .when(visibility == meta.visibility, |button| button.primary())
+                                    .when(visibility != meta.visibility, |button| button.ghost())
+                                    .on_click(cx.listener(move |this, _, _window, cx| {
+                                        this.update_meta(|meta| meta.visibility = visibility, cx);
+                                    }))
+                            }))
+                    )
+                    .child(
+                        Checkbox::new("unsafe-trait")
+                            .label("unsafe trait")
+                            .checked(meta.is_unsafe)
+                            .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                let checked = *checked;
+                                this.update_meta(|meta| meta.is_unsafe = checked, cx);
+                            }))
+                    )
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(cx.theme().foreground)
+                            .child("Supertraits")
+                    )
+                    .child(TextInput::new(&self.supertraits_input))
+                    .child(
+                        h_flex()
+                            .flex_wrap()
+                            .gap_1()
+                            .children(suggestions.into_iter().map(|name| {
+                                Button::new(SharedString::from(format!("supertrait-{}", name)))
+                                    .ghost()
+                                    .with_size(ui::Size::XSmall)
+                                    .icon(IconName::Plus)
+                                    .label(name.clone())
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.add_supertrait(&name, window, cx);
+                                    }))
+                            }))
+                    )
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(cx.theme().foreground)
+                                    .child("Trait Objects")
+                            )
+                            .child(
+                                div()
+                                    .px_2()
+                                    .rounded(px(4.0))
+                                    .border_1()
+                                    .border_color(badge_color)
+                                    .text_xs()
+                                    .text_color(badge_color)
+                                    .child(badge_label)
+                            )
+                    )
+                    .when(dyn_compatible, |this| {
+                        this.child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(format!("Usable as `Box<dyn {}>`", trait_name))
+                        )
+                    })
+                    .children(violations.into_iter().enumerate().map(|(ix, violation)| {
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_xs()
+                                    .text_color(cx.theme().foreground)
+                                    .child(violation.message.clone())
+                            )
+                            .when_some(violation.quick_fix(), |this, fix| {
+                                this.child(
+                                    Button::new(("object-safety-fix", ix))
+                                        .ghost()
+                                        .with_size(ui::Size::XSmall)
+                                        .label(fix.label())
+                                        .on_click(cx.listener(move |this, _, _window, cx| {
+                                            this.apply_quick_fix(fix.clone(), cx);
+                                        }))
+                                )
+                            })
+                    }))
+            )
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(cx.theme().foreground)
+                            .child(format!("Attributes ({})", self.attribute_inputs.len()))
+                    )
+                    .children(self.attribute_inputs.iter().enumerate().map(|(ix, input)| {
+                        h_flex()
+                            .gap_1()
+                            .items_center()
+                            .child(div().text_sm().text_color(cx.theme().muted_foreground).child("#["))
+                            .child(TextInput::new(input).flex_1().with_size(ui::Size::Small))
+                            .child(div().text_sm().text_color(cx.theme().muted_foreground).child("]"))
+                            .child(
+                                Button::new(("remove-attribute", ix))
+                                    .ghost()
+                                    .with_size(ui::Size::XSmall)
+                                    .icon(IconName::Close)
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.remove_attribute(ix, window, cx);
+                                    }))
+                            )
+                    }))
+                    .child(
+                        h_flex()
+                            .flex_wrap()
+                            .gap_1()
+                            .children(ATTRIBUTE_PRESETS.iter().enumerate().map(|(ix, preset)| {
+                                Button::new(("add-attribute", ix))
+                                    .ghost()
+                                    .with_size(ui::Size::XSmall)
+                                    .icon(IconName::Plus)
+                                    .label(*preset)
+                                    .on_click(cx.listener(move |this, _, window, cx| {
+                                        this.add_attribute(preset, window, cx);
+                                    }))
+                            }))
+                            .child(
+                                Button::new("add-custom-attribute")
+                                    .ghost()
+                                    .with_size(ui::Size::XSmall)
+                                    .icon(IconName::Plus)
+                                    .label("Custom")
+                                    .on_click(cx.listener(|this, _, window, cx| {
+                                        this.add_attribute("", window, cx);
+                                    }))
+                            )
+                    )
+            )
+            .child(self.generics_editor.clone())
+            .child(
+                v_flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(cx.theme().foreground)
+                            .child("Output")
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("Generated code is written to this file on save, relative to the project root")
+                    )
+                    .child(TextInput::new(&self.output_path_input).with_size(ui::Size::Small))
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(cx.theme().muted_foreground)
+                            .child("Module path, used for the file name when no path is set")
+                    )
+                    .child(TextInput::new(&self.module_path_input).with_size(ui::Size::Small))
+                    .child(
+                        Button::new("regenerate-all-traits")
+                            .ghost()
+                            .with_size(ui::Size::Small)
+                            .icon(IconName::Code)
+                            .label("Regenerate all traits")
+                            .on_click(cx.listener(|_this, _, _window, cx| {
+                                cx.emit(RegenerateAllRequested);
+                            }))
+                    )
+            )
+            .child(
+                Button::new("import-from-rust")
+                    .ghost()
+                    .with_size(ui::Size::Small)
+                    .icon(IconName::Code)
+                    .label("Import from Rust…")
+                    .on_click(cx.listener(|_this, _, _window, cx| {
+                        cx.emit(ImportRequested);
+                    }))
+            )
+            .child(
+                Button::new("new-from-template")
+                    .ghost()
+                    .with_size(ui::Size::Small)
+                    .icon(IconName::Plus)
+                    .label("New from template…")
+                    .on_click(cx.listener(|_this, _, _window, cx| {
+                        cx.emit(TemplateRequested);
+                    }))
+            )
     }
 }
 
@@ -187,61 +662,46 @@ impl Panel for PropertiesPanel {
 /// Methods Panel - Manage trait methods
 pub struct MethodsPanel {
     asset: Arc<parking_lot::RwLock<TraitAsset>>,
+    history: Arc<parking_lot::Mutex<EditHistory>>,
     method_editors: Vec<Entity<MethodEditorView>>,
+    // Open type picker and the method/slot it writes to
+    type_picker: Option<(Entity<TypePicker>, usize, TypeSlot)>,
+    project_root: Option<PathBuf>,
+    scroll_handle: ScrollHandle,
     focus_handle: FocusHandle,
-    on_modified: Arc<parking_lot::Mutex<Option<Box<dyn Fn() + Send + Sync>>>>,
 }
 
 impl MethodsPanel {
     pub fn new(
         asset: Arc<parking_lot::RwLock<TraitAsset>>,
+        history: Arc<parking_lot::Mutex<EditHistory>>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
         let asset_read = asset.read();
+        let meta = TraitMeta::from_asset(&asset_read);
         let mut method_editors = Vec::new();
 
         for (index, method) in asset_read.methods.iter().enumerate() {
-            let editor = cx.new(|cx| MethodEditorView::new(method.clone(), index, window, cx));
-
-            // Subscribe to method editor events
-            cx.subscribe(&editor, |this: &mut Self, _, event: &MethodEditorEvent, cx| {
-                match event {
-                    MethodEditorEvent::MethodChanged(index, method) => {
-                        let mut asset = this.asset.write();
-                        if *index < asset.methods.len() {
-                            asset.methods[*index] = method.clone();
-                            drop(asset);
-                            this.notify_modified();
-                            cx.emit(PanelEvent::LayoutChanged);
-                            cx.notify();
-                        }
-                    }
-                    MethodEditorEvent::RemoveRequested(index) => {
-                        this.remove_method(*index, cx);
-                    }
-                    MethodEditorEvent::TypePickerRequested(index) => {
-                        tracing::info!("Type picker requested for method {}", index);
-                    }
-                    MethodEditorEvent::AddParameterRequested(index) => {
-                        tracing::info!("Add parameter requested for method {}", index);
-                    }
-                }
-            }).detach();
-
+            let method_meta = meta.method(index);
+            let editor = cx.new(|cx| MethodEditorView::new(method.clone(), method_meta, index, window, cx));
+            Self::subscribe_method_editor(&editor, window, cx);
             method_editors.push(editor);
         }
         drop(asset_read);
 
         Self {
             asset,
+            history,
             method_editors,
+            type_picker: None,
+            project_root: None,
+            scroll_handle: ScrollHandle::new(),
             focus_handle: cx.focus_handle(),
-            on_modified: Arc::new(parking_lot::Mutex::new(None)),
         }
     }
 
-    fn add_method(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+    pub fn add_method(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let new_method = TraitMethod {
             name: format!("method{}", self.asset.read().methods.len() + 1),
             signature: MethodSignature {
@@ -252,50 +712,123 @@ impl MethodsPanel {
             doc: None,
         };
 
-        let index = {
-            let mut asset = self.asset.write();
-            let idx = asset.methods.len();
-            asset.methods.push(new_method.clone());
-            idx
-        };
+        let index = self.asset.read().methods.len();
+        let meta = MethodMeta::default();
+        self.history.lock().execute(
+            TraitCommand::InsertMethod { index, method: new_method.clone(), meta: meta.clone() },
+            &mut self.asset.write(),
+        );
+
+        let editor = cx.new(|cx| MethodEditorView::new(new_method, meta, index, window, cx));
+        Self::subscribe_method_editor(&editor, window, cx);
+
+        self.method_editors.push(editor);
 
-        let editor = cx.new(|cx| MethodEditorView::new(new_method, index, window, cx));
+        self.notify_modified(cx);
+        cx.emit(PanelEvent::LayoutChanged);
+        cx.notify();
+    }
 
-        // Subscribe to method editor events
-        cx.subscribe(&editor, |this: &mut Self, _, event: &MethodEditorEvent, cx| {
+    fn subscribe_method_editor(
+        editor: &Entity<MethodEditorView>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.subscribe_in(editor, window, |this: &mut Self, _, event: &MethodEditorEvent, window, cx| {
             match event {
-                MethodEditorEvent::MethodChanged(index, method) => {
-                    let mut asset = this.asset.write();
-                    if *index < asset.methods.len() {
-                        asset.methods[*index] = method.clone();
-                        drop(asset);
-                        this.notify_modified();
-                        cx.emit(PanelEvent::LayoutChanged);
-                        cx.notify();
+                MethodEditorEvent::MethodChanged(index, method, meta) => {
+                    let old = this.asset.read().methods.get(*index).cloned();
+                    if let Some(old) = old {
+                        let old_meta = model::method_meta(&this.asset.read(), *index);
+                        let command = TraitCommand::ReplaceMethod {
+                            index: *index,
+                            old,
+                            new: method.clone(),
+                            old_meta,
+                            new_meta: meta.clone(),
+                        };
+                        if this.history.lock().execute(command, &mut this.asset.write()) {
+                            this.notify_modified(cx);
+                            cx.emit(PanelEvent::LayoutChanged);
+                            cx.notify();
+                        }
                     }
                 }
                 MethodEditorEvent::RemoveRequested(index) => {
                     this.remove_method(*index, cx);
                 }
-                MethodEditorEvent::TypePickerRequested(index) => {
-                    tracing::info!("Type picker requested for method {}", index);
+                MethodEditorEvent::TypePickerRequested(index, slot) => {
+                    this.open_type_picker(*index, *slot, window, cx);
                 }
                 MethodEditorEvent::AddParameterRequested(index) => {
-                    tracing::info!("Add parameter requested for method {}", index);
+                    this.open_type_picker(*index, TypeSlot::NewParam, window, cx);
                 }
             }
         }).detach();
+    }
 
-        self.method_editors.push(editor);
+    /// Sets the directory scanned for project types offered by the type picker
+    pub fn set_project_root(&mut self, root: Option<PathBuf>) {
+        self.project_root = root;
+    }
 
-        self.notify_modified();
-        cx.emit(PanelEvent::LayoutChanged);
+    fn open_type_picker(&mut self, method_index: usize, slot: TypeSlot, window: &mut Window, cx: &mut Context<Self>) {
+        let mut candidates = associated_types(&TraitMeta::from_asset(&self.asset.read()));
+        candidates.extend(builtin_types());
+        if let Some(root) = &self.project_root {
+            candidates.extend(project_types(root));
+        }
+
+        let current = {
+            let asset = self.asset.read();
+            asset.methods.get(method_index).and_then(|method| match slot {
+                TypeSlot::Return => Some(method.signature.return_type.clone()),
+                TypeSlot::Param(i) => method.signature.params.get(i).map(|param| param.type_ref.clone()),
+                TypeSlot::NewParam => None,
+            })
+        };
+
+        let picker = cx.new(|cx| TypePicker::new(candidates, current, window, cx));
+        cx.subscribe_in(&picker, window, |this: &mut Self, _, event: &TypePickerEvent, window, cx| {
+            match event {
+                TypePickerEvent::Selected(type_ref) => {
+                    if let Some((_, method_index, slot)) = this.type_picker.take() {
+                        if let Some(editor) = this.method_editors.get(method_index) {
+                            editor.update(cx, |editor, cx| editor.apply_type(slot, type_ref.clone(), window, cx));
+                        }
+                    }
+                    cx.notify();
+                }
+                TypePickerEvent::Dismissed => {
+                    this.type_picker = None;
+                    cx.notify();
+                }
+            }
+        }).detach();
+        picker.update(cx, |picker, cx| picker.focus(window, cx));
+
+        self.type_picker = Some((picker, method_index, slot));
+        cx.notify();
+    }
+
+    /// Scrolls a method editor into view and focuses its name or one of its parameters
+    pub fn reveal_method(&mut self, index: usize, param: Option<usize>, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(editor) = self.method_editors.get(index).cloned() else {
+            return;
+        };
+        self.scroll_handle.scroll_to_item(index);
+        editor.update(cx, |editor, cx| editor.focus_target(param, window, cx));
         cx.notify();
     }
 
     fn remove_method(&mut self, index: usize, cx: &mut Context<Self>) {
-        if index < self.method_editors.len() {
-            self.asset.write().methods.remove(index);
+        let method = self.asset.read().methods.get(index).cloned();
+        if let Some(method) = method.filter(|_| index < self.method_editors.len()) {
+            let meta = model::method_meta(&self.asset.read(), index);
+            self.history.lock().execute(
+                TraitCommand::RemoveMethod { index, method, meta },
+                &mut self.asset.write(),
+            );
             self.method_editors.remove(index);
 
             // Update indices for remaining method editors
@@ -305,33 +838,79 @@ impl MethodsPanel {
                 });
             }
 
-            self.notify_modified();
+            self.notify_modified(cx);
             cx.emit(PanelEvent::LayoutChanged);
             cx.notify();
         }
     }
 
-    fn notify_modified(&self) {
-        if let Some(callback) = self.on_modified.lock().as_ref() {
-            callback();
+    /// Brings the method editors back in line with the shared asset after it was changed
+    /// elsewhere (undo, reload). Existing editors are updated in place.
+    pub fn sync_from_asset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (methods, meta) = {
+            let asset = self.asset.read();
+            (asset.methods.clone(), TraitMeta::from_asset(&asset))
+        };
+
+        // An editor follows its method by name, so the fields open in it stay with the
+        // method when methods moved. Other methods reuse the editor at their position.
+        let mut old_editors: Vec<_> = std::mem::take(&mut self.method_editors).into_iter().map(Some).collect();
+        let old_names: Vec<String> = old_editors
+            .iter()
+            .map(|editor| editor.as_ref().map(|editor| editor.read(cx).method.name.clone()).unwrap_or_default())
+            .collect();
+        let mut matched: Vec<_> = methods
+            .iter()
+            .map(|method| {
+                let position = (0..old_editors.len()).find(|&i| old_editors[i].is_some() && old_names[i] == method.name);
+                position.and_then(|i| old_editors[i].take())
+            })
+            .collect();
+        for (index, editor) in matched.iter_mut().enumerate() {
+            if editor.is_none() {
+                *editor = old_editors.get_mut(index).and_then(Option::take);
+            }
+        }
+
+        for (index, (method, editor)) in methods.into_iter().zip(matched).enumerate() {
+            let method_meta = meta.method(index);
+            let editor = match editor {
+                Some(editor) => {
+                    editor.update(cx, |editor, cx| editor.set_method(method, method_meta, index, window, cx));
+                    editor
+                }
+                None => {
+                    let editor = cx.new(|cx| MethodEditorView::new(method, method_meta, index, window, cx));
+                    Self::subscribe_method_editor(&editor, window, cx);
+                    editor
+                }
+            };
+            self.method_editors.push(editor);
+        }
+
+        // A type picker for a method that is gone has nothing left to write to
+        if self.type_picker.as_ref().is_some_and(|(_, index, _)| *index >= self.method_editors.len()) {
+            self.type_picker = None;
         }
+
+        cx.emit(PanelEvent::LayoutChanged);
+        cx.notify();
     }
 
-    pub fn set_on_modified<F>(&self, callback: F)
-    where
-        F: Fn() + Send + Sync + 'static,
-    {
-        *self.on_modified.lock() = Some(Box::new(callback));
+    fn notify_modified(&self, cx: &mut Context<Self>) {
+        cx.emit(AssetChanged);
     }
 }
 
 impl EventEmitter<PanelEvent> for MethodsPanel {}
+impl EventEmitter<AssetChanged> for MethodsPanel {}
 
 impl Render for MethodsPanel {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let methods_count = self.asset.read().methods.len();
 
         v_flex()
+            .relative()
             .size_full()
             .gap_3()
             .bg(cx.theme().sidebar)
@@ -367,6 +946,7 @@ impl Render for MethodsPanel {
                     .gap_2()
                     .flex_1()
                     .overflow_scroll()
+                    .track_scroll(&self.scroll_handle)
                     .children(
                         self.method_editors.iter().map(|editor| editor.clone())
                     )
@@ -400,6 +980,31 @@ impl Render for MethodsPanel {
                         )
                     })
             )
+            .when_some(self.type_picker.as_ref().map(|(picker, _, _)| picker.clone()), |this, picker| {
+                this.child(
+                    div()
+                        .id("type-picker-overlay")
+                        .absolute()
+                        .top_0()
+                        .left_0()
+                        .size_full()
+                        .flex()
+                        .justify_center()
+                        .pt(px(48.0))
+                        .bg(gpui::black().opacity(0.3))
+                        .occlude()
+                        .on_click(cx.listener(|this, _, _window, cx| {
+                            this.type_picker = None;
+                            cx.notify();
+                        }))
+                        .child(
+                            div()
+                                .id("type-picker-popover")
+                                .on_click(|_, _window, cx| cx.stop_propagation())
+                                .child(picker)
+                        )
+                )
+            })
     }
 }
 
@@ -426,130 +1031,614 @@ impl Panel for MethodsPanel {
     }
 }
 
-/// Code Preview Panel - Display generated Rust code
-pub struct CodePreviewPanel {
+/// Inputs of one associated item. For constants `bounds` holds the type.
+struct AssociatedItemRow {
+    name: Entity<InputState>,
+    bounds: Entity<InputState>,
+    default: Entity<InputState>,
+}
+
+/// Associated Items Panel - Edit associated types and constants
+pub struct AssociatedItemsPanel {
     asset: Arc<parking_lot::RwLock<TraitAsset>>,
-    code_input: Entity<InputState>,
+    history: Arc<parking_lot::Mutex<EditHistory>>,
+    type_rows: Vec<AssociatedItemRow>,
+    const_rows: Vec<AssociatedItemRow>,
     focus_handle: FocusHandle,
-    needs_update: Arc<parking_lot::Mutex<bool>>,
+    _subscriptions: Vec<Subscription>,
 }
 
-impl CodePreviewPanel {
+impl AssociatedItemsPanel {
     pub fn new(
         asset: Arc<parking_lot::RwLock<TraitAsset>>,
+        history: Arc<parking_lot::Mutex<EditHistory>>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> Self {
-        let code_input = cx.new(|cx| {
-            use ui::input::TabSize;
-            InputState::new(window, cx)
-                .code_editor("rust")
-                .line_number(true)
-                .minimap(true)
-                .tab_size(TabSize {
-                    tab_size: 4,
-                    hard_tabs: false,
-                })
-        });
-
-        let panel = Self {
+        let mut panel = Self {
             asset,
-            code_input,
+            history,
+            type_rows: Vec::new(),
+            const_rows: Vec::new(),
             focus_handle: cx.focus_handle(),
-            needs_update: Arc::new(parking_lot::Mutex::new(true)),
+            _subscriptions: Vec::new(),
         };
+        panel.rebuild_rows(window, cx);
+        panel
+    }
 
-        // Generate initial code
-        panel.update_code(window, cx);
+    fn item_texts(meta: &TraitMeta) -> (Vec<[String; 3]>, Vec<[String; 3]>) {
+        let types = meta
+            .associated_types
+            .iter()
+            .map(|item| [item.name.clone(), item.bounds.join(" + "), item.default.clone().unwrap_or_default()])
+            .collect();
+        let consts = meta
+            .associated_consts
+            .iter()
+            .map(|item| [item.name.clone(), item.ty.clone(), item.default.clone().unwrap_or_default()])
+            .collect();
+        (types, consts)
+    }
 
-        panel
+    /// Recreates every input from the shared asset
+    fn rebuild_rows(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self._subscriptions.clear();
+        let (types, consts) = Self::item_texts(&TraitMeta::from_asset(&self.asset.read()));
+
+        let type_rows = types
+            .into_iter()
+            .map(|[name, bounds, default]| AssociatedItemRow {
+                name: self.new_input("Output", name, window, cx),
+                bounds: self.new_input("Clone + Send", bounds, window, cx),
+                default: self.new_input("default", default, window, cx),
+            })
+            .collect();
+        let const_rows = consts
+            .into_iter()
+            .map(|[name, ty, default]| AssociatedItemRow {
+                name: self.new_input("ID", name, window, cx),
+                bounds: self.new_input("u32", ty, window, cx),
+                default: self.new_input("default", default, window, cx),
+            })
+            .collect();
+        self.type_rows = type_rows;
+        self.const_rows = const_rows;
+        cx.notify();
     }
 
-    fn update_code(&self, window: &mut Window, cx: &mut Context<Self>) {
-        let code = self.generate_rust_code();
-        self.code_input.update(cx, |input, cx| {
-            input.replace_text_in_range(None, &code, window, cx);
+    fn new_input(&mut self, placeholder: &'static str, value: String, window: &mut Window, cx: &mut Context<Self>) -> Entity<InputState> {
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder(placeholder));
+        input.update(cx, |input, cx| input.set_value(value, window, cx));
+
+        let subscription = cx.subscribe_in(&input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                let (associated_types, associated_consts) = this.read_rows(cx);
+                let old = TraitMeta::from_asset(&this.asset.read());
+                let new = TraitMeta { associated_types, associated_consts, ..old.clone() };
+                this.execute(TraitCommand::SetTraitMeta { old, new }, cx);
+            }
         });
-        *self.needs_update.lock() = false;
+        self._subscriptions.push(subscription);
+        input
     }
 
-    fn generate_rust_code(&self) -> String {
-        let asset = self.asset.read();
-        let mut code = String::new();
+    /// Builds the associated items described by the inputs
+    fn read_rows(&self, cx: &App) -> (Vec<AssociatedType>, Vec<AssociatedConst>) {
+        let text = |input: &Entity<InputState>| input.read(cx).text().to_string().trim().to_string();
+        let optional = |input: &Entity<InputState>| Some(text(input)).filter(|value| !value.is_empty());
+
+        let types = self
+            .type_rows
+            .iter()
+            .map(|row| AssociatedType {
+                name: text(&row.name),
+                bounds: split_bounds(&text(&row.bounds)),
+                default: optional(&row.default),
+            })
+            .collect();
+        let consts = self
+            .const_rows
+            .iter()
+            .map(|row| AssociatedConst {
+                name: text(&row.name),
+                ty: text(&row.bounds),
+                default: optional(&row.default),
+            })
+            .collect();
+        (types, consts)
+    }
 
-        // Add documentation if present
-        if let Some(desc) = &asset.description {
-            code.push_str(&format!("/// {}\n", desc));
+    fn execute(&mut self, command: TraitCommand, cx: &mut Context<Self>) -> bool {
+        let changed = self.history.lock().execute(command, &mut self.asset.write());
+        if changed {
+            cx.emit(AssetChanged);
+            cx.emit(PanelEvent::LayoutChanged);
+            cx.notify();
         }
+        changed
+    }
 
-        // Trait declaration
-        code.push_str(&format!("pub trait {} {{\n", asset.name));
-
-        // Methods
-        for method in &asset.methods {
-            if let Some(doc) = &method.doc {
-                code.push_str(&format!("    /// {}\n", doc));
-            }
+    /// Applies a structural change (add or remove) to the associated items
+    fn edit_items(&mut self, edit: impl FnOnce(&mut TraitMeta), window: &mut Window, cx: &mut Context<Self>) {
+        let old = TraitMeta::from_asset(&self.asset.read());
+        let mut new = old.clone();
+        edit(&mut new);
+        if self.execute(TraitCommand::SetTraitMeta { old, new }, cx) {
+            self.rebuild_rows(window, cx);
+        }
+    }
 
-            // Method signature
-            code.push_str("    fn ");
-            code.push_str(&method.name);
-            code.push_str("(&self");
-
-            // Parameters
-            for param in &method.signature.params {
-                code.push_str(", ");
-                code.push_str(&param.name);
-                code.push_str(": ");
-                code.push_str(&Self::type_ref_to_string(&param.type_ref));
-            }
+    fn add_type(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.edit_items(|meta| {
+            let name = ["Output", "Item", "Error", "Target"]
+                .into_iter()
+                .map(String::from)
+                .chain((1..).map(|n| format!("Type{}", n)))
+                .find(|name| !meta.associated_types.iter().any(|item| &item.name == name))
+                .unwrap_or_default();
+            meta.associated_types.push(AssociatedType { name, ..Default::default() });
+        }, window, cx);
+    }
 
-            code.push_str(")");
+    fn add_const(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.edit_items(|meta| {
+            let name = (1..)
+                .map(|n| if n == 1 { "ID".to_string() } else { format!("CONST{}", n) })
+                .find(|name| !meta.associated_consts.iter().any(|item| &item.name == name))
+                .unwrap_or_default();
+            meta.associated_consts.push(AssociatedConst { name, ty: "u32".to_string(), default: None });
+        }, window, cx);
+    }
 
-            // Return type
-            let return_type = Self::type_ref_to_string(&method.signature.return_type);
-            if return_type != "()" {
-                code.push_str(" -> ");
-                code.push_str(&return_type);
+    fn remove_type(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.edit_items(|meta| {
+            if index < meta.associated_types.len() {
+                meta.associated_types.remove(index);
             }
+        }, window, cx);
+    }
 
-            // Default implementation
-            if let Some(body) = &method.default_body {
-                code.push_str(" {\n");
-                for line in body.lines() {
-                    code.push_str(&format!("        {}\n", line));
-                }
-                code.push_str("    }\n");
-            } else {
-                code.push_str(";\n");
+    fn remove_const(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        self.edit_items(|meta| {
+            if index < meta.associated_consts.len() {
+                meta.associated_consts.remove(index);
             }
+        }, window, cx);
+    }
 
-            code.push('\n');
+    /// Brings the inputs back in line with the shared asset after it was changed
+    /// elsewhere (undo, reload)
+    pub fn sync_from_asset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let meta = TraitMeta::from_asset(&self.asset.read());
+        if self.read_rows(cx) == (meta.associated_types.clone(), meta.associated_consts.clone()) {
+            return;
+        }
+        if self.type_rows.len() != meta.associated_types.len() || self.const_rows.len() != meta.associated_consts.len() {
+            self.rebuild_rows(window, cx);
+            return;
         }
 
-        code.push_str("}\n");
-        code
-    }
-
-    fn type_ref_to_string(type_ref: &TypeRef) -> String {
-        match type_ref {
-            TypeRef::Primitive { name } => name.clone(),
-            TypeRef::Path { path } => path.clone(),
-            TypeRef::AliasRef { alias } => alias.clone(),
+        let (types, consts) = Self::item_texts(&meta);
+        let rows = self.type_rows.iter().zip(types).chain(self.const_rows.iter().zip(consts));
+        let mut values = Vec::new();
+        for (row, [name, bounds, default]) in rows {
+            values.push((row.name.clone(), name));
+            values.push((row.bounds.clone(), bounds));
+            values.push((row.default.clone(), default));
         }
+        for (input, value) in values {
+            if input.read(cx).text().to_string() != value {
+                input.update(cx, |input, cx| input.set_value(value, window, cx));
+            }
+        }
+        cx.notify();
     }
 
-    pub fn mark_needs_update(&self) {
-        *self.needs_update.lock() = true;
+    fn render_row(&self, is_type: bool, index: usize, row: &AssociatedItemRow, cx: &Context<Self>) -> impl IntoElement {
+        let name = row.name.read(cx).text().to_string();
+        let name_error = identifier_error(name.trim());
+        let badge = if is_type { "type" } else { "const" };
+
+        v_flex()
+            .gap_1()
+            .child(
+                h_flex()
+                    .gap_2()
+                    .p_2()
+                    .items_center()
+                    .rounded(px(4.0))
+                    .bg(cx.theme().secondary.opacity(0.2))
+                    .border_1()
+                    .border_color(if name_error.is_some() {
+                        cx.theme().danger
+                    } else {
+                        cx.theme().border.opacity(0.3)
+                    })
+                    .child(
+                        div()
+                            .text_xs()
+                            .px_2()
+                            .py_1()
+                            .rounded(px(3.0))
+                            .bg(cx.theme().accent.opacity(0.2))
+                            .text_color(cx.theme().accent)
+                            .child(badge)
+                    )
+                    .child(TextInput::new(&row.name).w(px(140.0)).with_size(ui::Size::Small))
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground).child(":"))
+                    .child(TextInput::new(&row.bounds).flex_1().with_size(ui::Size::Small))
+                    .child(div().text_sm().text_color(cx.theme().muted_foreground).child("="))
+                    .child(TextInput::new(&row.default).w(px(140.0)).with_size(ui::Size::Small))
+                    .child(
+                        Button::new(SharedString::from(format!("remove-associated-{}-{}", badge, index)))
+                            .ghost()
+                            .with_size(ui::Size::XSmall)
+                            .icon(IconName::Close)
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                if is_type {
+                                    this.remove_type(index, window, cx);
+                                } else {
+                                    this.remove_const(index, window, cx);
+                                }
+                            }))
+                    )
+            )
+            .when_some(name_error, |this, error| {
+                this.child(
+                    div()
+                        .px_2()
+                        .text_xs()
+                        .text_color(cx.theme().danger)
+                        .child(error)
+                )
+            })
+    }
+}
+
+impl EventEmitter<PanelEvent> for AssociatedItemsPanel {}
+impl EventEmitter<AssetChanged> for AssociatedItemsPanel {}
+
+impl Render for AssociatedItemsPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let type_rows: Vec<AnyElement> = self
+            .type_rows
+            .iter()
+            .enumerate()
+            .map(|(ix, row)| self.render_row(true, ix, row, cx).into_any_element())
+            .collect();
+        let const_rows: Vec<AnyElement> = self
+            .const_rows
+            .iter()
+            .enumerate()
+            .map(|(ix, row)| self.render_row(false, ix, row, cx).into_any_element())
+            .collect();
+        let section_label = |label: String, cx: &Context<Self>| {
+            div()
+                .text_xs()
+                .font_semibold()
+                .text_color(cx.theme().muted_foreground)
+                .child(label)
+        };
+
+        v_flex()
+            .size_full()
+            .gap_3()
+            .bg(cx.theme().sidebar)
+            .child(
+                h_flex()
+                    .w_full()
+                    .p_3()
+                    .items_center()
+                    .gap_2()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(cx.theme().foreground)
+                            .child("Associated Items")
+                    )
+                    .child(
+                        Button::new("add-associated-type")
+                            .label("Type")
+                            .icon(IconName::Plus)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.add_type(window, cx);
+                            }))
+                    )
+                    .child(
+                        Button::new("add-associated-const")
+                            .label("Const")
+                            .icon(IconName::Plus)
+                            .on_click(cx.listener(|this, _, window, cx| {
+                                this.add_const(window, cx);
+                            }))
+                    )
+            )
+            .child(
+                v_flex()
+                    .id("trait-associated-items-content")
+                    .px_3()
+                    .gap_2()
+                    .flex_1()
+                    .overflow_scroll()
+                    .child(section_label(format!("Types ({})", self.type_rows.len()), cx))
+                    .children(type_rows)
+                    .child(section_label(format!("Constants ({})", self.const_rows.len()), cx))
+                    .children(const_rows)
+                    .when(self.type_rows.is_empty() && self.const_rows.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .w_full()
+                                .p_8()
+                                .text_center()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("No associated types or constants yet")
+                        )
+                    })
+            )
+    }
+}
+
+impl Focusable for AssociatedItemsPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for AssociatedItemsPanel {
+    fn panel_name(&self) -> &'static str {
+        "Associated Items"
+    }
+
+    fn title(&self, _window: &Window, _cx: &App) -> gpui::AnyElement {
+        "Associated Items".into_any_element()
+    }
+
+    fn dump(&self, _cx: &App) -> ui::dock::PanelState {
+        ui::dock::PanelState {
+            panel_name: self.panel_name().to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Creates a Rust code editor input with the settings shared by every code field
+pub(crate) fn rust_code_input(window: &mut Window, cx: &mut Context<InputState>) -> InputState {
+    use ui::input::TabSize;
+    InputState::new(window, cx)
+        .code_editor("rust")
+        .line_number(true)
+        .tab_size(TabSize {
+            tab_size: 4,
+            hard_tabs: false,
+        })
+}
+
+/// Code Preview Panel - Display generated Rust code, optionally editable
+pub struct CodePreviewPanel {
+    asset: Arc<parking_lot::RwLock<TraitAsset>>,
+    history: Arc<parking_lot::Mutex<EditHistory>>,
+    code_input: Entity<InputState>,
+    focus_handle: FocusHandle,
+    needs_update: Arc<parking_lot::Mutex<bool>>,
+    /// Whether edits to the code are parsed back into the asset
+    editable: bool,
+    /// Code as last generated, to tell user edits from regeneration
+    last_generated: String,
+    /// The text holds edits not yet applied to the asset, so it must not be regenerated
+    user_edited: bool,
+    /// Set when this panel applied an edit, so the change it causes doesn't regenerate the code
+    applying_edit: bool,
+    // Bumped on every user edit; a pending apply only runs if no edit followed it
+    edit_generation: usize,
+    parse_error: Option<String>,
+    parse_warnings: Vec<String>,
+    /// What the panel generates; only the trait itself can be edited
+    mode: CodegenMode,
+    mode_menu_open: bool,
+    /// Implementing type of the impl skeleton
+    type_name_input: Entity<InputState>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl CodePreviewPanel {
+    pub fn new(
+        asset: Arc<parking_lot::RwLock<TraitAsset>>,
+        history: Arc<parking_lot::Mutex<EditHistory>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let code_input = cx.new(|cx| rust_code_input(window, cx).minimap(true));
+        let type_name_input = cx.new(|cx| InputState::new(window, cx).placeholder(codegen::DEFAULT_IMPL_TYPE));
+
+        let code_subscription = cx.subscribe_in(&code_input, window, |this, _state, event: &ui::input::InputEvent, window, cx| {
+            if !this.editable {
+                return;
+            }
+            match event {
+                ui::input::InputEvent::Change => {
+                    if this.code_input.read(cx).text().to_string() != this.last_generated {
+                        this.user_edited = true;
+                        this.schedule_apply(window, cx);
+                    }
+                }
+                ui::input::InputEvent::Blur => {
+                    if this.user_edited {
+                        this.apply_code(cx);
+                    }
+                    // Reformat what was typed once the user is done with it
+                    if !this.user_edited {
+                        this.mark_needs_update();
+                        cx.notify();
+                    }
+                }
+                _ => {}
+            }
+        });
+        let type_name_subscription = cx.subscribe_in(&type_name_input, window, |this, _state, event: &ui::input::InputEvent, _window, cx| {
+            if let ui::input::InputEvent::Change = event {
+                *this.needs_update.lock() = true;
+                cx.notify();
+            }
+        });
+
+        let mut panel = Self {
+            asset,
+            history,
+            code_input,
+            focus_handle: cx.focus_handle(),
+            needs_update: Arc::new(parking_lot::Mutex::new(true)),
+            editable: false,
+            last_generated: String::new(),
+            user_edited: false,
+            applying_edit: false,
+            edit_generation: 0,
+            parse_error: None,
+            parse_warnings: Vec::new(),
+            mode: CodegenMode::Trait,
+            mode_menu_open: false,
+            type_name_input,
+            _subscriptions: vec![code_subscription, type_name_subscription],
+        };
+
+        // Generate initial code
+        panel.update_code(window, cx);
+
+        panel
+    }
+
+    fn update_code(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let code = self.generate_rust_code(cx);
+        self.last_generated = code.clone();
+        self.code_input.update(cx, |input, cx| {
+            input.replace_text_in_range(None, &code, window, cx);
+        });
+        *self.needs_update.lock() = false;
+    }
+
+    fn generate_rust_code(&self, cx: &App) -> String {
+        let type_name = self.type_name_input.read(cx).text().to_string();
+        codegen::generate(&self.asset.read(), self.mode, &type_name, &CodegenOptions::default())
+    }
+
+    /// Regenerates the code from the shared asset after it was replaced as a whole (reload),
+    /// dropping unapplied edits to the code
+    pub fn sync_from_asset(&mut self, cx: &mut Context<Self>) {
+        self.edit_generation += 1;
+        self.user_edited = false;
+        self.applying_edit = false;
+        self.parse_error = None;
+        self.parse_warnings.clear();
+        *self.needs_update.lock() = true;
+        cx.notify();
+    }
+
+    /// Regenerates the code on the next render, unless the change came from this panel
+    pub fn mark_needs_update(&mut self) {
+        if std::mem::take(&mut self.applying_edit) {
+            return;
+        }
+        *self.needs_update.lock() = true;
+    }
+
+    fn set_editable(&mut self, editable: bool, cx: &mut Context<Self>) {
+        self.editable = editable;
+        if !editable {
+            // Unapplied text is dropped in favor of the asset
+            self.user_edited = false;
+            self.parse_error = None;
+            self.parse_warnings.clear();
+            *self.needs_update.lock() = true;
+        }
+        cx.notify();
+    }
+
+    fn set_mode(&mut self, mode: CodegenMode, cx: &mut Context<Self>) {
+        self.mode_menu_open = false;
+        if mode == self.mode {
+            cx.notify();
+            return;
+        }
+        if self.editable {
+            self.set_editable(false, cx);
+        }
+        self.mode = mode;
+        *self.needs_update.lock() = true;
+        cx.notify();
+    }
+
+    /// Applies the code once typing pauses
+    fn schedule_apply(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.edit_generation += 1;
+        let generation = self.edit_generation;
+        cx.spawn_in(window, async move |this, cx| {
+            cx.background_executor().timer(CODE_EDIT_DEBOUNCE).await;
+            this.update_in(cx, |this, _window, cx| {
+                if this.edit_generation == generation && this.user_edited {
+                    this.apply_code(cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+    }
+
+    /// Parses the code and replaces the asset with the result. Code that doesn't parse is
+    /// left as typed, with the error shown above it.
+    fn apply_code(&mut self, cx: &mut Context<Self>) {
+        let code = self.code_input.read(cx).text().to_string();
+        let parsed = match rust_import::find_traits(&code) {
+            Ok(mut traits) if traits.len() == 1 => traits.remove(0),
+            Ok(traits) if traits.is_empty() => {
+                self.parse_error = Some("No trait declaration found".to_string());
+                cx.notify();
+                return;
+            }
+            Ok(_) => {
+                self.parse_error = Some("Only one trait can be declared here".to_string());
+                cx.notify();
+                return;
+            }
+            Err(e) => {
+                self.parse_error = Some(e);
+                cx.notify();
+                return;
+            }
+        };
+
+        let mut new_asset = parsed.asset;
+        let command = {
+            let asset = self.asset.read();
+            rust_import::carry_over_editor_data(&asset, &mut new_asset);
+            TraitCommand::replace_asset(&asset, &new_asset)
+        };
+        let changed = self.history.lock().execute(command, &mut self.asset.write());
+
+        self.user_edited = false;
+        self.parse_error = None;
+        self.parse_warnings = parsed.warnings;
+        if changed {
+            self.applying_edit = true;
+            cx.emit(SyncRequested);
+            cx.emit(AssetChanged);
+        }
+        cx.notify();
     }
 }
 
 impl EventEmitter<PanelEvent> for CodePreviewPanel {}
+impl EventEmitter<AssetChanged> for CodePreviewPanel {}
+impl EventEmitter<SyncRequested> for CodePreviewPanel {}
 
 impl Render for CodePreviewPanel {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        // Update code if needed
-        if *self.needs_update.lock() {
+        // Update code if needed, keeping text the user is still working on
+        if *self.needs_update.lock() && !self.user_edited {
             self.update_code(window, cx);
         }
 
@@ -562,16 +1651,107 @@ impl Render for CodePreviewPanel {
                     .h(px(40.0))
                     .px_3()
                     .items_center()
+                    .justify_between()
                     .border_b_1()
                     .border_color(cx.theme().border)
                     .child(
-                        div()
-                            .text_sm()
-                            .font_semibold()
-                            .text_color(cx.theme().foreground)
-                            .child("Generated Code")
+                        h_flex()
+                            .gap_2()
+                            .items_center()
+                            .child(
+                                div()
+                                    .text_sm()
+                                    .font_semibold()
+                                    .text_color(cx.theme().foreground)
+                                    .child(if self.editable { "Code" } else { "Generated Code" })
+                            )
+                            .child(
+                                // Mode selector
+                                Button::new("codegen-mode")
+                                    .ghost()
+                                    .with_size(ui::Size::XSmall)
+                                    .label(self.mode.label())
+                                    .icon(IconName::ChevronDown)
+                                    .on_click(cx.listener(|this, _, _window, cx| {
+                                        this.mode_menu_open = !this.mode_menu_open;
+                                        cx.notify();
+                                    }))
+                            )
                     )
+                    .when(self.mode == CodegenMode::Trait, |this| {
+                        this.child(
+                            Checkbox::new("edit-code")
+                                .label("Editable")
+                                .checked(self.editable)
+                                .on_click(cx.listener(|this, checked: &bool, _window, cx| {
+                                    this.set_editable(*checked, cx);
+                                }))
+                        )
+                    })
+                    .when(self.mode.takes_type_name(), |this| {
+                        this.child(
+                            h_flex()
+                                .gap_1()
+                                .items_center()
+                                .child(
+                                    div()
+                                        .text_xs()
+                                        .text_color(cx.theme().muted_foreground)
+                                        .child("for")
+                                )
+                                .child(
+                                    div()
+                                        .w(px(140.0))
+                                        .child(TextInput::new(&self.type_name_input).with_size(ui::Size::XSmall))
+                                )
+                        )
+                    })
             )
+            .when(self.mode_menu_open, |this| {
+                let current = self.mode;
+                this.child(
+                    h_flex()
+                        .px_3()
+                        .py_1()
+                        .flex_wrap()
+                        .gap_1()
+                        .border_b_1()
+                        .border_color(cx.theme().border)
+                        .children(CodegenMode::ALL.into_iter().map(|mode| {
+                            Button::new(SharedString::from(format!("codegen-mode-{:?}", mode)))
+                                .with_size(ui::Size::XSmall)
+                                .label(mode.label())
+                                .when(mode == current, |button| button.primary())
+                                .when(mode != current, |button| button.ghost())
+                                .on_click(cx.listener(move |this, _, _window, cx| {
+                                    this.set_mode(mode, cx);
+                                }))
+                        }))
+                )
+            })
+            .when_some(self.parse_error.clone(), |this, error| {
+                this.child(
+                    div()
+                        .px_3()
+                        .py_1()
+                        .text_xs()
+                        .text_color(cx.theme().danger)
+                        .child(format!("Not applied: {}", error))
+                )
+            })
+            .when(self.parse_error.is_none() && !self.parse_warnings.is_empty(), |this| {
+                this.child(
+                    v_flex()
+                        .px_3()
+                        .py_1()
+                        .children(self.parse_warnings.iter().map(|warning| {
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().warning)
+                                .child(warning.clone())
+                        }))
+                )
+            })
             .child(
                 TextInput::new(&self.code_input)
                     .w_full()
@@ -602,3 +1782,195 @@ impl Panel for CodePreviewPanel {
         }
     }
 }
+
+/// Emitted by the diagnostics panel when a diagnostic is clicked
+#[derive(Clone, Debug)]
+pub struct NavigateToDiagnostic(pub DiagnosticTarget);
+
+/// Diagnostics Panel - List validation problems of the trait
+pub struct DiagnosticsPanel {
+    asset: Arc<parking_lot::RwLock<TraitAsset>>,
+    scope: TypeScope,
+    diagnostics: Vec<Diagnostic>,
+    focus_handle: FocusHandle,
+}
+
+impl DiagnosticsPanel {
+    pub fn new(
+        asset: Arc<parking_lot::RwLock<TraitAsset>>,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut panel = Self {
+            asset,
+            scope: TypeScope::new(&builtin_types()),
+            diagnostics: Vec::new(),
+            focus_handle: cx.focus_handle(),
+        };
+        panel.refresh(cx);
+        panel
+    }
+
+    /// Scans the project for types once, so signatures referring to them resolve
+    pub fn set_project_root(&mut self, root: Option<PathBuf>, cx: &mut Context<Self>) {
+        let mut candidates = builtin_types();
+        if let Some(root) = &root {
+            candidates.extend(project_types(root));
+        }
+        self.scope = TypeScope::new(&candidates);
+        self.refresh(cx);
+    }
+
+    /// Re-runs validation over the shared asset
+    pub fn refresh(&mut self, cx: &mut Context<Self>) {
+        self.diagnostics = validation::validate(&self.asset.read(), &self.scope);
+        cx.emit(PanelEvent::LayoutChanged);
+        cx.notify();
+    }
+
+    fn count(&self, severity: Severity) -> usize {
+        self.diagnostics.iter().filter(|diagnostic| diagnostic.severity == severity).count()
+    }
+
+    fn severity_color(severity: Severity, cx: &App) -> Hsla {
+        match severity {
+            Severity::Error => cx.theme().danger,
+            Severity::Warning => cx.theme().warning,
+            Severity::Info => cx.theme().muted_foreground,
+        }
+    }
+
+    fn target_label(&self, target: DiagnosticTarget) -> String {
+        let asset = self.asset.read();
+        match target {
+            DiagnosticTarget::Trait => "trait".to_string(),
+            DiagnosticTarget::AssociatedItems => "associated items".to_string(),
+            DiagnosticTarget::Method(index) | DiagnosticTarget::Param { method: index, .. } => asset
+                .methods
+                .get(index)
+                .map(|method| format!("fn {}", method.name))
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl EventEmitter<PanelEvent> for DiagnosticsPanel {}
+impl EventEmitter<NavigateToDiagnostic> for DiagnosticsPanel {}
+
+impl Render for DiagnosticsPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let hover_bg = cx.theme().secondary;
+        let rows = self.diagnostics.iter().enumerate().map(|(ix, diagnostic)| {
+            let target = diagnostic.target;
+            h_flex()
+                .id(("diagnostic", ix))
+                .w_full()
+                .px_3()
+                .py_1()
+                .gap_2()
+                .items_start()
+                .cursor_pointer()
+                .hover(|this| this.bg(hover_bg))
+                .on_click(cx.listener(move |_this, _, _window, cx| {
+                    cx.emit(NavigateToDiagnostic(target));
+                }))
+                .child(
+                    div()
+                        .w(px(56.0))
+                        .flex_shrink_0()
+                        .text_xs()
+                        .font_semibold()
+                        .text_color(Self::severity_color(diagnostic.severity, cx))
+                        .child(diagnostic.severity.label())
+                )
+                .child(
+                    v_flex()
+                        .flex_1()
+                        .child(
+                            div()
+                                .text_sm()
+                                .text_color(cx.theme().foreground)
+                                .child(diagnostic.message.clone())
+                        )
+                        .child(
+                            div()
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(self.target_label(target))
+                        )
+                )
+        }).collect::<Vec<_>>();
+
+        v_flex()
+            .size_full()
+            .bg(cx.theme().sidebar)
+            .child(
+                h_flex()
+                    .w_full()
+                    .h(px(40.0))
+                    .px_3()
+                    .gap_3()
+                    .items_center()
+                    .border_b_1()
+                    .border_color(cx.theme().border)
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_sm()
+                            .font_semibold()
+                            .text_color(cx.theme().foreground)
+                            .child("Diagnostics")
+                    )
+                    .children([Severity::Error, Severity::Warning, Severity::Info].map(|severity| {
+                        div()
+                            .text_xs()
+                            .text_color(Self::severity_color(severity, cx))
+                            .child(format!("{} {}", self.count(severity), severity.label()))
+                    }))
+            )
+            .child(
+                v_flex()
+                    .id("trait-diagnostics")
+                    .flex_1()
+                    .py_1()
+                    .overflow_y_scroll()
+                    .children(rows)
+                    .when(self.diagnostics.is_empty(), |this| {
+                        this.child(
+                            div()
+                                .p_8()
+                                .text_center()
+                                .text_sm()
+                                .text_color(cx.theme().muted_foreground)
+                                .child("No problems found")
+                        )
+                    })
+            )
+    }
+}
+
+impl Focusable for DiagnosticsPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Panel for DiagnosticsPanel {
+    fn panel_name(&self) -> &'static str {
+        "Diagnostics"
+    }
+
+    fn title(&self, _window: &Window, _cx: &App) -> gpui::AnyElement {
+        match self.count(Severity::Error) + self.count(Severity::Warning) {
+            0 => "Diagnostics".into_any_element(),
+            problems => format!("Diagnostics ({})", problems).into_any_element(),
+        }
+    }
+
+    fn dump(&self, _cx: &App) -> ui::dock::PanelState {
+        ui::dock::PanelState {
+            panel_name: self.panel_name().to_string(),
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,279 @@
+mod common;
+
+use common::trait_asset;
+use trait_editor_plugin::{generate_trait, CodegenMode, CodegenOptions};
+use ui_types_common::TraitAsset;
+
+fn generate(asset: &TraitAsset) -> String {
+    generate_trait(asset, &CodegenOptions::default())
+}
+
+#[test]
+fn multi_line_description_comments_every_line() {
+    let mut asset = trait_asset("pub trait Shape {}");
+    asset.description = Some("First line\nSecond line\n\nAfter a blank line".to_string());
+
+    assert_eq!(
+        generate(&asset),
+        "/// First line\n/// Second line\n///\n/// After a blank line\npub trait Shape {}\n"
+    );
+}
+
+#[test]
+fn carriage_returns_end_doc_lines() {
+    let mut asset = trait_asset("pub trait Shape {}");
+    // A lone `\r` is a bare CR, which rustc rejects inside doc comments
+    asset.description = Some("Windows\r\nline endings\rand old Mac ones".to_string());
+
+    let code = generate(&asset);
+    assert!(!code.contains('\r'));
+    assert!(code.starts_with("/// Windows\n/// line endings\n/// and old Mac ones\n"));
+}
+
+#[test]
+fn blank_lines_around_docs_are_dropped() {
+    let mut asset = trait_asset("pub trait Shape { fn area(&self) -> f64; }");
+    asset.description = Some("\n\n  \nShapes\n\n".to_string());
+    asset.methods[0].doc = Some("\nArea\n".to_string());
+
+    assert_eq!(generate(&asset), "/// Shapes\npub trait Shape {\n    /// Area\n    fn area(&self) -> f64;\n}\n");
+}
+
+#[test]
+fn doc_text_cannot_escape_the_comment() {
+    let mut asset = trait_asset("pub trait Shape {}");
+    asset.description = Some("*/ fn injected() {}\n/// nested\n#[doc(hidden)]".to_string());
+
+    let code = generate(&asset);
+    for line in code.lines().take(3) {
+        assert!(line.starts_with("/// "), "line not commented: {:?}", line);
+    }
+    assert!(code.contains("\npub trait Shape {}\n"));
+}
+
+#[test]
+fn control_and_bidi_characters_are_escaped() {
+    let mut asset = trait_asset("pub trait Shape {}");
+    asset.description = Some("admin\u{202E}txt.exe\u{7}\ttabbed".to_string());
+
+    let code = generate(&asset);
+    assert!(code.starts_with("/// admin\\u{202E}txt.exe\\u{0007}\ttabbed\n"));
+}
+
+#[test]
+fn default_bodies_are_reindented() {
+    let mut asset = trait_asset("pub trait Shape { fn area(&self) -> f64 { 0.0 } }");
+    asset.methods[0].default_body = Some(
+        "\n\t\t\tif true {\n\t\t\t\tlet x = 1.0;\n\n\t\t\t\tx\n\t\t\t} else {\n\t\t\t    0.0\n\t\t\t}   \n\n".to_string(),
+    );
+
+    assert_eq!(
+        generate(&asset),
+        "pub trait Shape {\n    fn area(&self) -> f64 {\n        if true {\n            let x = 1.0;\n\n            x\n        } else {\n            0.0\n        }\n    }\n}\n"
+    );
+}
+
+#[test]
+fn short_signatures_stay_on_one_line() {
+    let asset = trait_asset("pub trait Shape { fn scale(&mut self, factor: f64) -> f64; }");
+
+    assert_eq!(generate(&asset), "pub trait Shape {\n    fn scale(&mut self, factor: f64) -> f64;\n}\n");
+}
+
+#[test]
+fn long_signatures_get_one_parameter_per_line() {
+    let asset = trait_asset(
+        "pub trait Store {
+            fn insert(&mut self, key: std::collections::HashMap<String, u32>, value: Vec<u8>) -> Result<u32, String>;
+        }",
+    );
+
+    assert_eq!(
+        generate(&asset),
+        "pub trait Store {
+    fn insert(
+        &mut self,
+        key: std::collections::HashMap<String, u32>,
+        value: Vec<u8>,
+    ) -> Result<u32, String>;
+}
+"
+    );
+}
+
+#[test]
+fn wrapping_follows_the_configured_width() {
+    let asset = trait_asset("pub trait Shape { fn scale(&mut self, factor: f64) -> f64; }");
+    let narrow = CodegenOptions { max_width: 40 };
+
+    assert_eq!(
+        generate_trait(&asset, &narrow),
+        "pub trait Shape {\n    fn scale(\n        &mut self,\n        factor: f64,\n    ) -> f64;\n}\n"
+    );
+}
+
+#[test]
+fn exactly_max_width_is_not_wrapped() {
+    let asset = trait_asset("pub trait Shape { fn scale(&mut self, factor: f64) -> f64; }");
+    let line = "    fn scale(&mut self, factor: f64) -> f64;";
+    let exact = CodegenOptions { max_width: line.len() };
+
+    assert!(generate_trait(&asset, &exact).contains(&format!("\n{}\n", line)));
+}
+
+#[test]
+fn wrapped_signatures_keep_where_clauses_and_bodies() {
+    let asset = trait_asset(
+        "pub trait Store {
+            fn merge<T>(&self, first_argument: std::collections::HashMap<String, T>, second_argument: Vec<T>) -> usize
+            where
+                T: Clone,
+            {
+                second_argument.len()
+            }
+        }",
+    );
+
+    assert_eq!(
+        generate(&asset),
+        "pub trait Store {
+    fn merge<T>(
+        &self,
+        first_argument: std::collections::HashMap<String, T>,
+        second_argument: Vec<T>,
+    ) -> usize
+    where
+        T: Clone,
+    {
+        second_argument.len()
+    }
+}
+"
+    );
+}
+
+#[test]
+fn long_supertrait_lists_are_wrapped() {
+    let asset = trait_asset(
+        "pub trait Component: std::fmt::Debug + Clone + Send + Sync + 'static + serde::Serialize + Default + Eq {}",
+    );
+    assert_eq!(
+        generate(&asset),
+        "pub trait Component:\n    std::fmt::Debug + Clone + Send + Sync + 'static + serde::Serialize + Default + Eq\n{\n}\n"
+    );
+
+    let asset = trait_asset("pub trait Component: Clone + Send + Sync + Default {}");
+    let narrow = CodegenOptions { max_width: 30 };
+    assert_eq!(
+        generate_trait(&asset, &narrow),
+        "pub trait Component:\n    Clone\n    + Send\n    + Sync\n    + Default\n{\n}\n"
+    );
+}
+
+#[test]
+fn methods_are_separated_by_single_blank_lines() {
+    let asset = trait_asset(
+        "pub trait Shape {
+            type Unit;
+            fn area(&self) -> f64;
+            fn name(&self) -> String { String::new() }
+        }",
+    );
+
+    assert_eq!(
+        generate(&asset),
+        "pub trait Shape {
+    type Unit;
+
+    fn area(&self) -> f64;
+
+    fn name(&self) -> String {
+        String::new()
+    }
+}
+"
+    );
+}
+
+#[test]
+fn generated_code_round_trips() {
+    let source = "/// Stores values
+///
+/// Keyed by name.
+pub trait Store<T: Clone>: Send
+where
+    T: Default,
+{
+    type Key;
+    const LIMIT: usize = 16;
+
+    /// Inserts `value`
+    fn insert(
+        &mut self,
+        key: std::collections::HashMap<String, Vec<Option<u32>>>,
+        value: T,
+        replace_existing: bool,
+    ) -> Option<T>;
+
+    fn len(&self) -> usize {
+        match self.limit() {
+            0 => 0,
+            n => n - 1,
+        }
+    }
+}
+";
+    let generated = generate(&trait_asset(source));
+    assert_eq!(generated, source);
+    assert_eq!(generate(&trait_asset(&generated)), generated);
+}
+
+#[test]
+fn empty_default_bodies_are_written_as_braces() {
+    let mut asset = trait_asset("pub trait Hooks { fn on_load(&self) {} fn on_drop<T>(&self) where T: Clone {} }");
+    asset.methods[0].default_body = Some("\n  \n".to_string());
+    assert_eq!(
+        generate(&asset),
+        "pub trait Hooks {\n    fn on_load(&self) {}\n\n    fn on_drop<T>(&self)\n    where\n        T: Clone,\n    {\n    }\n}\n"
+    );
+}
+
+fn generate_mode(asset: &TraitAsset, mode: CodegenMode) -> String {
+    trait_editor_plugin::generate(asset, mode, "", &CodegenOptions::default())
+}
+
+#[test]
+fn sized_only_methods_are_not_forwarded() {
+    let asset = trait_asset("pub trait Shape { fn area(&self) -> f64; fn scaled(&self, factor: f64) -> f64 where Self: Sized; }");
+    let code = generate_mode(&asset, CodegenMode::Forwarders);
+    assert!(!code.contains("T::scaled"), "{}", code);
+    assert_eq!(code.matches("unimplemented!(\"`scaled` can't be forwarded to `T`\")").count(), 2, "{}", code);
+    assert!(code.contains("impl<T: Shape + ?Sized> Shape for &T"), "{}", code);
+}
+
+#[test]
+fn mocks_clone_only_clone_returns() {
+    let asset = trait_asset("pub trait Store { fn count(&self) -> Vec<u32>; fn open(&self) -> std::fs::File; }");
+    let code = generate_mode(&asset, CodegenMode::Mock);
+    assert!(code.contains("pub count_returns: Option<Vec<u32>>,"), "{}", code);
+    assert!(code.contains("self.count_returns.clone().expect("), "{}", code);
+    assert!(code.contains("pub open_returns: std::sync::Mutex<Option<std::fs::File>>,"), "{}", code);
+    assert!(code.contains("self.open_returns.lock().unwrap().take().expect("), "{}", code);
+}
+
+#[test]
+fn thread_safe_mocks_do_not_store_trait_objects() {
+    let asset = trait_asset("pub trait Loader: Send { fn load(&self) -> Result<(), Box<dyn std::error::Error>>; }");
+    let code = generate_mode(&asset, CodegenMode::Mock);
+    assert!(!code.contains("load_returns"), "{}", code);
+    assert!(code.contains("todo!()"), "{}", code);
+}
+
+#[test]
+fn mock_supertraits_are_implemented_or_named() {
+    let asset = trait_asset("pub trait Store: std::fmt::Debug + Clone + Default + Send { fn len(&self) -> usize; }");
+    let code = generate_mode(&asset, CodegenMode::Mock);
+    assert!(code.contains("impl std::fmt::Debug for MockStore {"), "{}", code);
+    assert!(code.contains("// `MockStore` needs an impl of supertrait `Clone`"), "{}", code);
+    assert!(!code.contains("supertrait `Default`") && !code.contains("supertrait `Send`"), "{}", code);
+}
@@ -0,0 +1,8 @@
+use ui_types_common::TraitAsset;
+
+/// The asset of the only trait declared in `source`
+pub fn trait_asset(source: &str) -> TraitAsset {
+    let mut traits = trait_editor_plugin::find_traits(source).expect("source should parse");
+    assert_eq!(traits.len(), 1, "expected exactly one trait");
+    traits.remove(0).asset
+}
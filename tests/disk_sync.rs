@@ -0,0 +1,34 @@
+mod common;
+
+use common::trait_asset;
+use trait_editor_plugin::{asset_snapshot, merge};
+
+#[test]
+fn edits_on_both_sides_are_kept() {
+    let base = trait_asset("pub trait Shape { fn area(&self) -> f64; fn name(&self) -> String; }");
+    let mut mine = base.clone();
+    mine.methods[0].doc = Some("Area in square units".to_string());
+    let mut theirs = base.clone();
+    theirs.description = Some("Anything with an area".to_string());
+    theirs.methods.push(trait_asset("trait T { fn scale(&mut self, factor: f64); }").methods.remove(0));
+
+    let merge = merge(&asset_snapshot(&base), &mine, &theirs).unwrap();
+    assert!(merge.conflicts.is_empty(), "unexpected conflicts: {:?}", merge.conflicts);
+    assert_eq!(merge.asset.description.as_deref(), Some("Anything with an area"));
+    let names: Vec<&str> = merge.asset.methods.iter().map(|method| method.name.as_str()).collect();
+    assert_eq!(names, ["area", "name", "scale"]);
+    assert_eq!(merge.asset.methods[0].doc.as_deref(), Some("Area in square units"));
+}
+
+#[test]
+fn conflicting_edits_are_reported_and_keep_the_local_version() {
+    let base = trait_asset("pub trait Shape { fn area(&self) -> f64; }");
+    let mut mine = base.clone();
+    mine.methods[0].doc = Some("Mine".to_string());
+    let mut theirs = base.clone();
+    theirs.methods[0].doc = Some("Theirs".to_string());
+
+    let merge = merge(&asset_snapshot(&base), &mine, &theirs).unwrap();
+    assert_eq!(merge.conflicts, ["methods.area.doc"]);
+    assert_eq!(merge.asset.methods[0].doc.as_deref(), Some("Mine"));
+}
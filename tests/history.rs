@@ -0,0 +1,66 @@
+mod common;
+
+use common::trait_asset;
+use serde_json::json;
+use trait_editor_plugin::{asset_snapshot, EditHistory, MethodMeta, TraitCommand};
+use ui_types_common::{MethodSignature, TraitAsset, TraitMethod, TypeKind, TypeRef};
+
+fn json(asset: &TraitAsset) -> serde_json::Value {
+    serde_json::to_value(asset).unwrap()
+}
+
+#[test]
+fn undoing_back_to_a_loaded_asset_matches_its_snapshot() {
+    // As saved by an editor that never wrote any meta
+    let mut current: TraitAsset = serde_json::from_value(json!({
+        "schema_version": 1,
+        "type_kind": "Trait",
+        "name": "Shape",
+        "display_name": "Shape",
+        "description": null,
+        "methods": [],
+        "meta": {}
+    }))
+    .unwrap();
+    let loaded = asset_snapshot(&current);
+
+    let method = TraitMethod {
+        name: "area".to_string(),
+        signature: MethodSignature { params: Vec::new(), return_type: TypeRef::Primitive { name: "f32".to_string() } },
+        default_body: None,
+        doc: None,
+    };
+    let mut history = EditHistory::new();
+    history.execute(TraitCommand::InsertMethod { index: 0, method, meta: MethodMeta::default() }, &mut current);
+    assert_ne!(asset_snapshot(&current), loaded);
+
+    assert!(history.undo(&mut current));
+    assert_eq!(asset_snapshot(&current), loaded);
+}
+
+#[test]
+fn replacing_an_asset_copies_every_field() {
+    let mut current = trait_asset("pub trait Old { fn a(&self); }");
+    let mut new = trait_asset("/// Docs\npub trait New: Send { fn b(&self, x: u32) -> u32; }");
+    current.schema_version = 0;
+    new.type_kind = TypeKind::Struct;
+
+    let mut history = EditHistory::new();
+    let command = TraitCommand::replace_asset(&current, &new);
+    assert!(history.execute(command, &mut current));
+    assert_eq!(json(&current), json(&new));
+}
+
+#[test]
+fn undoing_a_replace_restores_every_field() {
+    let original = trait_asset("pub trait Old { fn a(&self); }");
+    let mut new = trait_asset("pub trait New { fn b(&self); }");
+    new.schema_version = original.schema_version + 1;
+    new.type_kind = TypeKind::Struct;
+
+    let mut current = original.clone();
+    let mut history = EditHistory::new();
+    history.execute(TraitCommand::replace_asset(&current, &new), &mut current);
+    assert!(history.undo(&mut current));
+    assert_eq!(json(&current), json(&original));
+}
@@ -0,0 +1,148 @@
+use trait_editor_plugin::{violations, GenericParam, Generics, MethodMeta, QuickFix, Receiver, TraitMeta, ViolationKind};
+use ui_types_common::{MethodParam, MethodSignature, TraitAsset, TraitMethod, TypeKind, TypeRef};
+
+fn path(path: &str) -> TypeRef {
+    TypeRef::Path { path: path.to_string() }
+}
+
+fn unit() -> TypeRef {
+    TypeRef::Primitive { name: "()".to_string() }
+}
+
+fn method(name: &str, params: &[(&str, TypeRef)], return_type: TypeRef) -> TraitMethod {
+    TraitMethod {
+        name: name.to_string(),
+        signature: MethodSignature {
+            params: params.iter().map(|(name, type_ref)| MethodParam { name: name.to_string(), type_ref: type_ref.clone() }).collect(),
+            return_type,
+        },
+        default_body: None,
+        doc: None,
+    }
+}
+
+/// Trait `Shape` with `supertraits` and `methods`, each with its editor data
+fn shape(supertraits: &[&str], methods: Vec<(TraitMethod, MethodMeta)>) -> TraitAsset {
+    let (methods, method_metas): (Vec<_>, Vec<_>) = methods.into_iter().unzip();
+    let mut asset = TraitAsset {
+        schema_version: 1,
+        type_kind: TypeKind::Trait,
+        name: "Shape".to_string(),
+        display_name: "Shape".to_string(),
+        description: None,
+        methods,
+        meta: serde_json::Value::Null,
+    };
+    let meta = TraitMeta {
+        supertraits: supertraits.iter().map(|bound| bound.to_string()).collect(),
+        methods: method_metas,
+        ..TraitMeta::default()
+    };
+    meta.write_to(&mut asset);
+    asset
+}
+
+fn kinds(asset: &TraitAsset) -> Vec<(ViolationKind, Option<usize>)> {
+    violations(asset).into_iter().map(|violation| (violation.kind, violation.method)).collect()
+}
+
+fn apply(fix: QuickFix, asset: &mut TraitAsset) {
+    let mut meta = TraitMeta::from_asset(asset);
+    fix.apply(&mut meta);
+    meta.write_to(asset);
+}
+
+#[test]
+fn dispatchable_traits_have_no_violations() {
+    let asset = shape(&["Send"], vec![(method("area", &[("scale", path("f32"))], path("Self::Output")), MethodMeta::default())]);
+    assert!(kinds(&asset).is_empty());
+}
+
+#[test]
+fn generic_methods_are_reported() {
+    let generic = MethodMeta {
+        generics: Generics {
+            params: vec![GenericParam::Type { name: "T".to_string(), bounds: Vec::new(), default: None }],
+            where_clause: Vec::new(),
+        },
+        ..MethodMeta::default()
+    };
+    let lifetime_only = MethodMeta {
+        generics: Generics {
+            params: vec![GenericParam::Lifetime { name: "'a".to_string(), bounds: Vec::new() }],
+            where_clause: Vec::new(),
+        },
+        ..MethodMeta::default()
+    };
+    let asset = shape(&[], vec![
+        (method("insert", &[("value", path("T"))], unit()), generic),
+        (method("show", &[("value", path("impl std::fmt::Display"))], unit()), MethodMeta::default()),
+        (method("borrow", &[("value", path("&'a str"))], unit()), lifetime_only),
+    ]);
+    assert_eq!(kinds(&asset), [(ViolationKind::GenericMethod, Some(0)), (ViolationKind::GenericMethod, Some(1))]);
+}
+
+#[test]
+fn self_in_arguments_and_returns_is_reported() {
+    let asset = shape(&[], vec![
+        (method("merge", &[("other", path("&Self"))], unit()), MethodMeta::default()),
+        (method("duplicate", &[], path("Option<Self>")), MethodMeta::default()),
+    ]);
+    let violations = violations(&asset);
+    assert_eq!(
+        violations.iter().map(|violation| (violation.kind, violation.method)).collect::<Vec<_>>(),
+        [(ViolationKind::SelfInSignature, Some(0)), (ViolationKind::SelfInSignature, Some(1))]
+    );
+    assert_eq!(violations[0].message, "`merge`: parameter `other` uses `Self`");
+    assert_eq!(violations[1].message, "`duplicate`: returns `Self`");
+}
+
+#[test]
+fn associated_functions_are_reported() {
+    let asset = shape(&[], vec![(method("new", &[], path("Self")), MethodMeta { receiver: Receiver::None, ..MethodMeta::default() })]);
+    assert_eq!(kinds(&asset), [(ViolationKind::NoReceiver, Some(0)), (ViolationKind::SelfInSignature, Some(0))]);
+}
+
+#[test]
+fn requiring_sized_clears_method_violations() {
+    let mut asset = shape(&[], vec![
+        (method("area", &[], path("f32")), MethodMeta::default()),
+        (method("new", &[], path("Self")), MethodMeta { receiver: Receiver::None, ..MethodMeta::default() }),
+    ]);
+    let fix = violations(&asset)[0].quick_fix();
+    assert_eq!(fix, Some(QuickFix::RequireSized(1)));
+
+    apply(QuickFix::RequireSized(1), &mut asset);
+    assert!(kinds(&asset).is_empty());
+    // Applying it again doesn't add a second `Self: Sized`
+    apply(QuickFix::RequireSized(1), &mut asset);
+    assert_eq!(TraitMeta::from_asset(&asset).method(1).generics.where_clause.len(), 1);
+}
+
+#[test]
+fn sized_supertraits_are_reported_and_removed() {
+    let mut asset = shape(&["std::marker::Sized", "Send"], Vec::new());
+    let violations = violations(&asset);
+    assert_eq!(violations.len(), 1);
+    assert_eq!((violations[0].kind, violations[0].method), (ViolationKind::SizedSupertrait, None));
+    assert_eq!(violations[0].quick_fix(), Some(QuickFix::RemoveSizedBound));
+
+    apply(QuickFix::RemoveSizedBound, &mut asset);
+    assert!(kinds(&asset).is_empty());
+    assert_eq!(TraitMeta::from_asset(&asset).supertraits, ["Send"]);
+}
+
+#[test]
+fn incompatible_supertraits_are_reported_and_removed() {
+    let mut asset = shape(&["Clone", "std::cmp::PartialEq<u32>", "Send"], Vec::new());
+    let violations = violations(&asset);
+    let supertraits: Vec<Option<&str>> = violations.iter().map(|violation| violation.supertrait.as_deref()).collect();
+    assert_eq!(supertraits, [Some("Clone"), Some("std::cmp::PartialEq<u32>")]);
+    assert!(violations.iter().all(|violation| violation.kind == ViolationKind::IncompatibleSupertrait));
+
+    for violation in &violations {
+        apply(violation.quick_fix().expect("supertraits can be removed"), &mut asset);
+    }
+    assert!(kinds(&asset).is_empty());
+    assert_eq!(TraitMeta::from_asset(&asset).supertraits, ["Send"]);
+}
@@ -0,0 +1,93 @@
+mod common;
+
+use std::path::{Path, PathBuf};
+use common::trait_asset;
+use trait_editor_plugin::{output_file, write_output, OutputStatus, TraitMeta};
+use ui_types_common::TraitAsset;
+
+/// `trait.json` of a trait asset in an empty project of its own
+fn asset_file(test: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("trait_editor_output_{}_{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("assets/shape.trait")).unwrap();
+    std::fs::write(root.join("Cargo.toml"), "[package]\n").unwrap();
+    root.join("assets/shape.trait/trait.json")
+}
+
+fn project_root(asset_file: &Path) -> &Path {
+    asset_file.ancestors().nth(3).unwrap()
+}
+
+fn shape(module_path: Option<&str>, output_path: Option<&str>) -> TraitAsset {
+    let mut asset = trait_asset("pub trait Shape { fn area(&self) -> f64; }");
+    let mut meta = TraitMeta::from_asset(&asset);
+    meta.module_path = module_path.map(str::to_string);
+    meta.output_path = output_path.map(str::to_string);
+    meta.write_to(&mut asset);
+    asset
+}
+
+#[test]
+fn module_paths_map_to_files_under_src() {
+    let file = asset_file("module_paths");
+    let root = project_root(&file);
+    assert_eq!(output_file(&shape(Some("crate::traits::shape"), None), &file), Some(root.join("src/traits/shape.rs")));
+    assert_eq!(output_file(&shape(Some("crate::traits::shape"), Some("gen/shape.rs")), &file), Some(root.join("gen/shape.rs")));
+    for module in ["crate", "traits::shape", "crate::traits::::shape", ""] {
+        assert_eq!(output_file(&shape(Some(module), None), &file), None, "{}", module);
+    }
+    assert_eq!(output_file(&shape(None, None), &file), None);
+}
+
+#[test]
+fn unchanged_code_is_not_rewritten() {
+    let file = asset_file("unchanged");
+    let asset = shape(Some("crate::shape"), None);
+    let path = project_root(&file).join("src/shape.rs");
+    assert_eq!(write_output(&asset, &file, false), Ok(OutputStatus::Written(path.clone())));
+    assert!(std::fs::read_to_string(&path).unwrap().contains("pub trait Shape {"));
+    assert_eq!(write_output(&asset, &file, false), Ok(OutputStatus::Unchanged(path)));
+}
+
+#[test]
+fn crlf_checkouts_still_count_as_generated() {
+    let file = asset_file("crlf");
+    let asset = shape(Some("crate::shape"), None);
+    let path = project_root(&file).join("src/shape.rs");
+    write_output(&asset, &file, false).unwrap();
+    let crlf = std::fs::read_to_string(&path).unwrap().replace('\n', "\r\n");
+    std::fs::write(&path, crlf).unwrap();
+    assert_eq!(write_output(&asset, &file, false), Ok(OutputStatus::Unchanged(path.clone())));
+
+    // Regenerating checks the CRLF file's hash
+    let changed = TraitAsset { description: Some("Anything with an area".to_string()), ..asset };
+    assert_eq!(write_output(&changed, &file, false), Ok(OutputStatus::Written(path)));
+}
+
+#[test]
+fn hand_edited_files_are_only_overwritten_by_force() {
+    let file = asset_file("hand_edited");
+    let asset = shape(Some("crate::shape"), None);
+    let path = project_root(&file).join("src/shape.rs");
+    write_output(&asset, &file, false).unwrap();
+    let edited = std::fs::read_to_string(&path).unwrap().replace("fn area", "fn perimeter");
+    std::fs::write(&path, &edited).unwrap();
+
+    let changed = TraitAsset { description: Some("Anything with an area".to_string()), ..asset };
+    assert_eq!(write_output(&changed, &file, false), Ok(OutputStatus::ManuallyEdited(path.clone())));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), edited);
+    assert_eq!(write_output(&changed, &file, true), Ok(OutputStatus::Written(path)));
+}
+
+#[test]
+fn foreign_files_are_only_overwritten_by_force() {
+    let file = asset_file("foreign");
+    let asset = shape(Some("crate::shape"), None);
+    let path = project_root(&file).join("src/shape.rs");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, "pub trait Shape {}\n").unwrap();
+
+    assert_eq!(write_output(&asset, &file, false), Ok(OutputStatus::NotGenerated(path.clone())));
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "pub trait Shape {}\n");
+    assert_eq!(write_output(&asset, &file, true), Ok(OutputStatus::Written(path)));
+}
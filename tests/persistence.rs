@@ -0,0 +1,88 @@
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime};
+use trait_editor_plugin::{backups, pending_recovery, recovery_path, save, write_atomic, write_recovery, MAX_BACKUPS};
+
+/// `trait.json` in an empty folder of its own
+fn trait_file(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("trait_editor_{}_{}", test, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir.join("trait.json")
+}
+
+fn set_modified(path: &PathBuf, time: SystemTime) {
+    std::fs::File::options().write(true).open(path).unwrap().set_modified(time).unwrap();
+}
+
+#[test]
+fn failed_writes_leave_the_original_intact() {
+    let path = trait_file("failed_write");
+    std::fs::write(&path, "original").unwrap();
+    // A folder where the temporary file goes can't be created as a file
+    std::fs::create_dir(path.with_file_name(".trait.json.tmp")).unwrap();
+
+    assert!(write_atomic(&path, "replacement").is_err());
+    assert!(save(&path, "replacement").is_err());
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+}
+
+#[test]
+fn saves_replace_the_file_and_clear_recovery() {
+    let path = trait_file("save");
+    std::fs::write(&path, "first").unwrap();
+    write_recovery(&path, "unsaved").unwrap();
+
+    save(&path, "second").unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "second");
+    assert!(!recovery_path(&path).exists());
+    assert!(!path.with_file_name(".trait.json.tmp").exists());
+}
+
+#[test]
+fn backups_are_rotated_at_the_limit() {
+    let path = trait_file("backups");
+    let saves = MAX_BACKUPS + 3;
+    // Quickly enough for several saves to share a millisecond
+    for version in 0..=saves {
+        save(&path, &version.to_string()).unwrap();
+    }
+
+    let backups = backups(&path);
+    assert_eq!(backups.len(), MAX_BACKUPS);
+    let contents: Vec<String> = backups.iter().map(|backup| std::fs::read_to_string(backup).unwrap()).collect();
+    let expected: Vec<String> = (saves - MAX_BACKUPS..saves).map(|version| version.to_string()).collect();
+    assert_eq!(contents, expected);
+}
+
+#[test]
+fn unchanged_saves_are_not_backed_up() {
+    let path = trait_file("unchanged");
+    save(&path, "same").unwrap();
+    save(&path, "same").unwrap();
+    assert!(backups(&path).is_empty());
+}
+
+#[test]
+fn autosaves_newer_than_the_file_are_recovered() {
+    let path = trait_file("recovery");
+    std::fs::write(&path, "saved").unwrap();
+    write_recovery(&path, "unsaved").unwrap();
+    let now = SystemTime::now();
+    set_modified(&path, now - Duration::from_secs(60));
+    set_modified(&recovery_path(&path), now);
+
+    let (content, _) = pending_recovery(&path).expect("recovery should be offered");
+    assert_eq!(content, "unsaved");
+}
+
+#[test]
+fn autosaves_older_than_the_file_are_ignored() {
+    let path = trait_file("stale_recovery");
+    std::fs::write(&path, "saved").unwrap();
+    write_recovery(&path, "unsaved").unwrap();
+    let now = SystemTime::now();
+    set_modified(&recovery_path(&path), now - Duration::from_secs(60));
+    set_modified(&path, now);
+
+    assert!(pending_recovery(&path).is_none());
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use common::trait_asset;
+use trait_editor_plugin::{find_traits, ImportedTrait, Receiver, TraitMeta, Visibility};
+
+fn import(source: &str) -> ImportedTrait {
+    let mut traits = find_traits(source).expect("source should parse");
+    assert_eq!(traits.len(), 1, "expected exactly one trait");
+    traits.remove(0)
+}
+
+#[test]
+fn traits_in_nested_modules_are_found() {
+    let source = "mod outer {\n    pub mod inner {\n        pub trait Deep { fn a(&self); }\n    }\n    trait Shallow {}\n}\ntrait Top {}\n";
+    let names: Vec<String> = find_traits(source).unwrap().into_iter().map(|imported| imported.name).collect();
+    assert_eq!(names, ["Deep", "Shallow", "Top"]);
+}
+
+#[test]
+fn brackets_in_strings_and_chars_are_not_delimiters() {
+    let source = r####"
+        trait Text<'a> {
+            fn open(&self) -> char { '{' }
+            fn close(&'a self) -> &'a str { r#"}" ")"#; "\"}" }
+        }
+    "####;
+    let asset = trait_asset(source);
+    assert_eq!(asset.methods.len(), 2);
+    assert_eq!(asset.methods[0].default_body.as_deref().map(str::trim), Some("'{'"));
+    assert_eq!(asset.methods[1].default_body.as_deref().map(str::trim), Some(r####"r#"}" ")"#; "\"}""####));
+}
+
+#[test]
+fn lifetimes_are_not_char_literals() {
+    let asset = trait_asset("trait Borrow<'a, 'b: 'a> { fn get(&self, key: &'a str) -> &'b str; }");
+    assert_eq!(asset.methods.len(), 1);
+    assert_eq!(TraitMeta::from_asset(&asset).generics.params.len(), 2);
+}
+
+#[test]
+fn extern_abis_are_kept() {
+    let asset = trait_asset("trait Ffi { extern \"C\" fn call(&self); unsafe extern fn raw(&self); }");
+    let meta = TraitMeta::from_asset(&asset);
+    assert_eq!(meta.method(0).abi.as_deref(), Some("C"));
+    assert_eq!(meta.method(1).abi.as_deref(), Some(""));
+    assert!(meta.method(1).is_unsafe);
+}
+
+#[test]
+fn restricted_visibility_becomes_crate_with_a_warning() {
+    let imported = import("pub(in crate::systems) trait Scoped {}");
+    assert_eq!(TraitMeta::from_asset(&imported.asset).visibility, Visibility::Crate);
+    assert!(imported.warnings.iter().any(|warning| warning.contains("pub(in crate::systems)")), "{:?}", imported.warnings);
+}
+
+#[test]
+fn unmodeled_associated_items_are_kept_verbatim() {
+    let source = "trait Container {\n    type Item<'a> where Self: 'a;\n    /// What it holds\n    type Plain;\n    type Bare;\n}\n";
+    let imported = import(source);
+    let meta = TraitMeta::from_asset(&imported.asset);
+    assert_eq!(meta.verbatim_items, ["    type Item<'a> where Self: 'a;", "    /// What it holds\n    type Plain;"]);
+    let modeled: Vec<&str> = meta.associated_types.iter().map(|item| item.name.as_str()).collect();
+    assert_eq!(modeled, ["Bare"]);
+    assert_eq!(imported.warnings.len(), 2, "{:?}", imported.warnings);
+}
+
+#[test]
+fn const_on_methods_is_dropped_with_a_warning() {
+    let imported = import("trait Loader { const async unsafe fn load(&self); }");
+    let meta = TraitMeta::from_asset(&imported.asset).method(0);
+    assert!(meta.is_async && meta.is_unsafe);
+    assert!(imported.warnings.iter().any(|warning| warning.contains("`const`")), "{:?}", imported.warnings);
+}
+
+#[test]
+fn smart_pointer_receivers_are_recognized() {
+    let asset = trait_asset("trait Node { fn boxed(self: Box<Self>); fn shared(self: std::rc::Rc<Self>); fn plain(); }");
+    let meta = TraitMeta::from_asset(&asset);
+    assert_eq!(meta.method(0).receiver, Receiver::Box);
+    assert_eq!(meta.method(1).receiver, Receiver::Rc);
+    assert_eq!(meta.method(2).receiver, Receiver::None);
+}
+
+#[test]
+fn pattern_parameters_fall_back_to_numbered_names() {
+    let imported = import("trait Points { fn add(&self, (x, y): (f32, f32), scale: f32); }");
+    let names: Vec<&str> = imported.asset.methods[0].signature.params.iter().map(|param| param.name.as_str()).collect();
+    assert_eq!(names, ["param1", "scale"]);
+    assert!(imported.warnings.iter().any(|warning| warning.contains("imported as `param1`")), "{:?}", imported.warnings);
+}
+
+#[test]
+fn unbalanced_delimiters_report_their_line() {
+    let mismatched = find_traits("trait Broken {\n    fn a(&self;\n}\n").err().unwrap();
+    assert!(mismatched.starts_with("Line 3:"), "{}", mismatched);
+    let unclosed = find_traits("trait Open {\n    fn a(&self);\n").err().unwrap();
+    assert_eq!(unclosed, "Line 1: `{` is never closed");
+    let unexpected = find_traits("trait Extra {}\n}\n").err().unwrap();
+    assert_eq!(unexpected, "Line 2: unexpected `}`");
+}
@@ -0,0 +1,162 @@
+use serde_json::{json, Value};
+use trait_editor_plugin::{asset_to_json, find_traits, load_asset, syntax_error_location, LoadError, CURRENT_SCHEMA_VERSION, PRESERVED_FIELDS_KEY};
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/schema/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("failed to read {}: {}", path, e))
+}
+
+fn saved(json: &str) -> Value {
+    let asset = load_asset(json).expect("fixture should load");
+    serde_json::from_str(&asset_to_json(&asset).expect("asset should serialize")).unwrap()
+}
+
+#[test]
+fn v0_default_content_is_migrated() {
+    let asset = load_asset(&fixture("v0_default_content.json")).unwrap();
+
+    assert_eq!(asset.schema_version, CURRENT_SCHEMA_VERSION);
+    assert_eq!(asset.name, "NewTrait");
+    assert_eq!(asset.display_name, "NewTrait");
+    assert!(asset.methods.is_empty());
+    assert_eq!(asset.meta, json!({}));
+}
+
+#[test]
+fn v0_migration_keeps_fields_that_are_present() {
+    let asset = load_asset(&fixture("v0_partial.json")).unwrap();
+
+    assert_eq!(asset.schema_version, CURRENT_SCHEMA_VERSION);
+    assert_eq!(asset.display_name, "Shape Trait");
+    assert_eq!(asset.description.as_deref(), Some("Anything with an area"));
+    // A null meta is replaced with an empty object
+    assert_eq!(asset.meta, json!({}));
+}
+
+#[test]
+fn v0_without_a_name_is_invalid() {
+    let error = load_asset(r#"{"methods": []}"#).unwrap_err();
+    assert!(matches!(error, LoadError::Invalid(_)), "{:?}", error);
+}
+
+#[test]
+fn migrated_files_are_saved_at_the_current_version() {
+    let saved = saved(&fixture("v0_default_content.json"));
+    assert_eq!(saved["schema_version"], json!(CURRENT_SCHEMA_VERSION));
+    assert_eq!(saved["display_name"], json!("NewTrait"));
+}
+
+#[test]
+fn v1_loads_unchanged() {
+    let original: Value = serde_json::from_str(&fixture("v1_minimal.json")).unwrap();
+    let saved = saved(&fixture("v1_minimal.json"));
+    for field in ["schema_version", "type_kind", "name", "display_name", "methods", "meta"] {
+        assert_eq!(saved[field], original[field], "{} changed", field);
+    }
+}
+
+#[test]
+fn newer_versions_are_refused() {
+    let error = load_asset(&fixture("v2_future.json")).unwrap_err();
+
+    assert_eq!(error, LoadError::NewerVersion { found: 2 });
+    let message = error.to_string();
+    assert!(message.contains("schema version 2"), "{}", message);
+    assert!(message.contains(&format!("up to version {}", CURRENT_SCHEMA_VERSION)), "{}", message);
+}
+
+#[test]
+fn malformed_versions_are_invalid() {
+    let error = load_asset(&fixture("invalid_version.json")).unwrap_err();
+    assert!(matches!(error, LoadError::Invalid(_)), "{:?}", error);
+
+    let error = load_asset(r#"{"schema_version": -1, "name": "Shape"}"#).unwrap_err();
+    assert!(matches!(error, LoadError::Invalid(_)), "{:?}", error);
+}
+
+#[test]
+fn malformed_editor_meta_is_refused() {
+    let error = load_asset(&fixture("v1_malformed_meta.json")).unwrap_err();
+    assert!(matches!(error, LoadError::Invalid(_)), "{:?}", error);
+    assert!(error.to_string().contains("meta"), "{}", error);
+
+    // The same file with well-formed meta loads
+    let mut document: Value = serde_json::from_str(&fixture("v1_malformed_meta.json")).unwrap();
+    document["meta"]["generics"] = json!({});
+    assert!(load_asset(&document.to_string()).is_ok());
+}
+
+#[test]
+fn non_objects_are_syntax_errors() {
+    assert!(matches!(load_asset("{ not json"), Err(LoadError::Syntax(_))));
+    assert!(matches!(load_asset("[]"), Err(LoadError::Syntax(_))));
+    assert!(matches!(load_asset(""), Err(LoadError::Syntax(_))));
+}
+
+#[test]
+fn syntax_errors_have_a_location() {
+    assert_eq!(syntax_error_location("{\n  \"name\": \"Shape\",\n  oops\n}"), Some((3, 3)));
+    assert_eq!(syntax_error_location("[]"), None);
+    assert_eq!(syntax_error_location(&fixture("v1_minimal.json")), None);
+}
+
+#[test]
+fn unknown_asset_fields_survive_a_round_trip() {
+    let original: Value = serde_json::from_str(&fixture("v1_unknown_fields.json")).unwrap();
+    let asset = load_asset(&fixture("v1_unknown_fields.json")).unwrap();
+
+    // Stashed in the meta while the asset is open
+    assert_eq!(asset.meta[PRESERVED_FIELDS_KEY]["color"], json!("#ff8800"));
+    // Unknown meta keys are left where they are
+    assert_eq!(asset.meta["layout_tool"], json!({ "x": 12, "y": 40 }));
+
+    assert_eq!(saved(&fixture("v1_unknown_fields.json")), original);
+}
+
+#[test]
+fn unknown_method_fields_survive_a_round_trip() {
+    let asset = find_traits("pub trait Shape { fn area(&self) -> f64; fn name(&self) -> String; }")
+        .unwrap()
+        .remove(0)
+        .asset;
+    let mut document = serde_json::from_str::<Value>(&asset_to_json(&asset).unwrap()).unwrap();
+    document["methods"][1]["deprecated_since"] = json!("0.3");
+
+    let loaded = load_asset(&document.to_string()).unwrap();
+    assert_eq!(loaded.meta["methods"][1][PRESERVED_FIELDS_KEY], json!({ "deprecated_since": "0.3" }));
+
+    let saved: Value = serde_json::from_str(&asset_to_json(&loaded).unwrap()).unwrap();
+    assert_eq!(saved["methods"][1]["deprecated_since"], json!("0.3"));
+    assert!(saved["methods"][0].get("deprecated_since").is_none());
+    assert_eq!(saved["meta"], document["meta"]);
+}
+
+#[test]
+fn preserved_fields_follow_their_method() {
+    let asset = find_traits("pub trait Shape { fn area(&self) -> f64; fn name(&self) -> String; }")
+        .unwrap()
+        .remove(0)
+        .asset;
+    let mut document = serde_json::from_str::<Value>(&asset_to_json(&asset).unwrap()).unwrap();
+    document["methods"][0]["owner"] = json!("geometry");
+
+    // Removing the first method removes its meta entry along with it
+    let mut loaded = load_asset(&document.to_string()).unwrap();
+    loaded.methods.remove(0);
+    if let Value::Array(entries) = &mut loaded.meta["methods"] {
+        entries.remove(0);
+    }
+
+    let saved: Value = serde_json::from_str(&asset_to_json(&loaded).unwrap()).unwrap();
+    assert_eq!(saved["methods"].as_array().unwrap().len(), 1);
+    assert!(saved["methods"][0].get("owner").is_none());
+}
+
+#[test]
+fn modelled_fields_win_over_preserved_copies() {
+    let mut asset = load_asset(&fixture("v1_unknown_fields.json")).unwrap();
+    asset.meta[PRESERVED_FIELDS_KEY]["name"] = json!("Stale");
+
+    let saved: Value = serde_json::from_str(&asset_to_json(&asset).unwrap()).unwrap();
+    assert_eq!(saved["name"], json!("Shape"));
+}